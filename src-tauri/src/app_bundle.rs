@@ -0,0 +1,694 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// macOS 应用包（.app）支持，纯 Rust 实现，不依赖 macOS 系统接口：
+//   Contents/Info.plist：读取 CFBundleDisplayName / CFBundleName / CFBundleIconFile，支持 XML 和二进制格式
+//   Contents/Resources/*.icns：解析图标容器，支持 PNG 和传统的 RLE / ARGB 位图
+// JPEG 2000 格式的图标条目无法解码，会跳过并改用其他尺寸
+
+// 应用包中读取到的信息
+#[derive(Debug, Clone, Default)]
+pub struct BundleInfo {
+    pub display_name: Option<String>,
+    pub icon_file: Option<PathBuf>,
+}
+
+pub fn is_app_bundle(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("app"))
+        && path.is_dir()
+}
+
+pub fn read_bundle_info(path: &Path) -> Result<BundleInfo, String> {
+    let contents = path.join("Contents");
+    let data = fs::read(contents.join("Info.plist"))
+        .map_err(|e| format!("Failed to read Info.plist: {}", e))?;
+    let values = parse_plist_strings(&data)?;
+
+    let display_name = ["CFBundleDisplayName", "CFBundleName"].iter()
+        .filter_map(|key| values.get(*key))
+        .map(|name| name.trim())
+        .find(|name| !name.is_empty())
+        .map(|name| name.to_string());
+
+    // CFBundleIconFile 可以省略 .icns 扩展名；没有声明时尝试 Xcode 的默认名称
+    let resources = contents.join("Resources");
+    let icon_name = values.get("CFBundleIconFile")
+        .map(|name| name.trim())
+        .filter(|name| !name.is_empty())
+        .unwrap_or("AppIcon");
+    let icon_file = [resources.join(icon_name), resources.join(format!("{}.icns", icon_name))]
+        .into_iter()
+        .find(|candidate| candidate.is_file());
+
+    Ok(BundleInfo { display_name, icon_file })
+}
+
+// 应用包的显示名称，读取失败时返回 None，由调用方退回文件名
+pub fn display_name(path: &Path) -> Option<String> {
+    if !is_app_bundle(path) {
+        return None;
+    }
+    read_bundle_info(path).ok()?.display_name
+}
+
+// 提取应用包的图标，返回 PNG 数据
+pub fn extract_bundle_icon(path: &Path, size: u32) -> Result<Vec<u8>, String> {
+    let info = read_bundle_info(path)?;
+    let icon_file = info.icon_file.ok_or("App bundle has no icon file")?;
+    extract_icns_file(&icon_file, size)
+}
+
+pub fn extract_icns_file(path: &Path, size: u32) -> Result<Vec<u8>, String> {
+    let data = fs::read(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    decode_icns(&data, size)
+}
+
+// ---------- Info.plist ----------
+
+// 读取属性列表顶层字典中的字符串值，其他类型的值忽略
+pub fn parse_plist_strings(data: &[u8]) -> Result<HashMap<String, String>, String> {
+    if data.starts_with(b"bplist00") {
+        parse_binary_plist(data)
+    } else {
+        parse_xml_plist(&String::from_utf8_lossy(data))
+    }
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(amp) = rest.find('&') {
+        decoded.push_str(&rest[..amp]);
+        let tail = &rest[amp..];
+        let Some(semi) = tail.find(';') else { break };
+
+        let entity = &tail[1..semi];
+        let replacement = match entity {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity.strip_prefix("#x").or_else(|| entity.strip_prefix("#X"))
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32),
+        };
+
+        match replacement {
+            Some(c) => {
+                decoded.push(c);
+                rest = &tail[semi + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &tail[1..];
+            }
+        }
+    }
+
+    decoded.push_str(rest);
+    decoded
+}
+
+fn parse_xml_plist(text: &str) -> Result<HashMap<String, String>, String> {
+    let mut values = HashMap::new();
+    let mut depth = 0usize;  // dict / array 的嵌套层数，顶层字典为 1
+    let mut pending_key: Option<String> = None;
+    let mut rest = text;
+
+    while let Some(open) = rest.find('<') {
+        let after = &rest[open + 1..];
+
+        if let Some(comment) = after.strip_prefix("!--") {
+            let end = comment.find("-->").ok_or("Unterminated comment in plist")?;
+            rest = &comment[end + 3..];
+            continue;
+        }
+
+        let close = after.find('>').ok_or("Unterminated tag in plist")?;
+        let tag = &after[..close];
+        rest = &after[close + 1..];
+
+        if tag.starts_with('?') || tag.starts_with('!') {
+            continue;
+        }
+
+        let is_closing = tag.starts_with('/');
+        let self_closing = tag.ends_with('/');
+        let name = tag.trim_matches('/').split_whitespace().next().unwrap_or("");
+
+        if is_closing {
+            if name == "dict" || name == "array" {
+                depth = depth.saturating_sub(1);
+            }
+            continue;
+        }
+
+        match name {
+            "plist" => {}
+            "dict" | "array" => {
+                // 嵌套的字典或数组作为某个键的值，该键不是字符串，丢弃
+                if depth == 1 {
+                    pending_key = None;
+                }
+                if !self_closing {
+                    depth += 1;
+                }
+            }
+            "key" | "string" => {
+                let content = if self_closing {
+                    String::new()
+                } else {
+                    let end = rest.find('<').ok_or("Unterminated element in plist")?;
+                    let content = decode_entities(&rest[..end]);
+                    rest = &rest[end..];
+                    content
+                };
+
+                if depth == 1 {
+                    if name == "key" {
+                        pending_key = Some(content);
+                    } else if let Some(key) = pending_key.take() {
+                        values.insert(key, content);
+                    }
+                }
+            }
+            _ => {
+                if depth == 1 {
+                    pending_key = None;
+                }
+            }
+        }
+    }
+
+    Ok(values)
+}
+
+fn read_be(data: &[u8], offset: usize, width: usize) -> Result<usize, String> {
+    if width == 0 || width > 8 {
+        return Err(format!("Invalid integer width {} in plist", width));
+    }
+    let bytes = offset.checked_add(width)
+        .and_then(|end| data.get(offset..end))
+        .ok_or_else(|| format!("Unexpected end of plist at offset {}", offset))?;
+    Ok(bytes.iter().fold(0usize, |value, byte| (value << 8) | *byte as usize))
+}
+
+// 二进制属性列表（bplist00）：对象表 + 偏移表 + 32 字节尾部；
+// 尾部和对象中的数值都可能是任意值，偏移和长度的计算一律检查溢出
+fn parse_binary_plist(data: &[u8]) -> Result<HashMap<String, String>, String> {
+    if data.len() < 40 {
+        return Err("Binary plist is too short".to_string());
+    }
+
+    let trailer = data.len() - 32;
+    let offset_size = data[trailer + 6] as usize;
+    let ref_size = data[trailer + 7] as usize;
+    let object_count = read_be(data, trailer + 8, 8)?;
+    let top_object = read_be(data, trailer + 16, 8)?;
+    let offset_table = read_be(data, trailer + 24, 8)?;
+
+    let object_offset = |index: usize| -> Result<usize, String> {
+        if index >= object_count {
+            return Err(format!("Object reference {} is out of range", index));
+        }
+        let entry = index.checked_mul(offset_size)
+            .and_then(|relative| offset_table.checked_add(relative))
+            .ok_or("Offset table is out of range")?;
+        read_be(data, entry, offset_size)
+    };
+
+    // 对象头的低 4 位是长度，为 0xF 时后面紧跟一个整数对象表示长度
+    let object_length = |offset: usize| -> Result<(usize, usize), String> {
+        let marker = *data.get(offset).ok_or("Unexpected end of plist")?;
+        if marker & 0x0F != 0x0F {
+            return Ok(((marker & 0x0F) as usize, offset + 1));
+        }
+        let int_marker = *data.get(offset + 1).ok_or("Unexpected end of plist")?;
+        if int_marker >> 4 != 0x1 {
+            return Err("Invalid length in plist object".to_string());
+        }
+        let width = 1usize << (int_marker & 0x0F);
+        let start = (offset + 2).checked_add(width).ok_or("Unexpected end of plist")?;
+        Ok((read_be(data, offset + 2, width)?, start))
+    };
+
+    let read_string = |index: usize| -> Result<Option<String>, String> {
+        let offset = object_offset(index)?;
+        let marker = *data.get(offset).ok_or("Unexpected end of plist")?;
+        let (length, start) = object_length(offset)?;
+
+        let slice = |bytes: Option<usize>| bytes
+            .and_then(|bytes| start.checked_add(bytes))
+            .and_then(|end| data.get(start..end))
+            .ok_or("String is out of bounds");
+
+        match marker >> 4 {
+            0x5 => {
+                let bytes = slice(Some(length))?;
+                Ok(Some(String::from_utf8_lossy(bytes).to_string()))
+            }
+            0x6 => {
+                let bytes = slice(length.checked_mul(2))?;
+                let units: Vec<u16> = bytes.chunks_exact(2)
+                    .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+                    .collect();
+                Ok(Some(String::from_utf16_lossy(&units)))
+            }
+            _ => Ok(None),
+        }
+    };
+
+    let root = object_offset(top_object)?;
+    if data.get(root).map(|marker| marker >> 4) != Some(0xD) {
+        return Err("Binary plist root is not a dictionary".to_string());
+    }
+
+    let (count, start) = object_length(root)?;
+    let mut values = HashMap::new();
+    // 键引用在前，值引用紧随其后
+    let reference = |slot: usize| -> Result<usize, String> {
+        let offset = slot.checked_mul(ref_size)
+            .and_then(|relative| start.checked_add(relative))
+            .ok_or("Dictionary is out of range")?;
+        read_be(data, offset, ref_size)
+    };
+    for i in 0..count {
+        let key_ref = reference(i)?;
+        let value_ref = reference(count.checked_add(i).ok_or("Dictionary is out of range")?)?;
+        if let (Some(key), Some(value)) = (read_string(key_ref)?, read_string(value_ref)?) {
+            values.insert(key, value);
+        }
+    }
+
+    Ok(values)
+}
+
+// ---------- .icns ----------
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum IcnsKind {
+    Encoded,       // PNG 或 JPEG 2000
+    Argb,          // "ARGB" + 四通道 RLE
+    Rgb(&'static [u8; 4]),  // 三通道 RLE，参数为对应的透明遮罩类型
+}
+
+// 条目类型 -> (像素尺寸, 编码方式)
+fn icns_entry_kind(kind: &[u8]) -> Option<(u32, IcnsKind)> {
+    let entry = match kind {
+        b"icp4" => (16, IcnsKind::Encoded),
+        b"icp5" | b"ic11" => (32, IcnsKind::Encoded),
+        b"icp6" | b"ic12" => (64, IcnsKind::Encoded),
+        b"ic07" => (128, IcnsKind::Encoded),
+        b"ic08" | b"ic13" => (256, IcnsKind::Encoded),
+        b"ic09" | b"ic14" => (512, IcnsKind::Encoded),
+        b"ic10" => (1024, IcnsKind::Encoded),
+        b"ic04" => (16, IcnsKind::Argb),
+        b"ic05" => (32, IcnsKind::Argb),
+        b"is32" => (16, IcnsKind::Rgb(b"s8mk")),
+        b"il32" => (32, IcnsKind::Rgb(b"l8mk")),
+        b"ih32" => (48, IcnsKind::Rgb(b"h8mk")),
+        b"it32" => (128, IcnsKind::Rgb(b"t8mk")),
+        _ => return None,
+    };
+    Some(entry)
+}
+
+// 图标 RLE：控制字节小于 0x80 时后跟 n+1 个原样字节，否则下一个字节重复 n-125 次
+fn unpack_rle(data: &[u8], length: usize) -> Result<Vec<u8>, String> {
+    let mut output = Vec::with_capacity(length);
+    let mut position = 0;
+
+    while output.len() < length {
+        let control = *data.get(position).ok_or("Truncated icns bitmap")? as usize;
+        position += 1;
+
+        if control < 0x80 {
+            let run = data.get(position..position + control + 1).ok_or("Truncated icns bitmap")?;
+            output.extend_from_slice(run);
+            position += control + 1;
+        } else {
+            let value = *data.get(position).ok_or("Truncated icns bitmap")?;
+            output.extend(std::iter::repeat_n(value, control - 125));
+            position += 1;
+        }
+    }
+
+    output.truncate(length);
+    Ok(output)
+}
+
+fn decode_icns_entry(
+    entries: &HashMap<[u8; 4], &[u8]>,
+    data: &[u8],
+    pixel_size: u32,
+    kind: IcnsKind,
+) -> Result<image::DynamicImage, String> {
+    let pixels = (pixel_size * pixel_size) as usize;
+
+    let planes = match kind {
+        IcnsKind::Encoded => {
+            if data.starts_with(b"\x89PNG") {
+                return image::load_from_memory_with_format(data, image::ImageFormat::Png)
+                    .map_err(|e| format!("Failed to decode icns PNG: {}", e));
+            }
+            return Err("JPEG 2000 icns entries are not supported".to_string());
+        }
+        IcnsKind::Argb => {
+            let body = data.strip_prefix(b"ARGB").ok_or("Invalid ARGB icns entry")?;
+            let planes = unpack_rle(body, pixels * 4)?;
+            let (alpha, rgb) = planes.split_at(pixels);
+            [rgb[..pixels].to_vec(), rgb[pixels..pixels * 2].to_vec(), rgb[pixels * 2..].to_vec(), alpha.to_vec()]
+        }
+        IcnsKind::Rgb(mask_kind) => {
+            // it32 的数据前有 4 个字节的 0
+            let body = if data.len() >= 4 && data[..4] == [0, 0, 0, 0] { &data[4..] } else { data };
+            let rgb = if body.len() == pixels * 4 {
+                // 未压缩的数据为逐像素的 ARGB，A 字节无意义
+                let mut planes = vec![0u8; pixels * 3];
+                for (i, pixel) in body.chunks_exact(4).enumerate() {
+                    planes[i] = pixel[1];
+                    planes[pixels + i] = pixel[2];
+                    planes[pixels * 2 + i] = pixel[3];
+                }
+                planes
+            } else {
+                unpack_rle(body, pixels * 3)?
+            };
+            let alpha = entries.get(mask_kind)
+                .filter(|mask| mask.len() >= pixels)
+                .map(|mask| mask[..pixels].to_vec())
+                .unwrap_or_else(|| vec![255; pixels]);
+            [rgb[..pixels].to_vec(), rgb[pixels..pixels * 2].to_vec(), rgb[pixels * 2..].to_vec(), alpha]
+        }
+    };
+
+    let [red, green, blue, alpha] = &planes;
+    let rgba: Vec<u8> = (0..pixels)
+        .flat_map(|i| [red[i], green[i], blue[i], alpha[i]])
+        .collect();
+
+    image::RgbaImage::from_raw(pixel_size, pixel_size, rgba)
+        .map(image::DynamicImage::ImageRgba8)
+        .ok_or_else(|| "Invalid icns bitmap size".to_string())
+}
+
+// 从 .icns 数据中选出最合适的尺寸并转换为 PNG；无法解码的条目依次退回下一个候选
+pub fn decode_icns(data: &[u8], size: u32) -> Result<Vec<u8>, String> {
+    if data.get(0..4) != Some(b"icns") {
+        return Err("Not an icns file".to_string());
+    }
+
+    let header = data.get(4..8).ok_or("Truncated icns header")?;
+    let total = (u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize).min(data.len());
+    let mut entries: HashMap<[u8; 4], &[u8]> = HashMap::new();
+    let mut position = 8;
+
+    while position + 8 <= total {
+        let kind = [data[position], data[position + 1], data[position + 2], data[position + 3]];
+        let length = u32::from_be_bytes([data[position + 4], data[position + 5], data[position + 6], data[position + 7]]) as usize;
+        let Some(end) = position.checked_add(length).filter(|end| length >= 8 && *end <= total) else {
+            break;
+        };
+        entries.insert(kind, &data[position + 8..end]);
+        position = end;
+    }
+
+    // 优先不小于目标尺寸的最小图标，其次比目标小的最大图标；同尺寸时 PNG 优先
+    let mut candidates: Vec<(u32, IcnsKind, &[u8])> = entries.iter()
+        .filter_map(|(kind, body)| icns_entry_kind(kind).map(|(pixel_size, k)| (pixel_size, k, *body)))
+        .collect();
+    candidates.sort_by_key(|(pixel_size, kind, _)| {
+        let rank = if *pixel_size >= size { (0, *pixel_size as i64) } else { (1, -(*pixel_size as i64)) };
+        (rank, *kind != IcnsKind::Encoded)
+    });
+
+    let mut last_error = "Icns file contains no supported icons".to_string();
+    for (pixel_size, kind, body) in candidates {
+        match decode_icns_entry(&entries, body, pixel_size, kind) {
+            Ok(image) => return crate::icon_extractor::encode_png(image, size),
+            Err(e) => last_error = e,
+        }
+    }
+
+    Err(last_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XML_PLIST: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <!-- <key>Ignored</key> -->
+    <key>CFBundleName</key>
+    <string>Tom &amp; Jerry &#x4E2D;</string>
+    <key>CFBundleDocumentTypes</key>
+    <array>
+        <dict>
+            <key>CFBundleTypeName</key>
+            <string>Nested</string>
+        </dict>
+    </array>
+    <key>LSRequiresNativeExecution</key>
+    <true/>
+    <key>CFBundleIconFile</key>
+    <string>AppIcon</string>
+    <key>Empty</key>
+    <string/>
+</dict>
+</plist>"#;
+
+    #[test]
+    fn reads_top_level_strings_from_xml_plist() {
+        let values = parse_plist_strings(XML_PLIST.as_bytes()).unwrap();
+        assert_eq!(values.get("CFBundleName").map(String::as_str), Some("Tom & Jerry 中"));
+        assert_eq!(values.get("CFBundleIconFile").map(String::as_str), Some("AppIcon"));
+        assert_eq!(values.get("Empty").map(String::as_str), Some(""));
+        assert!(!values.contains_key("CFBundleTypeName"));
+        assert!(!values.contains_key("LSRequiresNativeExecution"));
+        assert!(!values.contains_key("Ignored"));
+    }
+
+    #[test]
+    fn rejects_unterminated_xml_plist() {
+        assert!(parse_plist_strings(b"<plist><dict><key>Name").is_err());
+        assert!(parse_plist_strings(b"<plist><dict><!-- comment").is_err());
+    }
+
+    fn ascii_object(text: &str) -> Vec<u8> {
+        let mut object = if text.len() < 15 {
+            vec![0x50 | text.len() as u8]
+        } else {
+            vec![0x5F, 0x10, text.len() as u8]
+        };
+        object.extend_from_slice(text.as_bytes());
+        object
+    }
+
+    fn utf16_object(text: &str) -> Vec<u8> {
+        let units: Vec<u16> = text.encode_utf16().collect();
+        let mut object = vec![0x60 | units.len() as u8];
+        object.extend(units.iter().flat_map(|unit| unit.to_be_bytes()));
+        object
+    }
+
+    // 顶层字典 + 键值字符串对象，引用和偏移都用 1 个字节
+    fn binary_plist(pairs: &[(&str, Vec<u8>)]) -> Vec<u8> {
+        let count = pairs.len();
+        let mut dict = vec![0xD0 | count as u8];
+        dict.extend((0..count).map(|i| 1 + i as u8));
+        dict.extend((0..count).map(|i| 1 + count as u8 + i as u8));
+
+        let mut objects = vec![dict];
+        objects.extend(pairs.iter().map(|(key, _)| ascii_object(key)));
+        objects.extend(pairs.iter().map(|(_, value)| value.clone()));
+
+        let mut data = b"bplist00".to_vec();
+        let mut offsets = Vec::new();
+        for object in &objects {
+            offsets.push(data.len() as u8);
+            data.extend_from_slice(object);
+        }
+        let offset_table = data.len() as u64;
+        data.extend_from_slice(&offsets);
+
+        data.extend_from_slice(&[0; 6]);
+        data.extend_from_slice(&[1, 1]);
+        data.extend_from_slice(&(objects.len() as u64).to_be_bytes());
+        data.extend_from_slice(&0u64.to_be_bytes());
+        data.extend_from_slice(&offset_table.to_be_bytes());
+        data
+    }
+
+    fn sample_binary_plist() -> Vec<u8> {
+        binary_plist(&[
+            ("CFBundleName", ascii_object("Preview")),
+            ("CFBundleDisplayName", utf16_object("预览")),
+            ("CFBundleIconFile", ascii_object("AppIcon.icns")),
+            ("LSMinimumSystemVersion", vec![0x10, 0x0B]),  // 整数，忽略
+        ])
+    }
+
+    #[test]
+    fn reads_strings_from_binary_plist() {
+        let values = parse_plist_strings(&sample_binary_plist()).unwrap();
+        assert_eq!(values.get("CFBundleName").map(String::as_str), Some("Preview"));
+        assert_eq!(values.get("CFBundleDisplayName").map(String::as_str), Some("预览"));
+        assert_eq!(values.get("CFBundleIconFile").map(String::as_str), Some("AppIcon.icns"));
+        assert!(!values.contains_key("LSMinimumSystemVersion"));
+    }
+
+    #[test]
+    fn rejects_malformed_binary_plist() {
+        assert_eq!(parse_plist_strings(b"bplist00").unwrap_err(), "Binary plist is too short");
+
+        let plist = sample_binary_plist();
+        let trailer = plist.len() - 32;
+
+        let mut root_not_dict = plist.clone();
+        root_not_dict[8] = 0xA0;
+        assert_eq!(parse_plist_strings(&root_not_dict).unwrap_err(), "Binary plist root is not a dictionary");
+
+        let mut bad_top = plist.clone();
+        bad_top[trailer + 16..trailer + 24].copy_from_slice(&99u64.to_be_bytes());
+        assert_eq!(parse_plist_strings(&bad_top).unwrap_err(), "Object reference 99 is out of range");
+
+        let mut huge_offset_table = plist.clone();
+        huge_offset_table[trailer + 24..trailer + 32].copy_from_slice(&u64::MAX.to_be_bytes());
+        assert!(parse_plist_strings(&huge_offset_table).is_err());
+
+        let mut bad_width = plist.clone();
+        bad_width[trailer + 6] = 0;
+        assert_eq!(parse_plist_strings(&bad_width).unwrap_err(), "Invalid integer width 0 in plist");
+
+        // 字符串长度远超文件大小，包括乘以 2 后溢出的 UTF-16 长度
+        let long_string = binary_plist(&[("Name", vec![0x6F, 0x13, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF])]);
+        assert_eq!(parse_plist_strings(&long_string).unwrap_err(), "String is out of bounds");
+    }
+
+    #[test]
+    fn corrupted_binary_plist_does_not_panic() {
+        let plist = sample_binary_plist();
+        for offset in 8..plist.len() {
+            for value in [0x00, 0x0F, 0x7F, 0x80, 0xFF] {
+                let mut corrupted = plist.clone();
+                corrupted[offset] = value;
+                let _ = parse_plist_strings(&corrupted);
+            }
+        }
+        for len in 0..plist.len() {
+            let _ = parse_plist_strings(&plist[..len]);
+        }
+    }
+
+    fn png_bytes(side: u32, color: [u8; 4]) -> Vec<u8> {
+        let image = image::DynamicImage::ImageRgba8(image::RgbaImage::from_pixel(side, side, image::Rgba(color)));
+        let mut data = Vec::new();
+        image.write_to(&mut std::io::Cursor::new(&mut data), image::ImageFormat::Png).unwrap();
+        data
+    }
+
+    fn icns(entries: &[(&[u8; 4], Vec<u8>)]) -> Vec<u8> {
+        let mut body = Vec::new();
+        for (kind, data) in entries {
+            body.extend_from_slice(*kind);
+            body.extend_from_slice(&(data.len() as u32 + 8).to_be_bytes());
+            body.extend_from_slice(data);
+        }
+        let mut data = b"icns".to_vec();
+        data.extend_from_slice(&(body.len() as u32 + 8).to_be_bytes());
+        data.extend_from_slice(&body);
+        data
+    }
+
+    // 16x16 的单色 RLE 位图：每个通道 256 字节，拆成 130 + 126 两段重复
+    fn rle_channel(value: u8) -> Vec<u8> {
+        vec![0xFF, value, 0xFB, value]
+    }
+
+    fn decoded_pixel(png: &[u8]) -> (u32, [u8; 4]) {
+        let image = image::load_from_memory_with_format(png, image::ImageFormat::Png).unwrap().to_rgba8();
+        (image.width(), image.get_pixel(0, 0).0)
+    }
+
+    #[test]
+    fn decodes_png_icns_entry() {
+        let data = icns(&[(b"icp4", png_bytes(16, [10, 20, 30, 255])), (b"ic07", png_bytes(128, [40, 50, 60, 255]))]);
+        assert_eq!(decoded_pixel(&decode_icns(&data, 16).unwrap()), (16, [10, 20, 30, 255]));
+        // 没有足够大的图标时从 128 缩小
+        assert_eq!(decoded_pixel(&decode_icns(&data, 64).unwrap()), (64, [40, 50, 60, 255]));
+    }
+
+    #[test]
+    fn decodes_rle_icns_entry_with_mask() {
+        let rgb = [rle_channel(200), rle_channel(100), rle_channel(50)].concat();
+        let data = icns(&[(b"is32", rgb), (b"s8mk", vec![128; 256])]);
+        assert_eq!(decoded_pixel(&decode_icns(&data, 16).unwrap()), (16, [200, 100, 50, 128]));
+
+        // 没有遮罩时完全不透明；it32 的数据前有 4 个字节的 0
+        let channels: Vec<u8> = (0..3).flat_map(|_| {
+            // 128x128 每个通道 16384 字节 = 126 段 130 字节 + 1 段 4 字节
+            let mut channel: Vec<u8> = (0..126).flat_map(|_| [0xFF, 7]).collect();
+            channel.extend_from_slice(&[0x81, 7]);
+            channel
+        }).collect();
+        let data = icns(&[(b"it32", [vec![0, 0, 0, 0], channels].concat())]);
+        assert_eq!(decoded_pixel(&decode_icns(&data, 128).unwrap()), (128, [7, 7, 7, 255]));
+    }
+
+    #[test]
+    fn decodes_argb_icns_entry() {
+        let body = [b"ARGB".to_vec(), rle_channel(255), rle_channel(1), rle_channel(2), rle_channel(3)].concat();
+        let data = icns(&[(b"ic04", body)]);
+        assert_eq!(decoded_pixel(&decode_icns(&data, 16).unwrap()), (16, [1, 2, 3, 255]));
+    }
+
+    #[test]
+    fn falls_back_when_an_entry_cannot_be_decoded() {
+        // JPEG 2000 条目无法解码，退回更小的 PNG
+        let data = icns(&[(b"ic08", b"\x00\x00\x00\x0CjP  ".to_vec()), (b"ic07", png_bytes(128, [1, 1, 1, 255]))]);
+        assert_eq!(decoded_pixel(&decode_icns(&data, 256).unwrap()), (256, [1, 1, 1, 255]));
+
+        let only_jpeg = icns(&[(b"ic08", b"\x00\x00\x00\x0CjP  ".to_vec())]);
+        assert_eq!(decode_icns(&only_jpeg, 256).unwrap_err(), "JPEG 2000 icns entries are not supported");
+    }
+
+    #[test]
+    fn rejects_malformed_icns() {
+        assert_eq!(decode_icns(b"abcd", 16).unwrap_err(), "Not an icns file");
+        assert_eq!(decode_icns(b"icns\x00", 16).unwrap_err(), "Truncated icns header");
+        assert_eq!(decode_icns(&icns(&[]), 16).unwrap_err(), "Icns file contains no supported icons");
+        assert_eq!(decode_icns(&icns(&[(b"is32", vec![0x05, 1])]), 16).unwrap_err(), "Truncated icns bitmap");
+        assert_eq!(decode_icns(&icns(&[(b"ic04", rle_channel(1))]), 16).unwrap_err(), "Invalid ARGB icns entry");
+
+        // 条目长度超出文件时停止读取
+        let mut overlong = icns(&[(b"icp4", png_bytes(16, [0, 0, 0, 255]))]);
+        overlong[12..16].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert_eq!(decode_icns(&overlong, 16).unwrap_err(), "Icns file contains no supported icons");
+    }
+
+    #[test]
+    fn corrupted_icns_does_not_panic() {
+        let rgb = [rle_channel(200), rle_channel(100), rle_channel(50)].concat();
+        let data = icns(&[(b"is32", rgb), (b"s8mk", vec![255; 256])]);
+        for offset in 0..data.len().min(64) {
+            for value in [0x00, 0x7F, 0x80, 0xFF] {
+                let mut corrupted = data.clone();
+                corrupted[offset] = value;
+                let _ = decode_icns(&corrupted, 16);
+            }
+        }
+        for len in 0..data.len() {
+            let _ = decode_icns(&data[..len], 16);
+        }
+    }
+}
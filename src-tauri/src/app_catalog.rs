@@ -0,0 +1,616 @@
+use crate::models::{AppSearchResult, InstalledApp};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde::Serialize;
+
+// 目录扫描的最大深度，避免在异常的符号链接结构中无限递归
+const MAX_SCAN_DEPTH: usize = 4;
+
+// 一个应用来源文件（.desktop / .lnk / .app）
+#[derive(Debug, Clone)]
+struct SourceFile {
+    path: PathBuf,
+    source: &'static str,
+    // 同一应用可能出现在多个目录中，按 key 去重，先出现的优先
+    dedup_key: String,
+}
+
+#[derive(Debug, Clone)]
+struct CatalogEntry {
+    modified: u64,
+    app: Option<InstalledApp>,
+}
+
+// 刷新统计
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CatalogRefreshStats {
+    pub total: usize,
+    pub added: usize,
+    pub updated: usize,
+    pub removed: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct AppCatalog {
+    entries: Arc<Mutex<HashMap<PathBuf, CatalogEntry>>>,
+    refreshed_at: Arc<Mutex<Option<u64>>>,
+    refresh_interval: u64, // 自动刷新间隔（秒）
+}
+
+impl AppCatalog {
+    pub fn new(refresh_interval: u64) -> Self {
+        Self {
+            entries: Arc::new(Mutex::new(HashMap::new())),
+            refreshed_at: Arc::new(Mutex::new(None)),
+            refresh_interval,
+        }
+    }
+
+    // 增量刷新：只重新解析修改时间发生变化的来源文件，并移除已删除的条目
+    pub fn refresh(&self) -> Result<CatalogRefreshStats, String> {
+        self.refresh_sources(discover_sources())
+    }
+
+    fn refresh_sources(&self, sources: Vec<SourceFile>) -> Result<CatalogRefreshStats, String> {
+        let mut stats = CatalogRefreshStats::default();
+
+        let mut entries = self.entries.lock()
+            .map_err(|e| format!("Failed to lock app catalog: {}", e))?;
+
+        let mut seen_keys: HashSet<String> = HashSet::new();
+        let mut live_paths: HashSet<PathBuf> = HashSet::new();
+
+        for source in sources {
+            if !seen_keys.insert(source.dedup_key.clone()) {
+                continue;
+            }
+
+            let modified = modified_secs(&source.path);
+            live_paths.insert(source.path.clone());
+
+            match entries.get(&source.path) {
+                Some(entry) if entry.modified == modified => continue,
+                Some(_) => stats.updated += 1,
+                None => stats.added += 1,
+            }
+
+            let app = parse_source(&source);
+            entries.insert(source.path.clone(), CatalogEntry { modified, app });
+        }
+
+        let before = entries.len();
+        entries.retain(|path, _| live_paths.contains(path));
+        stats.removed = before - entries.len();
+        stats.total = entries.values().filter(|e| e.app.is_some()).count();
+
+        if let Ok(mut refreshed_at) = self.refreshed_at.lock() {
+            *refreshed_at = Some(now_secs());
+        }
+
+        Ok(stats)
+    }
+
+    // 缓存为空或已过期时自动刷新
+    fn ensure_fresh(&self) -> Result<(), String> {
+        let stale = match self.refreshed_at.lock() {
+            Ok(refreshed_at) => refreshed_at.is_none_or(|t| now_secs().saturating_sub(t) > self.refresh_interval),
+            Err(_) => true,
+        };
+
+        if stale {
+            self.refresh()?;
+        }
+        Ok(())
+    }
+
+    // 获取全部已安装应用，按名称排序
+    pub fn list(&self) -> Result<Vec<InstalledApp>, String> {
+        self.ensure_fresh()?;
+
+        let entries = self.entries.lock()
+            .map_err(|e| format!("Failed to lock app catalog: {}", e))?;
+
+        let mut apps: Vec<InstalledApp> = entries.values()
+            .filter_map(|e| e.app.clone())
+            .collect();
+        apps.sort_by_cached_key(|a| crate::pinyin_match::collation_key(&a.name));
+
+        Ok(apps)
+    }
+
+    pub fn get(&self, id: &str) -> Result<Option<InstalledApp>, String> {
+        self.ensure_fresh()?;
+
+        let entries = self.entries.lock()
+            .map_err(|e| format!("Failed to lock app catalog: {}", e))?;
+
+        Ok(entries.values()
+            .filter_map(|e| e.app.as_ref())
+            .find(|a| a.id == id)
+            .cloned())
+    }
+
+    // 按名称（含拼音）和关键词搜索应用
+    pub fn search(&self, query: &str, limit: Option<usize>) -> Result<Vec<AppSearchResult>, String> {
+        let apps = self.list()?;
+        let query_lower = query.trim().to_lowercase();
+
+        if query_lower.is_empty() {
+            return Ok(apps.into_iter()
+                .take(limit.unwrap_or(usize::MAX))
+                .map(|app| AppSearchResult { app, score: 0.0, match_ranges: Vec::new() })
+                .collect());
+        }
+
+        let query_chars: Vec<char> = query_lower.chars().collect();
+        let mut matcher = crate::search::Matcher::default();
+
+        let mut results: Vec<AppSearchResult> = apps.into_iter()
+            .filter_map(|app| {
+                let name_match = matcher.score(&query_chars, &app.name)
+                    .map(|score| (score, matcher.positions().to_vec()));
+                let pinyin_match = crate::pinyin_match::match_pinyin(&query_chars, &app.name)
+                    .map(|m| (m.score, m.positions));
+
+                let best = match (name_match, pinyin_match) {
+                    (Some(a), Some(b)) => Some(if b.0 > a.0 { b } else { a }),
+                    (a, b) => a.or(b),
+                };
+
+                match best {
+                    Some((score, positions)) => Some(AppSearchResult {
+                        app,
+                        score: score as f64,
+                        match_ranges: crate::search::positions_to_ranges(&positions),
+                    }),
+                    None => {
+                        // 名称未命中时再看关键词，不提供高亮区间
+                        let keyword_score = app.keywords.iter()
+                            .filter_map(|k| matcher.score(&query_chars, k))
+                            .max()?;
+                        Some(AppSearchResult { app, score: keyword_score as f64 * 0.8, match_ranges: Vec::new() })
+                    }
+                }
+            })
+            .collect();
+
+        results.sort_by(|a, b| {
+            b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.app.name.cmp(&b.app.name))
+        });
+        results.truncate(limit.unwrap_or(usize::MAX));
+
+        Ok(results)
+    }
+}
+
+// 全局应用目录实例
+lazy_static::lazy_static! {
+    pub static ref GLOBAL_APP_CATALOG: AppCatalog = AppCatalog::new(300); // 5分钟后自动增量刷新
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn modified_secs(path: &Path) -> u64 {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+// 递归收集目录下指定扩展名的文件（或目录，用于 .app 包）
+fn collect_files(dir: &Path, extension: &str, depth: usize, out: &mut Vec<PathBuf>) {
+    let read_dir = match fs::read_dir(dir) {
+        Ok(read_dir) => read_dir,
+        Err(_) => return,
+    };
+
+    for entry in read_dir.flatten() {
+        let path = entry.path();
+        let matches = path.extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| e.eq_ignore_ascii_case(extension));
+
+        if matches {
+            out.push(path);
+        } else if depth < MAX_SCAN_DEPTH && path.is_dir() {
+            collect_files(&path, extension, depth + 1, out);
+        }
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(PathBuf::from)
+}
+
+// Linux：XDG 数据目录下的 applications，以及 Flatpak 和 Snap 导出的 .desktop 文件
+#[cfg(target_os = "linux")]
+fn discover_sources() -> Vec<SourceFile> {
+    let mut roots: Vec<(PathBuf, &'static str)> = Vec::new();
+
+    let data_home = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| home_dir().map(|h| h.join(".local/share")));
+    if let Some(data_home) = data_home {
+        roots.push((data_home.join("applications"), "desktop"));
+        roots.push((data_home.join("flatpak/exports/share/applications"), "flatpak"));
+    }
+
+    let data_dirs = std::env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+    for dir in data_dirs.split(':').filter(|d| !d.is_empty()) {
+        let source = if dir.contains("flatpak") {
+            "flatpak"
+        } else if dir.contains("snapd") {
+            "snap"
+        } else {
+            "desktop"
+        };
+        roots.push((PathBuf::from(dir).join("applications"), source));
+    }
+
+    roots.push((PathBuf::from("/var/lib/flatpak/exports/share/applications"), "flatpak"));
+    roots.push((PathBuf::from("/var/lib/snapd/desktop/applications"), "snap"));
+
+    desktop_sources(roots)
+}
+
+// 各 applications 目录中的 .desktop 文件，按目录的优先顺序排列
+#[cfg(target_os = "linux")]
+fn desktop_sources(roots: Vec<(PathBuf, &'static str)>) -> Vec<SourceFile> {
+    let mut sources = Vec::new();
+    for (root, source) in roots {
+        let mut files = Vec::new();
+        collect_files(&root, "desktop", 0, &mut files);
+
+        for path in files {
+            // desktop file id：相对 applications 目录的路径，分隔符替换为 '-'
+            let dedup_key = path.strip_prefix(&root)
+                .map(|p| p.to_string_lossy().replace(['/', '\\'], "-"))
+                .unwrap_or_else(|_| path.to_string_lossy().to_string());
+            sources.push(SourceFile { path, source, dedup_key });
+        }
+    }
+
+    sources
+}
+
+// Windows：开始菜单中的 .lnk 快捷方式
+#[cfg(target_os = "windows")]
+fn discover_sources() -> Vec<SourceFile> {
+    let mut roots: Vec<PathBuf> = Vec::new();
+    if let Some(app_data) = std::env::var_os("APPDATA") {
+        roots.push(PathBuf::from(app_data).join("Microsoft\\Windows\\Start Menu\\Programs"));
+    }
+    if let Some(program_data) = std::env::var_os("ProgramData") {
+        roots.push(PathBuf::from(program_data).join("Microsoft\\Windows\\Start Menu\\Programs"));
+    }
+
+    let mut sources = Vec::new();
+    for root in roots {
+        let mut files = Vec::new();
+        collect_files(&root, "lnk", 0, &mut files);
+
+        for path in files {
+            let dedup_key = path.strip_prefix(&root)
+                .map(|p| p.to_string_lossy().to_lowercase())
+                .unwrap_or_else(|_| path.to_string_lossy().to_lowercase());
+            sources.push(SourceFile { path, source: "start_menu", dedup_key });
+        }
+    }
+
+    sources
+}
+
+// macOS：应用程序目录中的 .app 包
+#[cfg(target_os = "macos")]
+fn discover_sources() -> Vec<SourceFile> {
+    let mut roots = vec![PathBuf::from("/Applications"), PathBuf::from("/System/Applications")];
+    if let Some(home) = home_dir() {
+        roots.push(home.join("Applications"));
+    }
+
+    let mut sources = Vec::new();
+    for root in roots {
+        let mut bundles = Vec::new();
+        collect_files(&root, "app", 0, &mut bundles);
+
+        for path in bundles {
+            let dedup_key = path.to_string_lossy().to_string();
+            sources.push(SourceFile { path, source: "app_bundle", dedup_key });
+        }
+    }
+
+    sources
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+fn discover_sources() -> Vec<SourceFile> {
+    Vec::new()
+}
+
+fn parse_source(source: &SourceFile) -> Option<InstalledApp> {
+    match source.source {
+        "desktop" | "flatpak" | "snap" => {
+            let content = fs::read_to_string(&source.path).ok()?;
+            parse_desktop_entry(&content, &source.path, source.source)
+        }
+        _ => {
+            let name = match crate::app_bundle::display_name(&source.path) {
+                Some(name) => name,
+                None => source.path.file_stem()?.to_string_lossy().to_string(),
+            };
+            let categories = source.path.parent()
+                .and_then(|p| p.file_name())
+                .map(|n| n.to_string_lossy().to_string())
+                .filter(|n| n != "Programs" && n != "Applications")
+                .into_iter()
+                .collect();
+            let path = source.path.to_string_lossy().to_string();
+
+            Some(InstalledApp {
+                id: path.clone(),
+                name,
+                exec: path.clone(),
+                icon: None,
+                categories,
+                keywords: Vec::new(),
+                source: source.source.to_string(),
+                source_path: path,
+            })
+        }
+    }
+}
+
+// 当前语言的候选后缀，例如 zh_CN.UTF-8 → ["zh_CN", "zh"]
+fn locale_candidates() -> Vec<String> {
+    let lang = std::env::var("LC_ALL")
+        .or_else(|_| std::env::var("LC_MESSAGES"))
+        .or_else(|_| std::env::var("LANG"))
+        .unwrap_or_default();
+    locale_candidates_for(&lang)
+}
+
+fn locale_candidates_for(lang: &str) -> Vec<String> {
+    let base = lang.split(['.', '@']).next().unwrap_or("").to_string();
+    let mut candidates = Vec::new();
+    if !base.is_empty() && base != "C" && base != "POSIX" {
+        if let Some((language, _)) = base.split_once('_') {
+            candidates.push(base.clone());
+            candidates.push(language.to_string());
+        } else {
+            candidates.push(base);
+        }
+    }
+    candidates
+}
+
+// 去掉 Exec 中的字段代码（%f、%U 等）
+fn strip_field_codes(exec: &str) -> String {
+    let mut result = String::with_capacity(exec.len());
+    let mut chars = exec.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '%' {
+            // %% 表示字面量 %，其他字段代码直接丢弃
+            if let Some('%') = chars.next() {
+                result.push('%');
+            }
+        } else {
+            result.push(c);
+        }
+    }
+
+    result.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn split_list(value: &str) -> Vec<String> {
+    value.split(';')
+        .map(|v| v.trim())
+        .filter(|v| !v.is_empty())
+        .map(|v| v.to_string())
+        .collect()
+}
+
+// TryExec 指向的程序不存在时视为应用未安装：绝对路径直接检查，否则在 PATH 中查找
+fn try_exec_available(program: &str) -> bool {
+    let program = Path::new(program.trim());
+    if program.is_absolute() {
+        return is_executable(program);
+    }
+    std::env::var_os("PATH")
+        .is_some_and(|paths| std::env::split_paths(&paths).any(|dir| is_executable(&dir.join(program))))
+}
+
+fn is_executable(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::metadata(path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+    }
+    #[cfg(not(unix))]
+    {
+        path.is_file()
+    }
+}
+
+// 解析 .desktop 文件的 [Desktop Entry] 部分
+pub fn parse_desktop_entry(content: &str, path: &Path, source: &str) -> Option<InstalledApp> {
+    parse_desktop_entry_for(content, path, source, &locale_candidates())
+}
+
+fn parse_desktop_entry_for(content: &str, path: &Path, source: &str, locales: &[String]) -> Option<InstalledApp> {
+    let mut values: HashMap<String, String> = HashMap::new();
+    let mut in_entry = false;
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with('[') {
+            in_entry = line == "[Desktop Entry]";
+            continue;
+        }
+        if !in_entry {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            values.entry(key.trim().to_string()).or_insert_with(|| value.trim().to_string());
+        }
+    }
+
+    if values.get("Type").map(|t| t.as_str()) != Some("Application") {
+        return None;
+    }
+    if values.get("NoDisplay").is_some_and(|v| v == "true") || values.get("Hidden").is_some_and(|v| v == "true") {
+        return None;
+    }
+    if values.get("TryExec").is_some_and(|program| !try_exec_available(program)) {
+        return None;
+    }
+
+    let localized = |key: &str| -> Option<String> {
+        locales.iter()
+            .find_map(|locale| values.get(&format!("{}[{}]", key, locale)).cloned())
+            .or_else(|| values.get(key).cloned())
+    };
+
+    let name = localized("Name")?;
+    let exec = strip_field_codes(values.get("Exec")?);
+    let path_string = path.to_string_lossy().to_string();
+
+    Some(InstalledApp {
+        id: path_string.clone(),
+        name,
+        exec,
+        icon: values.get("Icon").cloned().filter(|i| !i.is_empty()),
+        categories: values.get("Categories").map(|c| split_list(c)).unwrap_or_default(),
+        keywords: localized("Keywords").map(|k| split_list(&k)).unwrap_or_default(),
+        source: source.to_string(),
+        source_path: path_string,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(content: &str, locales: &[&str]) -> Option<InstalledApp> {
+        let locales: Vec<String> = locales.iter().map(|l| l.to_string()).collect();
+        parse_desktop_entry_for(content, Path::new("/usr/share/applications/app.desktop"), "desktop", &locales)
+    }
+
+    fn entry(extra: &str) -> String {
+        format!("[Desktop Entry]\nType=Application\nName=Files\nExec=files\n{}", extra)
+    }
+
+    #[test]
+    fn reads_locale_candidates() {
+        assert_eq!(locale_candidates_for("zh_CN.UTF-8"), vec!["zh_CN", "zh"]);
+        assert_eq!(locale_candidates_for("de_DE@euro"), vec!["de_DE", "de"]);
+        assert_eq!(locale_candidates_for("fr"), vec!["fr"]);
+        assert!(locale_candidates_for("C.UTF-8").is_empty());
+        assert!(locale_candidates_for("POSIX").is_empty());
+        assert!(locale_candidates_for("").is_empty());
+    }
+
+    #[test]
+    fn prefers_the_most_specific_localized_values() {
+        let content = entry("Name[zh]=文件\nName[zh_CN]=文件管理器\nKeywords=folder;browse;\nKeywords[zh]=文件夹;");
+        let app = parse(&content, &["zh_CN", "zh"]).unwrap();
+        assert_eq!(app.name, "文件管理器");
+        assert_eq!(app.keywords, vec!["文件夹"]);
+
+        let app = parse(&content, &["zh_TW", "zh"]).unwrap();
+        assert_eq!(app.name, "文件");
+
+        let app = parse(&content, &[]).unwrap();
+        assert_eq!(app.name, "Files");
+        assert_eq!(app.keywords, vec!["folder", "browse"]);
+    }
+
+    #[test]
+    fn reads_only_visible_applications_from_the_main_group() {
+        let app = parse(&entry("Icon=files\nCategories=Utility;System;\n[Desktop Action new]\nName=New Window\nExec=files --new"), &[]).unwrap();
+        assert_eq!(app.name, "Files");
+        assert_eq!(app.exec, "files");
+        assert_eq!(app.icon.as_deref(), Some("files"));
+        assert_eq!(app.categories, vec!["Utility", "System"]);
+
+        assert!(parse(&entry("NoDisplay=true"), &[]).is_none());
+        assert!(parse(&entry("Hidden=true"), &[]).is_none());
+        assert!(parse(&entry("NoDisplay=false"), &[]).is_some());
+        assert!(parse("[Desktop Entry]\nType=Link\nName=Site\nURL=https://example.com", &[]).is_none());
+        assert!(parse("[Desktop Entry]\nType=Application\nName=No Exec", &[]).is_none());
+        assert!(parse("[Other]\nType=Application\nName=Files\nExec=files", &[]).is_none());
+    }
+
+    #[test]
+    fn strips_field_codes_from_exec() {
+        assert_eq!(strip_field_codes("firefox %u"), "firefox");
+        assert_eq!(strip_field_codes("app --file=%f %F %i %c %k"), "app --file=");
+        assert_eq!(strip_field_codes("printf 100%%"), "printf 100%");
+        assert_eq!(strip_field_codes("  spaced   out  "), "spaced out");
+        assert_eq!(parse(&entry("").replace("Exec=files", "Exec=files %U"), &[]).unwrap().exec, "files");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn skips_entries_whose_try_exec_is_missing() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let program = dir.path().join("files-bin");
+        fs::write(&program, "#!/bin/sh\n").unwrap();
+        let not_executable = dir.path().join("readme");
+        fs::write(&not_executable, "").unwrap();
+        fs::set_permissions(&program, fs::Permissions::from_mode(0o755)).unwrap();
+
+        assert!(parse(&entry(&format!("TryExec={}", program.display())), &[]).is_some());
+        assert!(parse(&entry(&format!("TryExec={}", not_executable.display())), &[]).is_none());
+        assert!(parse(&entry(&format!("TryExec={}", dir.path().join("missing").display())), &[]).is_none());
+        assert!(parse(&entry("TryExec=surely-not-installed-program"), &[]).is_none());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn deduplicates_entries_by_desktop_file_id() {
+        let user = tempfile::tempdir().unwrap();
+        let system = tempfile::tempdir().unwrap();
+        let write = |root: &Path, relative: &str, name: &str| {
+            let path = root.join(relative);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, format!("[Desktop Entry]\nType=Application\nName={}\nExec=app", name)).unwrap();
+        };
+        write(user.path(), "files.desktop", "User Files");
+        write(system.path(), "files.desktop", "System Files");
+        write(system.path(), "kde/editor.desktop", "Editor");
+        write(system.path(), "kde-editor.desktop", "Shadowed Editor");
+
+        let sources = desktop_sources(vec![(user.path().to_path_buf(), "desktop"), (system.path().to_path_buf(), "desktop")]);
+        let mut keys: Vec<&str> = sources.iter().map(|s| s.dedup_key.as_str()).collect();
+        keys.sort();
+        assert_eq!(keys, vec!["files.desktop", "files.desktop", "kde-editor.desktop", "kde-editor.desktop"]);
+
+        let catalog = AppCatalog::new(300);
+        let stats = catalog.refresh_sources(sources).unwrap();
+        assert_eq!(stats.total, 2);
+        let names: Vec<String> = catalog.list().unwrap().into_iter().map(|a| a.name).collect();
+        assert!(names.contains(&"User Files".to_string()), "{:?}", names);
+        assert!(!names.contains(&"System Files".to_string()), "{:?}", names);
+    }
+}
@@ -0,0 +1,403 @@
+use std::f64::consts::{E, PI, TAU};
+
+// 计算器表达式：
+//   + - * / % ^（或 **），括号，一元正负号
+//   常量 pi、e、tau
+//   函数 sqrt abs ln log log2 exp sin cos tan asin acos atan floor ceil round min max pow
+// 同时接受 × ÷ 和全角括号，方便中文输入法下直接输入
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(String),
+    Op(char),
+    LParen,
+    RParen,
+    Comma,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            c if c.is_whitespace() => {
+                i += 1;
+            }
+            '0'..='9' | '.' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                // 科学计数法：1e3、2.5E-4
+                if i < chars.len() && matches!(chars[i], 'e' | 'E') {
+                    let mut j = i + 1;
+                    if j < chars.len() && matches!(chars[j], '+' | '-') {
+                        j += 1;
+                    }
+                    if j < chars.len() && chars[j].is_ascii_digit() {
+                        while j < chars.len() && chars[j].is_ascii_digit() {
+                            j += 1;
+                        }
+                        i = j;
+                    }
+                }
+                let text: String = chars[start..i].iter().collect();
+                let value = text.parse::<f64>()
+                    .map_err(|_| format!("Invalid number '{}'", text))?;
+                tokens.push(Token::Number(value));
+            }
+            c if c.is_ascii_alphabetic() => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                tokens.push(Token::Ident(chars[start..i].iter().collect::<String>().to_lowercase()));
+            }
+            '*' if chars.get(i + 1) == Some(&'*') => {
+                tokens.push(Token::Op('^'));
+                i += 2;
+            }
+            '+' | '-' | '*' | '/' | '%' | '^' => {
+                tokens.push(Token::Op(c));
+                i += 1;
+            }
+            '×' => {
+                tokens.push(Token::Op('*'));
+                i += 1;
+            }
+            '÷' => {
+                tokens.push(Token::Op('/'));
+                i += 1;
+            }
+            '(' | '（' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' | '）' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            ',' | '，' => {
+                tokens.push(Token::Comma);
+                i += 1;
+            }
+            _ => return Err(format!("Unexpected character '{}'", c)),
+        }
+    }
+
+    Ok(tokens)
+}
+
+// 括号、一元运算符和乘方的最大嵌套层数，避免深度递归导致栈溢出
+const MAX_DEPTH: usize = 64;
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), String> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            _ => Err(format!("Expected {:?}", expected)),
+        }
+    }
+
+    // expr := term (('+' | '-') term)*
+    fn expr(&mut self) -> Result<f64, String> {
+        let mut value = self.term()?;
+        while let Some(Token::Op(op @ ('+' | '-'))) = self.peek().cloned() {
+            self.pos += 1;
+            let rhs = self.term()?;
+            value = if op == '+' { value + rhs } else { value - rhs };
+        }
+        Ok(value)
+    }
+
+    // term := unary (('*' | '/' | '%') unary)*
+    fn term(&mut self) -> Result<f64, String> {
+        let mut value = self.unary()?;
+        while let Some(Token::Op(op @ ('*' | '/' | '%'))) = self.peek().cloned() {
+            self.pos += 1;
+            let rhs = self.unary()?;
+            value = match op {
+                '*' => value * rhs,
+                '/' => {
+                    if rhs == 0.0 {
+                        return Err("Division by zero".to_string());
+                    }
+                    value / rhs
+                }
+                _ => {
+                    if rhs == 0.0 {
+                        return Err("Division by zero".to_string());
+                    }
+                    value % rhs
+                }
+            };
+        }
+        Ok(value)
+    }
+
+    // 所有递归都经过 unary，在这里统计嵌套层数
+    fn unary(&mut self) -> Result<f64, String> {
+        if self.depth >= MAX_DEPTH {
+            return Err("Expression is nested too deeply".to_string());
+        }
+        self.depth += 1;
+        let value = self.signed();
+        self.depth -= 1;
+        value
+    }
+
+    // unary := ('+' | '-') unary | power，使 -2^2 = -4
+    fn signed(&mut self) -> Result<f64, String> {
+        match self.peek() {
+            Some(Token::Op('-')) => {
+                self.pos += 1;
+                Ok(-self.unary()?)
+            }
+            Some(Token::Op('+')) => {
+                self.pos += 1;
+                self.unary()
+            }
+            _ => self.power(),
+        }
+    }
+
+    // power := primary ('^' unary)?，右结合
+    fn power(&mut self) -> Result<f64, String> {
+        let base = self.primary()?;
+        if self.peek() == Some(&Token::Op('^')) {
+            self.pos += 1;
+            let exponent = self.unary()?;
+            return Ok(base.powf(exponent));
+        }
+        Ok(base)
+    }
+
+    fn primary(&mut self) -> Result<f64, String> {
+        match self.next() {
+            Some(Token::Number(value)) => Ok(value),
+            Some(Token::LParen) => {
+                let value = self.expr()?;
+                self.expect(Token::RParen)?;
+                Ok(value)
+            }
+            Some(Token::Ident(name)) => {
+                if self.peek() == Some(&Token::LParen) {
+                    self.pos += 1;
+                    let mut args = vec![self.expr()?];
+                    while self.peek() == Some(&Token::Comma) {
+                        self.pos += 1;
+                        args.push(self.expr()?);
+                    }
+                    self.expect(Token::RParen)?;
+                    call_function(&name, &args)
+                } else {
+                    constant(&name)
+                }
+            }
+            Some(token) => Err(format!("Unexpected {:?}", token)),
+            None => Err("Unexpected end of expression".to_string()),
+        }
+    }
+}
+
+fn constant(name: &str) -> Result<f64, String> {
+    match name {
+        "pi" => Ok(PI),
+        "e" => Ok(E),
+        "tau" => Ok(TAU),
+        _ => Err(format!("Unknown constant '{}'", name)),
+    }
+}
+
+fn call_function(name: &str, args: &[f64]) -> Result<f64, String> {
+    let unary = |f: fn(f64) -> f64| -> Result<f64, String> {
+        match args {
+            [x] => Ok(f(*x)),
+            _ => Err(format!("{}() takes 1 argument", name)),
+        }
+    };
+
+    match name {
+        "sqrt" => unary(f64::sqrt),
+        "abs" => unary(f64::abs),
+        "ln" => unary(f64::ln),
+        "log" => unary(f64::log10),
+        "log2" => unary(f64::log2),
+        "exp" => unary(f64::exp),
+        "sin" => unary(f64::sin),
+        "cos" => unary(f64::cos),
+        "tan" => unary(f64::tan),
+        "asin" => unary(f64::asin),
+        "acos" => unary(f64::acos),
+        "atan" => unary(f64::atan),
+        "floor" => unary(f64::floor),
+        "ceil" => unary(f64::ceil),
+        "round" => unary(f64::round),
+        "pow" => match args {
+            [base, exponent] => Ok(base.powf(*exponent)),
+            _ => Err("pow() takes 2 arguments".to_string()),
+        },
+        "min" | "max" => {
+            let fold = if name == "min" { f64::min } else { f64::max };
+            args.iter().copied().reduce(fold).ok_or_else(|| format!("{}() needs arguments", name))
+        }
+        _ => Err(format!("Unknown function '{}'", name)),
+    }
+}
+
+// 计算表达式的值
+pub fn evaluate(input: &str) -> Result<f64, String> {
+    let tokens = tokenize(input)?;
+    if tokens.is_empty() {
+        return Err("Empty expression".to_string());
+    }
+
+    let mut parser = Parser { tokens, pos: 0, depth: 0 };
+    let value = parser.expr()?;
+    if parser.pos < parser.tokens.len() {
+        return Err(format!("Unexpected {:?}", parser.tokens[parser.pos]));
+    }
+    if !value.is_finite() {
+        return Err("Result is not a finite number".to_string());
+    }
+
+    Ok(value)
+}
+
+// 判断输入是否像一个需要计算的表达式：以 = 开头，或者包含数字并带有运算符/函数调用；
+// 单独的数字不算，避免输入编号时总是出现计算结果
+pub fn looks_like_expression(input: &str) -> bool {
+    let trimmed = input.trim();
+    if trimmed.starts_with('=') {
+        return true;
+    }
+
+    let has_digit = trimmed.chars().any(|c| c.is_ascii_digit());
+    let has_operator = trimmed.chars().skip(1).any(|c| "+-*/%^×÷(（".contains(c));
+    has_digit && has_operator
+}
+
+// 格式化结果：整数不带小数点，其余最多保留 10 位小数并去掉末尾的 0
+pub fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        return format!("{}", value as i64);
+    }
+
+    if value.abs() >= 1e15 || value.abs() < 1e-6 {
+        return format!("{:e}", value);
+    }
+
+    let formatted = format!("{:.10}", value);
+    formatted.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(input: &str) -> f64 {
+        evaluate(input).unwrap_or_else(|e| panic!("{}: {}", input, e))
+    }
+
+    #[test]
+    fn follows_operator_precedence() {
+        assert_eq!(eval("1 + 2 * 3"), 7.0);
+        assert_eq!(eval("(1 + 2) * 3"), 9.0);
+        assert_eq!(eval("10 - 4 - 3"), 3.0);
+        assert_eq!(eval("24 / 4 / 2"), 3.0);
+        assert_eq!(eval("10 % 4 * 2"), 4.0);
+    }
+
+    #[test]
+    fn power_binds_tighter_than_unary_minus_and_is_right_associative() {
+        assert_eq!(eval("-2^2"), -4.0);
+        assert_eq!(eval("(-2)^2"), 4.0);
+        assert_eq!(eval("2^3^2"), 512.0);
+        assert_eq!(eval("2**3"), 8.0);
+        assert_eq!(eval("2^-1"), 0.5);
+        assert_eq!(eval("--3"), 3.0);
+        assert_eq!(eval("+3"), 3.0);
+    }
+
+    #[test]
+    fn evaluates_functions_and_constants() {
+        assert_eq!(eval("sqrt(16) + abs(-2)"), 6.0);
+        assert_eq!(eval("max(1, 5, 3) - min(4, 2)"), 3.0);
+        assert_eq!(eval("pow(2, 10)"), 1024.0);
+        assert_eq!(eval("floor(2.7) + ceil(2.1) + round(2.5)"), 8.0);
+        assert_eq!(eval("PI"), PI);
+        assert_eq!(eval("ln(e)"), 1.0);
+        assert_eq!(eval("log(1000)"), 3.0);
+    }
+
+    #[test]
+    fn accepts_scientific_notation_and_full_width_input() {
+        assert_eq!(eval("1e3 + 2.5E-1"), 1000.25);
+        assert_eq!(eval("2×（3+1）÷4"), 2.0);
+        assert_eq!(eval("max(1，2)"), 2.0);
+    }
+
+    #[test]
+    fn rejects_invalid_expressions() {
+        assert_eq!(evaluate("1 / 0").unwrap_err(), "Division by zero");
+        assert_eq!(evaluate("5 % 0").unwrap_err(), "Division by zero");
+        assert!(evaluate("").is_err());
+        assert!(evaluate("1 +").is_err());
+        assert!(evaluate("(1 + 2").is_err());
+        assert!(evaluate("1 2").is_err());
+        assert!(evaluate("foo(1)").is_err());
+        assert!(evaluate("sqrt(1, 2)").is_err());
+        assert!(evaluate("1 $ 2").is_err());
+        assert!(evaluate("sqrt(-1)").is_err());
+        assert!(evaluate("10^400").is_err());
+    }
+
+    #[test]
+    fn limits_nesting_depth() {
+        let nested = |depth: usize| format!("{}1{}", "(".repeat(depth), ")".repeat(depth));
+        assert_eq!(eval(&nested(30)), 1.0);
+        assert_eq!(evaluate(&nested(10_000)).unwrap_err(), "Expression is nested too deeply");
+        assert_eq!(evaluate(&format!("{}1", "-".repeat(10_000))).unwrap_err(), "Expression is nested too deeply");
+        assert_eq!(evaluate(&vec!["2"; 10_000].join("^")).unwrap_err(), "Expression is nested too deeply");
+    }
+
+    #[test]
+    fn detects_expressions() {
+        assert!(looks_like_expression("1+1"));
+        assert!(looks_like_expression("=pi"));
+        assert!(looks_like_expression("sqrt(2)"));
+        assert!(!looks_like_expression("42"));
+        assert!(!looks_like_expression("-5"));
+        assert!(!looks_like_expression("notepad"));
+    }
+
+    #[test]
+    fn formats_numbers() {
+        assert_eq!(format_number(3.0), "3");
+        assert_eq!(format_number(-12.0), "-12");
+        assert_eq!(format_number(0.1 + 0.2), "0.3");
+        assert_eq!(format_number(1.0 / 3.0), "0.3333333333");
+        assert_eq!(format_number(1e20), "1e20");
+        assert_eq!(format_number(1.5e-7), "1.5e-7");
+    }
+}
@@ -1,22 +1,65 @@
 use crate::models::*;
 use crate::storage::DataManager;
 use crate::utils::*;
-use tauri::{AppHandle, State};
-use std::sync::Mutex;
+use tauri::{AppHandle, Manager, State};
+use std::sync::{Mutex, MutexGuard};
 use std::collections::HashMap;
 use rfd::AsyncFileDialog;
 
 // 全局数据管理器状态
 pub type DataManagerState = Mutex<Option<DataManager>>;
 
+// 快捷方式的目标有增删或变化后调整目标监听：在数据锁内复制快捷方式，释放锁后再调整，两个锁不嵌套
+fn sync_watched_targets(mut manager_guard: MutexGuard<'_, Option<DataManager>>) -> Result<(), String> {
+    let shortcuts = match manager_guard.as_mut() {
+        Some(manager) => manager.get_data()?.shortcuts.clone(),
+        None => return Ok(()),
+    };
+    drop(manager_guard);
+    
+    crate::target_watcher::GLOBAL_TARGET_WATCHER.sync_targets(&shortcuts);
+    Ok(())
+}
+
 // 初始化数据管理器
 #[tauri::command]
 pub async fn initialize_data_manager(app_handle: AppHandle, state: State<'_, DataManagerState>) -> Result<(), String> {
     let mut manager_guard = state.lock().map_err(|e| format!("Failed to lock state: {}", e))?;
     
-    let manager = DataManager::new(&app_handle)?;
+    let mut manager = DataManager::new(&app_handle)?;
+    let config = manager.get_data()?.config.clone();
     *manager_guard = Some(manager);
     
+    // 启动文件索引；失败（例如旧配置中的无效规则）记录在索引状态中，不影响其他模块启动
+    let cache_dir = app_handle.path().app_cache_dir()
+        .map_err(|e| format!("Failed to get app cache directory: {}", e))?;
+    let _ = crate::file_indexer::GLOBAL_FILE_INDEXER.start(&cache_dir, config.indexer);
+    
+    // 按配置设置图标缓存容量，打开磁盘图标缓存并预热内存缓存；
+    // 磁盘缓存打开失败记录在缓存统计中，此时只使用内存缓存
+    crate::icon_cache::GLOBAL_ICON_CACHE.configure(&config.advanced)?;
+    let _ = crate::icon_cache::GLOBAL_ICON_CACHE.open_disk_cache(&cache_dir);
+    
+    // 打开自定义图标目录并清理不再被引用的图标；失败记录在自定义图标状态中，不影响启动
+    let data_dir = app_handle.path().app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let _ = crate::custom_icon::GLOBAL_CUSTOM_ICONS.open(&data_dir);
+    let mut shortcuts = Vec::new();
+    if let Some(manager) = manager_guard.as_mut() {
+        let _ = manager.collect_orphaned_icons();
+        shortcuts = manager.get_data()?.shortcuts.clone();
+    }
+    drop(manager_guard);
+    
+    // 监听目标所在目录，跟踪目标的移动和改名；失败记录在监听状态中，不影响启动
+    let _ = crate::target_watcher::GLOBAL_TARGET_WATCHER.start(app_handle.clone(), &shortcuts);
+    
+    // 后台定期检查快捷方式目标是否存在
+    crate::health_monitor::GLOBAL_HEALTH_MONITOR.start(app_handle.clone(), config.advanced.file_check_interval)?;
+    
+    // 注册全局快捷键，失败时通过 hotkey-status 事件报告，不影响启动
+    crate::hotkeys::GLOBAL_HOTKEYS.apply(&app_handle, &config.hotkeys)?;
+    
     Ok(())
 }
 
@@ -38,13 +81,27 @@ pub async fn open_file_dialog(app_handle: AppHandle) -> Result<String, String> {
 
 // 获取所有快捷方式
 #[tauri::command]
-pub async fn get_shortcuts(state: State<'_, DataManagerState>) -> Result<Vec<Shortcut>, String> {
+pub async fn get_shortcuts(sort_mode: Option<ShortcutSortMode>, state: State<'_, DataManagerState>) -> Result<Vec<Shortcut>, String> {
     let mut manager_guard = state.lock().map_err(|e| format!("Failed to lock state: {}", e))?;
     
     let manager = manager_guard.as_mut().ok_or("Data manager not initialized")?;
     let data = manager.get_data()?;
     
-    Ok(data.shortcuts.clone())
+    let mut shortcuts = data.shortcuts.clone();
+    
+    // 未指定排序方式时，按配置决定是否自动排序
+    let behavior = &data.config.behavior;
+    let sort_mode = sort_mode.or(match (behavior.auto_sort_enabled, behavior.sort_by_frequency) {
+        (true, true) => Some(ShortcutSortMode::Frecency),
+        (true, false) => Some(ShortcutSortMode::Name),
+        (false, _) => None,
+    });
+    
+    if let Some(mode) = sort_mode {
+        crate::search::sort_shortcuts(&mut shortcuts, mode);
+    }
+    
+    Ok(shortcuts)
 }
 
 // 根据分类获取快捷方式
@@ -76,6 +133,7 @@ pub async fn create_shortcut(request: CreateShortcutRequest, state: State<'_, Da
     }
     
     let shortcut = manager.add_shortcut(request)?;
+    sync_watched_targets(manager_guard)?;
     
     Ok(shortcut)
 }
@@ -94,7 +152,11 @@ pub async fn update_shortcut(id: String, request: UpdateShortcutRequest, state:
         }
     }
     
+    let path_changed = request.file_path.is_some();
     let shortcut = manager.update_shortcut(&id, request)?;
+    if path_changed {
+        sync_watched_targets(manager_guard)?;
+    }
     
     Ok(shortcut)
 }
@@ -107,13 +169,19 @@ pub async fn delete_shortcut(id: String, state: State<'_, DataManagerState>) ->
     let manager = manager_guard.as_mut().ok_or("Data manager not initialized")?;
     
     manager.delete_shortcut(&id)?;
+    sync_watched_targets(manager_guard)?;
     
     Ok(())
 }
 
-// 启动快捷方式
+// 启动快捷方式；带参数的快捷方式可以传入搜索框中的剩余文本 input，或按名称指定的 parameters
 #[tauri::command]
-pub async fn launch_shortcut(id: String, state: State<'_, DataManagerState>) -> Result<(), String> {
+pub async fn launch_shortcut(
+    id: String,
+    input: Option<String>,
+    parameters: Option<HashMap<String, String>>,
+    state: State<'_, DataManagerState>,
+) -> Result<LaunchOutcome, String> {
     let mut manager_guard = state.lock().map_err(|e| format!("Failed to lock state: {}", e))?;
     
     let manager = manager_guard.as_mut().ok_or("Data manager not initialized")?;
@@ -124,18 +192,53 @@ pub async fn launch_shortcut(id: String, state: State<'_, DataManagerState>) ->
         .find(|s| s.id == id)
         .ok_or("Shortcut not found")?;
     
-    let shortcut_path = shortcut.file_path.clone();
-    
-    // 释放数据引用，避免借用检查问题
-    drop(data);
+    // 显式指定的参数优先于按顺序分配的输入文本
+    let mut values = input
+        .map(|input| crate::shortcut_params::assign_input(&crate::shortcut_params::parameters(shortcut), &input))
+        .unwrap_or_default();
+    values.extend(parameters.unwrap_or_default());
+    
+    let resolved = match crate::shortcut_params::resolve(shortcut, &values) {
+        Ok(resolved) => resolved,
+        Err(missing_parameters) => {
+            return Ok(LaunchOutcome {
+                launched: false,
+                missing_parameters,
+            });
+        }
+    };
     
     // 启动文件
-    crate::utils::launch_file(&shortcut_path)?;
+    if is_url(&resolved.target) {
+        open_url(&resolved.target)?;
+    } else if resolved.arguments.is_empty() {
+        launch_file(&resolved.target)?;
+    } else {
+        launch_with_arguments(&resolved.target, &resolved.arguments)?;
+    }
     
     // 增加使用次数
     manager.increment_usage(&id)?;
     
-    Ok(())
+    Ok(LaunchOutcome {
+        launched: true,
+        missing_parameters: Vec::new(),
+    })
+}
+
+// 获取快捷方式的参数列表，供界面在启动前提示填写
+#[tauri::command]
+pub async fn get_shortcut_parameters(id: String, state: State<'_, DataManagerState>) -> Result<Vec<ShortcutParameter>, String> {
+    let mut manager_guard = state.lock().map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    let manager = manager_guard.as_mut().ok_or("Data manager not initialized")?;
+    let data = manager.get_data()?;
+    
+    let shortcut = data.shortcuts.iter()
+        .find(|s| s.id == id)
+        .ok_or("Shortcut not found")?;
+    
+    Ok(crate::shortcut_params::parameters(shortcut))
 }
 
 // 获取所有分类
@@ -185,6 +288,32 @@ pub async fn delete_category(id: String, state: State<'_, DataManagerState>) ->
     Ok(())
 }
 
+// 根据分类中快捷方式图标的颜色推荐分类颜色，没有可用图标时返回 None
+#[tauri::command]
+pub async fn suggest_category_color(category_id: String, state: State<'_, DataManagerState>) -> Result<Option<CategoryColorSuggestion>, String> {
+    let members: Vec<(String, Option<String>)> = {
+        let mut manager_guard = state.lock().map_err(|e| format!("Failed to lock state: {}", e))?;
+        
+        let manager = manager_guard.as_mut().ok_or("Data manager not initialized")?;
+        let data = manager.get_data()?;
+        
+        data.shortcuts.iter()
+            .filter(|s| s.category_id.as_ref() == Some(&category_id) && s.is_active)
+            .map(|s| (s.file_path.clone(), s.icon_path.clone()))
+            .collect()
+    };
+    
+    // 颜色分析不依赖尺寸，用小图标即可，多数情况下直接命中缓存
+    let colors: Vec<IconColors> = members.iter()
+        .filter_map(|(file_path, icon_path)| {
+            crate::icon_cache::get_cached_icon(file_path, icon_path.as_deref(), IconSize::from_large(true)).ok()
+        })
+        .filter_map(|icon| icon.colors)
+        .collect();
+    
+    Ok(crate::icon_colors::suggest_category_color(&colors))
+}
+
 // 验证文件路径
 #[tauri::command]
 pub fn validate_file_path_command(file_path: String) -> Result<bool, String> {
@@ -197,35 +326,160 @@ pub fn get_file_info_command(file_path: String) -> Result<FileInfo, String> {
     crate::utils::get_file_info(&file_path)
 }
 
-// 获取文件图标
+// 图标尺寸：指定 size 时按像素尺寸和缩放倍数，否则按旧的大/小图标
+fn requested_icon_size(large_icon: Option<bool>, size: Option<u32>, scale: Option<f32>, default_large: bool) -> IconSize {
+    let base = size.unwrap_or_else(|| IconSize::from_large(large_icon.unwrap_or(default_large)).size);
+    IconSize::new(base, scale.unwrap_or(1.0))
+}
+
+// 获取文件图标；传入快捷方式的 icon_path 时优先使用自定义图标
 #[tauri::command]
-pub fn get_file_icon_command(file_path: String, large_icon: Option<bool>) -> Result<IconResult, String> {
-    crate::icon_cache::get_cached_icon(&file_path, large_icon.unwrap_or(false))
+pub fn get_file_icon_command(file_path: String, icon_path: Option<String>, large_icon: Option<bool>, size: Option<u32>, scale: Option<f32>) -> Result<IconResult, String> {
+    crate::icon_cache::get_cached_icon(&file_path, icon_path.as_deref(), requested_icon_size(large_icon, size, scale, false))
 }
 
 // 获取目录图标
 #[tauri::command]
-pub fn get_directory_icon_command(directory_path: String, large_icon: Option<bool>) -> Result<IconResult, String> {
-    crate::icon_cache::get_cached_icon(&directory_path, large_icon.unwrap_or(false))
+pub fn get_directory_icon_command(directory_path: String, icon_path: Option<String>, large_icon: Option<bool>, size: Option<u32>, scale: Option<f32>) -> Result<IconResult, String> {
+    crate::icon_cache::get_cached_icon(&directory_path, icon_path.as_deref(), requested_icon_size(large_icon, size, scale, false))
+}
+
+// 获取带状态角标的快捷方式图标，默认尺寸为界面设置中的图标尺寸；ids 为空时返回全部快捷方式
+#[tauri::command]
+pub async fn get_shortcut_icons(ids: Option<Vec<String>>, size: Option<u32>, scale: Option<f32>, state: State<'_, DataManagerState>) -> Result<Vec<ShortcutIcon>, String> {
+    let (shortcuts, categories, config) = {
+        let mut manager_guard = state.lock().map_err(|e| format!("Failed to lock state: {}", e))?;
+        
+        let manager = manager_guard.as_mut().ok_or("Data manager not initialized")?;
+        let data = manager.get_data()?;
+        
+        let shortcuts: Vec<Shortcut> = match &ids {
+            Some(ids) => ids.iter()
+                .filter_map(|id| data.shortcuts.iter().find(|s| &s.id == id))
+                .cloned()
+                .collect(),
+            None => data.shortcuts.clone(),
+        };
+        (shortcuts, data.categories.clone(), data.config.ui.clone())
+    };
+    
+    let icon_size = IconSize::new(size.unwrap_or(config.icon_size), scale.unwrap_or(1.0));
+    
+    Ok(shortcuts.iter()
+        .map(|shortcut| crate::icon_badges::shortcut_icon(shortcut, &categories, &config.badges, icon_size))
+        .collect())
 }
 
-// 批量获取图标
+// 从图片文件设置快捷方式图标，支持常见图片格式以及 .ico、.icns 和程序文件中的图标
 #[tauri::command]
-pub fn get_icons_batch_command(file_paths: Vec<String>, large_icon: Option<bool>) -> Result<Vec<(String, IconResult)>, String> {
-    let results = crate::icon_extractor::extract_icons_batch(file_paths, large_icon.unwrap_or(true));
+pub async fn set_shortcut_icon_from_file(id: String, image_path: String, crop: Option<IconCrop>, state: State<'_, DataManagerState>) -> Result<Shortcut, String> {
+    // 解码和导入不需要数据锁，只在写入 icon_path 时加锁
+    let image = crate::custom_icon::decode_image_file(std::path::Path::new(&image_path))?;
+    let icon_path = crate::custom_icon::GLOBAL_CUSTOM_ICONS.import(image, crop)?;
     
-    // 过滤出成功的结果
-    let successful_results: Vec<(String, IconResult)> = results
-        .into_iter()
-        .filter_map(|(path, result)| {
-            match result {
-                Ok(icon) => Some((path, icon)),
-                Err(_) => None, // 忽略失败的结果
-            }
-        })
-        .collect();
+    let mut manager_guard = state.lock().map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    let manager = manager_guard.as_mut().ok_or("Data manager not initialized")?;
+    
+    manager.set_shortcut_icon(&id, Some(icon_path))
+}
+
+// 从剪贴板中的图片设置快捷方式图标
+#[tauri::command]
+pub async fn set_shortcut_icon_from_clipboard(id: String, crop: Option<IconCrop>, state: State<'_, DataManagerState>) -> Result<Shortcut, String> {
+    let image = crate::custom_icon::read_clipboard_image()?;
+    let icon_path = crate::custom_icon::GLOBAL_CUSTOM_ICONS.import(image, crop)?;
+    
+    let mut manager_guard = state.lock().map_err(|e| format!("Failed to lock state: {}", e))?;
     
-    Ok(successful_results)
+    let manager = manager_guard.as_mut().ok_or("Data manager not initialized")?;
+    
+    manager.set_shortcut_icon(&id, Some(icon_path))
+}
+
+// 使用另一个快捷方式的图标；对方是自定义图标时共用同一组文件，否则从其目标提取后导入
+#[tauri::command]
+pub async fn copy_shortcut_icon(id: String, source_id: String, state: State<'_, DataManagerState>) -> Result<Shortcut, String> {
+    let (source_icon, source_target) = {
+        let mut manager_guard = state.lock().map_err(|e| format!("Failed to lock state: {}", e))?;
+        
+        let manager = manager_guard.as_mut().ok_or("Data manager not initialized")?;
+        let data = manager.get_data()?;
+        
+        let source = data.shortcuts.iter()
+            .find(|s| s.id == source_id)
+            .ok_or("Source shortcut not found")?;
+        (source.icon_path.clone(), source.file_path.clone())
+    };
+    
+    // 提取和导入在释放数据锁后进行
+    let custom_icons = &crate::custom_icon::GLOBAL_CUSTOM_ICONS;
+    let icon_path = match source_icon.filter(|path| custom_icons.is_custom_icon(path)) {
+        Some(icon_path) => icon_path,
+        None => {
+            let largest = crate::custom_icon::STANDARD_SIZES[crate::custom_icon::STANDARD_SIZES.len() - 1];
+            let icon = crate::icon_cache::get_cached_icon(&source_target, None, IconSize::new(largest, 1.0))?;
+            custom_icons.import(crate::custom_icon::decode_icon_result(&icon)?, None)?
+        }
+    };
+    
+    let mut manager_guard = state.lock().map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    let manager = manager_guard.as_mut().ok_or("Data manager not initialized")?;
+    
+    manager.set_shortcut_icon(&id, Some(icon_path))
+}
+
+// 清除自定义图标，恢复为从目标提取
+#[tauri::command]
+pub async fn clear_shortcut_icon(id: String, state: State<'_, DataManagerState>) -> Result<Shortcut, String> {
+    let mut manager_guard = state.lock().map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    let manager = manager_guard.as_mut().ok_or("Data manager not initialized")?;
+    
+    manager.set_shortcut_icon(&id, None)
+}
+
+// 清理不再被引用的自定义图标，返回删除的数量
+#[tauri::command]
+pub async fn collect_orphaned_icons(state: State<'_, DataManagerState>) -> Result<usize, String> {
+    let mut manager_guard = state.lock().map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    let manager = manager_guard.as_mut().ok_or("Data manager not initialized")?;
+    
+    manager.collect_orphaned_icons()
+}
+
+// 批量获取图标；指定 sizes 时每个文件返回多种尺寸，失败的项带有原因，结果按请求顺序排列
+#[tauri::command]
+pub async fn get_icons_batch_command(file_paths: Vec<String>, large_icon: Option<bool>, sizes: Option<Vec<IconSize>>) -> Result<Vec<IconBatchItem>, String> {
+    let sizes = crate::icon_batch::normalize_sizes(sizes, large_icon.unwrap_or(true));
+    let results = Mutex::new(Vec::with_capacity(file_paths.len() * sizes.len()));
+    let cancelled = std::sync::atomic::AtomicBool::new(false);
+    
+    crate::icon_batch::run_batch(None, &file_paths, &sizes, &cancelled, |index, item| {
+        if let Ok(mut results) = results.lock() {
+            results.push((index, item));
+        }
+    });
+    
+    let mut results = results.into_inner().map_err(|e| format!("Failed to collect icon results: {}", e))?;
+    results.sort_by_key(|(index, _)| *index);
+    Ok(results.into_iter().map(|(_, item)| item).collect())
+}
+
+// 在后台批量提取图标，返回任务 ID；结果通过 icon-batch-item 和 icon-batch-progress 事件推送
+// job_id 由前端生成时，可以在调用前就开始按 ID 接收事件，不会漏掉返回之前发出的事件
+#[tauri::command]
+pub fn start_icon_batch(app_handle: AppHandle, job_id: Option<String>, file_paths: Vec<String>, large_icon: Option<bool>, sizes: Option<Vec<IconSize>>) -> Result<String, String> {
+    let sizes = crate::icon_batch::normalize_sizes(sizes, large_icon.unwrap_or(true));
+    crate::icon_batch::GLOBAL_ICON_BATCH.start(app_handle, job_id, file_paths, sizes)
+}
+
+// 取消后台批量提取任务，任务已结束时返回 false
+#[tauri::command]
+pub fn cancel_icon_batch(job_id: String) -> Result<bool, String> {
+    Ok(crate::icon_batch::GLOBAL_ICON_BATCH.cancel(&job_id))
 }
 
 // 验证目录路径
@@ -246,6 +500,159 @@ pub fn check_file_exists_command(file_path: String) -> Result<bool, String> {
     crate::utils::check_file_exists(&file_path)
 }
 
+// 立即在后台检查一轮快捷方式目标，结果通过 shortcut-broken / shortcut-recovered 事件推送
+#[tauri::command]
+pub fn check_shortcut_targets() -> Result<(), String> {
+    crate::health_monitor::GLOBAL_HEALTH_MONITOR.check_now()
+}
+
+// 获取目标检查状态：最近一轮检查的时间和失败原因
+#[tauri::command]
+pub fn get_health_monitor_status() -> Result<crate::health_monitor::HealthMonitorStatus, String> {
+    crate::health_monitor::GLOBAL_HEALTH_MONITOR.status()
+}
+
+// 目标被改名、移动或替换后等待确认的路径修复建议
+#[tauri::command]
+pub fn get_path_suggestions() -> Result<Vec<PathSuggestion>, String> {
+    crate::target_watcher::GLOBAL_TARGET_WATCHER.suggestions()
+}
+
+// 应用修复建议，更新快捷方式的目标路径
+#[tauri::command]
+pub async fn apply_path_suggestion(suggestion_id: String, state: State<'_, DataManagerState>) -> Result<Shortcut, String> {
+    let suggestion = crate::target_watcher::GLOBAL_TARGET_WATCHER.take_suggestion(&suggestion_id)?
+        .ok_or("Suggestion not found")?;
+    
+    let mut manager_guard = state.lock().map_err(|e| format!("Failed to lock state: {}", e))?;
+    let manager = manager_guard.as_mut().ok_or("Data manager not initialized")?;
+    
+    let updated = manager.update_target_paths(&[(suggestion.shortcut_id.clone(), suggestion.new_path)])?;
+    let shortcut = match updated.into_iter().next() {
+        Some((_, shortcut)) => shortcut,
+        None => manager.get_data()?.shortcuts.iter()
+            .find(|s| s.id == suggestion.shortcut_id)
+            .cloned()
+            .ok_or("Shortcut not found")?,
+    };
+    sync_watched_targets(manager_guard)?;
+    
+    Ok(shortcut)
+}
+
+// 忽略修复建议
+#[tauri::command]
+pub fn dismiss_path_suggestion(suggestion_id: String) -> Result<bool, String> {
+    Ok(crate::target_watcher::GLOBAL_TARGET_WATCHER.take_suggestion(&suggestion_id)?.is_some())
+}
+
+// 获取自定义图标存储状态
+#[tauri::command]
+pub fn get_custom_icon_status() -> Result<crate::custom_icon::CustomIconStatus, String> {
+    crate::custom_icon::GLOBAL_CUSTOM_ICONS.status()
+}
+
+// 获取目标监听状态
+#[tauri::command]
+pub fn get_target_watcher_status() -> Result<crate::target_watcher::TargetWatcherStatus, String> {
+    crate::target_watcher::GLOBAL_TARGET_WATCHER.status()
+}
+
+// 为目标丢失的快捷方式查找可能的替代目标；不指定 ID 时检查全部快捷方式
+#[tauri::command]
+pub async fn suggest_repairs(shortcut_ids: Option<Vec<String>>, state: State<'_, DataManagerState>) -> Result<Vec<RepairSuggestion>, String> {
+    // 查找期间不占用数据锁
+    let shortcuts: Vec<Shortcut> = {
+        let mut manager_guard = state.lock().map_err(|e| format!("Failed to lock state: {}", e))?;
+        let manager = manager_guard.as_mut().ok_or("Data manager not initialized")?;
+        manager.get_data()?.shortcuts.iter()
+            .filter(|s| shortcut_ids.as_ref().is_none_or(|ids| ids.contains(&s.id)))
+            .filter(|s| !is_url(&s.file_path) && !std::path::Path::new(&s.file_path).exists())
+            .cloned()
+            .collect()
+    };
+    if shortcuts.is_empty() {
+        return Ok(Vec::new());
+    }
+    
+    let apps = crate::app_catalog::GLOBAL_APP_CATALOG.list().unwrap_or_default();
+    Ok(shortcuts.iter()
+        .map(|shortcut| crate::repair::suggest(shortcut, &apps))
+        .collect())
+}
+
+// 批量应用选定的修复，目标路径都存在时才会修改
+#[tauri::command]
+pub async fn apply_repairs(fixes: Vec<RepairFix>, state: State<'_, DataManagerState>) -> Result<Vec<Shortcut>, String> {
+    if let Some(fix) = fixes.iter().find(|fix| !std::path::Path::new(&fix.new_path).exists()) {
+        return Err(format!("Repair target does not exist: {}", fix.new_path));
+    }
+    
+    let mut manager_guard = state.lock().map_err(|e| format!("Failed to lock state: {}", e))?;
+    let manager = manager_guard.as_mut().ok_or("Data manager not initialized")?;
+    
+    let updates: Vec<(String, String)> = fixes.into_iter()
+        .map(|fix| (fix.shortcut_id, fix.new_path))
+        .collect();
+    let updated: Vec<Shortcut> = manager.update_target_paths(&updates)?
+        .into_iter()
+        .map(|(_, shortcut)| shortcut)
+        .collect();
+    sync_watched_targets(manager_guard)?;
+    
+    Ok(updated)
+}
+
+// 计算文件的内容指纹；full 为 true 时大文件也计算全部内容
+#[tauri::command]
+pub async fn get_file_fingerprint(file_path: String, full: Option<bool>) -> Result<crate::fingerprint::Fingerprint, String> {
+    let path = std::path::Path::new(&file_path);
+    if full.unwrap_or(false) {
+        crate::fingerprint::compute(path, crate::fingerprint::FingerprintKind::Sha256)
+    } else {
+        crate::fingerprint::content(path)
+    }
+}
+
+// 比对快捷方式目标与记录的内容指纹；不指定 ID 时检查全部本地目标
+#[tauri::command]
+pub async fn check_target_integrity(shortcut_ids: Option<Vec<String>>, state: State<'_, DataManagerState>) -> Result<Vec<TargetIntegrity>, String> {
+    // 计算指纹期间不占用数据锁
+    let targets: Vec<(String, String, Option<String>)> = {
+        let mut manager_guard = state.lock().map_err(|e| format!("Failed to lock state: {}", e))?;
+        let manager = manager_guard.as_mut().ok_or("Data manager not initialized")?;
+        manager.get_data()?.shortcuts.iter()
+            .filter(|s| shortcut_ids.as_ref().is_none_or(|ids| ids.contains(&s.id)))
+            .filter(|s| !is_url(&s.file_path))
+            .map(|s| (s.id.clone(), s.file_path.clone(), s.target_fingerprint.clone()))
+            .collect()
+    };
+    
+    Ok(targets.into_iter()
+        .map(|(shortcut_id, file_path, recorded)| {
+            let path = std::path::Path::new(&file_path);
+            let recorded_fingerprint = recorded.as_deref().and_then(crate::fingerprint::Fingerprint::parse);
+            // 按记录时的种类重新计算，部分指纹和完整指纹不能直接比较
+            let current = match &recorded_fingerprint {
+                Some(fingerprint) => crate::fingerprint::compute(path, fingerprint.kind).ok(),
+                None => crate::fingerprint::content(path).ok(),
+            };
+            let status = match (&recorded_fingerprint, &current) {
+                _ if !path.exists() => "missing",
+                (None, _) => "unrecorded",
+                (Some(recorded), Some(current)) if recorded == current => "unchanged",
+                _ => "modified",
+            };
+            TargetIntegrity {
+                shortcut_id,
+                status: status.to_string(),
+                recorded,
+                current: current.map(|f| f.to_string()),
+            }
+        })
+        .collect())
+}
+
 // 获取应用配置
 #[tauri::command]
 pub async fn get_app_config(state: State<'_, DataManagerState>) -> Result<AppConfig, String> {
@@ -259,18 +666,44 @@ pub async fn get_app_config(state: State<'_, DataManagerState>) -> Result<AppCon
 
 // 更新应用配置
 #[tauri::command]
-pub async fn update_app_config(config: AppConfig, state: State<'_, DataManagerState>) -> Result<(), String> {
+pub async fn update_app_config(app_handle: AppHandle, config: AppConfig, state: State<'_, DataManagerState>) -> Result<(), String> {
+    // 快捷键格式错误或互相冲突、索引规则无效时不保存
+    crate::hotkeys::validate(&config.hotkeys)?;
+    crate::file_indexer::validate(&config.indexer)?;
+    
     let mut manager_guard = state.lock().map_err(|e| format!("Failed to lock state: {}", e))?;
     
     let manager = manager_guard.as_mut().ok_or("Data manager not initialized")?;
     let data = manager.get_data_mut()?;
     
+    let indexer_config = config.indexer.clone();
+    let advanced_config = config.advanced.clone();
+    let hotkey_config = config.hotkeys.clone();
     data.config = config;
     manager.save_data()?;
+    drop(manager_guard);
+    
+    crate::file_indexer::GLOBAL_FILE_INDEXER.configure(indexer_config)?;
+    crate::icon_cache::GLOBAL_ICON_CACHE.configure(&advanced_config)?;
+    crate::health_monitor::GLOBAL_HEALTH_MONITOR.configure(advanced_config.file_check_interval)?;
+    // 注册失败（例如被其他程序占用）时配置仍然保存，结果通过 hotkey-status 事件报告
+    crate::hotkeys::GLOBAL_HOTKEYS.apply(&app_handle, &hotkey_config)?;
     
     Ok(())
 }
 
+// 快捷键的注册状态
+#[tauri::command]
+pub fn get_hotkey_status() -> Result<HotkeyStatus, String> {
+    crate::hotkeys::GLOBAL_HOTKEYS.status()
+}
+
+// 解析快捷键，返回规范写法，格式错误时返回具体原因
+#[tauri::command]
+pub fn validate_hotkey(accelerator: String) -> Result<String, String> {
+    crate::hotkeys::Accelerator::parse(&accelerator).map(|a| a.to_string())
+}
+
 // 批量更新快捷方式排序
 #[tauri::command]
 pub async fn update_shortcuts_order(updates: Vec<(String, i32)>, state: State<'_, DataManagerState>) -> Result<(), String> {
@@ -317,24 +750,176 @@ pub async fn update_categories_order(updates: Vec<(String, i32)>, state: State<'
 
 // 搜索快捷方式
 #[tauri::command]
-pub async fn search_shortcuts(query: String, state: State<'_, DataManagerState>) -> Result<Vec<Shortcut>, String> {
+pub async fn search_shortcuts(query: String, limit: Option<usize>, state: State<'_, DataManagerState>) -> Result<Vec<SearchResult>, String> {
     let mut manager_guard = state.lock().map_err(|e| format!("Failed to lock state: {}", e))?;
     
     let manager = manager_guard.as_mut().ok_or("Data manager not initialized")?;
     let data = manager.get_data()?;
     
-    let query_lower = query.to_lowercase();
-    let shortcuts = data.shortcuts.iter()
-        .filter(|s| {
-            s.is_active && (
-                s.name.to_lowercase().contains(&query_lower) ||
-                s.file_path.to_lowercase().contains(&query_lower)
-            )
-        })
-        .cloned()
-        .collect();
+    let results = crate::search::search_shortcuts(data, &query, limit)
+        .map_err(|e| e.to_string())?;
     
-    Ok(shortcuts)
+    Ok(results)
+}
+
+// 统一搜索：合并快捷方式、计算器、网页搜索等提供者的结果
+#[tauri::command]
+pub async fn unified_search(query: String, limit: Option<usize>, state: State<'_, DataManagerState>) -> Result<Vec<ProviderResult>, String> {
+    // 复制数据后释放锁，等待提供者期间其他命令不会被阻塞
+    let data = {
+        let mut manager_guard = state.lock().map_err(|e| format!("Failed to lock state: {}", e))?;
+        let manager = manager_guard.as_mut().ok_or("Data manager not initialized")?;
+        manager.get_data()?.clone()
+    };
+    
+    Ok(crate::providers::GLOBAL_PROVIDER_REGISTRY.search(&data, &query, limit.unwrap_or(20)))
+}
+
+// 获取搜索提供者设置（包含配置中尚未出现的内置提供者）
+#[tauri::command]
+pub async fn get_search_providers(state: State<'_, DataManagerState>) -> Result<Vec<ProviderSettings>, String> {
+    let mut manager_guard = state.lock().map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    let manager = manager_guard.as_mut().ok_or("Data manager not initialized")?;
+    let data = manager.get_data()?;
+    
+    Ok(crate::providers::GLOBAL_PROVIDER_REGISTRY.resolve_settings(&data.config.search))
+}
+
+// 执行搜索结果的动作；复制文本由前端处理
+#[tauri::command]
+pub async fn execute_provider_action(action: ProviderAction, state: State<'_, DataManagerState>) -> Result<LaunchOutcome, String> {
+    match action {
+        ProviderAction::LaunchShortcut { id, input } => return launch_shortcut(id, input, None, state).await,
+        ProviderAction::OpenPath(path) => launch_file(&path)?,
+        ProviderAction::OpenUrl(url) => open_url(&url)?,
+        ProviderAction::CopyText(_) => return Err("Copy actions are handled by the frontend".to_string()),
+    }
+    
+    Ok(LaunchOutcome {
+        launched: true,
+        missing_parameters: Vec::new(),
+    })
+}
+
+// 检查搜索语法，返回错误位置供界面标注
+#[tauri::command]
+pub fn validate_search_query(query: String) -> Result<Option<crate::query::QueryError>, String> {
+    Ok(crate::query::parse_query(&query).err())
+}
+
+// 检查重复的别名
+#[tauri::command]
+pub async fn find_alias_conflicts(state: State<'_, DataManagerState>) -> Result<Vec<AliasConflict>, String> {
+    let mut manager_guard = state.lock().map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    let manager = manager_guard.as_mut().ok_or("Data manager not initialized")?;
+    
+    manager.find_alias_conflicts()
+}
+
+// 获取已安装应用列表
+#[tauri::command]
+pub async fn get_installed_apps(refresh: Option<bool>) -> Result<Vec<InstalledApp>, String> {
+    if refresh.unwrap_or(false) {
+        crate::app_catalog::GLOBAL_APP_CATALOG.refresh()?;
+    }
+    crate::app_catalog::GLOBAL_APP_CATALOG.list()
+}
+
+// 增量刷新已安装应用
+#[tauri::command]
+pub async fn refresh_installed_apps() -> Result<crate::app_catalog::CatalogRefreshStats, String> {
+    crate::app_catalog::GLOBAL_APP_CATALOG.refresh()
+}
+
+// 搜索已安装应用
+#[tauri::command]
+pub async fn search_installed_apps(query: String, limit: Option<usize>) -> Result<Vec<AppSearchResult>, String> {
+    crate::app_catalog::GLOBAL_APP_CATALOG.search(&query, limit)
+}
+
+// 将已安装应用添加为快捷方式
+#[tauri::command]
+pub async fn add_installed_app_shortcut(app_id: String, category_id: Option<String>, state: State<'_, DataManagerState>) -> Result<Shortcut, String> {
+    let app = crate::app_catalog::GLOBAL_APP_CATALOG.get(&app_id)?
+        .ok_or("Installed application not found")?;
+    
+    if !std::path::Path::new(&app.source_path).exists() {
+        return Err("Application no longer exists".to_string());
+    }
+    
+    // 应用自带的图标导入为自定义图标；找不到或无法解码时仍按目标文件提取图标
+    let icon_path = app.icon.as_deref()
+        .and_then(|icon| crate::custom_icon::decode_app_icon(icon).ok())
+        .and_then(|image| crate::custom_icon::GLOBAL_CUSTOM_ICONS.import(image, None).ok());
+    
+    let mut manager_guard = state.lock().map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    let manager = manager_guard.as_mut().ok_or("Data manager not initialized")?;
+    
+    // .desktop 和 .lnk 文件本身即可启动；.app 包是目录，不走文件路径校验
+    let request = CreateShortcutRequest {
+        name: app.name,
+        file_path: app.source_path,
+        category_id: category_id.or(Some("default".to_string())),
+        icon_path,
+        sort_order: None,
+        aliases: None,
+        keywords: Some(app.keywords),
+        arguments: None,
+    };
+    
+    let shortcut = manager.add_shortcut(request)?;
+    sync_watched_targets(manager_guard)?;
+    
+    Ok(shortcut)
+}
+
+// 获取文件索引状态
+#[tauri::command]
+pub fn get_file_index_status() -> Result<crate::file_indexer::IndexerStatus, String> {
+    crate::file_indexer::GLOBAL_FILE_INDEXER.status()
+}
+
+// 重建文件索引
+#[tauri::command]
+pub fn rebuild_file_index() -> Result<(), String> {
+    crate::file_indexer::GLOBAL_FILE_INDEXER.rebuild()
+}
+
+// 将文件索引中的文件固定为快捷方式
+#[tauri::command]
+pub async fn pin_indexed_file(file_path: String, category_id: Option<String>, state: State<'_, DataManagerState>) -> Result<Shortcut, String> {
+    let file = crate::file_indexer::GLOBAL_FILE_INDEXER.get(&file_path)
+        .ok_or("File is not in the index")?;
+    
+    if !std::path::Path::new(&file.path).exists() {
+        return Err("File no longer exists".to_string());
+    }
+    
+    let mut manager_guard = state.lock().map_err(|e| format!("Failed to lock state: {}", e))?;
+    
+    let manager = manager_guard.as_mut().ok_or("Data manager not initialized")?;
+    
+    let name = crate::app_bundle::display_name(std::path::Path::new(&file.path))
+        .unwrap_or(file.name);
+    
+    let request = CreateShortcutRequest {
+        name,
+        file_path: file.path,
+        category_id: category_id.or(Some("default".to_string())),
+        icon_path: None,
+        sort_order: None,
+        aliases: None,
+        keywords: None,
+        arguments: None,
+    };
+    
+    let shortcut = manager.add_shortcut(request)?;
+    sync_watched_targets(manager_guard)?;
+    
+    Ok(shortcut)
 }
 
 // 获取最近使用的快捷方式
@@ -376,9 +961,7 @@ pub async fn get_popular_shortcuts(limit: Option<usize>, state: State<'_, DataMa
         .cloned()
         .collect::<Vec<_>>();
     
-    shortcuts.sort_by(|a, b| {
-        b.usage_count.cmp(&a.usage_count)
-    });
+    crate::search::sort_shortcuts(&mut shortcuts, ShortcutSortMode::Frecency);
     
     shortcuts.truncate(limit);
     
@@ -403,6 +986,7 @@ pub async fn reload_data(state: State<'_, DataManagerState>) -> Result<(), Strin
     let manager = manager_guard.as_mut().ok_or("Data manager not initialized")?;
     
     manager.reload_data()?;
+    sync_watched_targets(manager_guard)?;
     
     Ok(())
 }
@@ -422,7 +1006,8 @@ pub fn get_cache_stats() -> Result<crate::icon_cache::CacheStats, String> {
 
 // 预加载图标
 #[tauri::command]
-pub fn preload_icons(file_paths: Vec<String>) -> Result<String, String> {
-    crate::icon_cache::GLOBAL_ICON_CACHE.preload_icons(file_paths)?;
-    Ok("Icons preloaded successfully".to_string())
+pub fn preload_icons(file_paths: Vec<String>, sizes: Option<Vec<IconSize>>) -> Result<String, String> {
+    let sizes = crate::icon_batch::normalize_sizes(sizes, true);
+    crate::icon_cache::GLOBAL_ICON_CACHE.preload_icons(file_paths, sizes)?;
+    Ok("Icon preloading started".to_string())
 }
\ No newline at end of file
@@ -0,0 +1,389 @@
+use crate::models::{IconCrop, IconResult};
+use base64::{Engine as _, engine::general_purpose};
+use image::DynamicImage;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use uuid::Uuid;
+
+// 自定义图标保存在 <app_data_dir>/custom_icons/<图标ID>_<尺寸>.png，
+// 每次导入生成新的图标 ID，文件写入后不再修改；
+// 快捷方式的 icon_path 指向最大尺寸的文件，读取时按请求的尺寸选取最接近的一张
+const CUSTOM_ICON_DIR_NAME: &str = "custom_icons";
+pub const STANDARD_SIZES: [u32; 6] = [16, 32, 48, 64, 128, 256];
+
+// 导入在数据锁外进行，写入 icon_path 之前图标还没有被引用；清理时跳过这段时间内写入的文件
+const IMPORT_GRACE_PERIOD: Duration = Duration::from_secs(60);
+
+// 自定义图标存储状态
+#[derive(Debug, Clone, Serialize)]
+pub struct CustomIconStatus {
+    pub open: bool,
+    pub directory: Option<String>,
+    pub last_error: Option<String>,  // 最近一次打开目录或清理图标的错误
+}
+
+pub struct CustomIconStore {
+    dir: Mutex<Option<PathBuf>>,
+    last_error: Mutex<Option<String>>,
+}
+
+impl CustomIconStore {
+    pub fn new() -> Self {
+        Self { dir: Mutex::new(None), last_error: Mutex::new(None) }
+    }
+
+    // 打开图标目录；失败时记录在状态中，存储保持未打开，导入和读取返回错误
+    pub fn open(&self, app_data_dir: &Path) -> Result<(), String> {
+        let dir = app_data_dir.join(CUSTOM_ICON_DIR_NAME);
+        if let Err(e) = fs::create_dir_all(&dir) {
+            let error = format!("Failed to create custom icon directory: {}", e);
+            self.record_error(error.clone());
+            return Err(error);
+        }
+        *self.dir.lock().map_err(|e| format!("Failed to lock custom icon store: {}", e))? = Some(dir);
+        Ok(())
+    }
+
+    pub fn status(&self) -> Result<CustomIconStatus, String> {
+        let dir = self.dir.lock().map_err(|e| format!("Failed to lock custom icon store: {}", e))?.clone();
+        let last_error = self.last_error.lock().map_err(|e| format!("Failed to lock custom icon store: {}", e))?.clone();
+        Ok(CustomIconStatus {
+            open: dir.is_some(),
+            directory: dir.map(|d| d.to_string_lossy().to_string()),
+            last_error,
+        })
+    }
+
+    fn record_error(&self, error: String) {
+        if let Ok(mut last_error) = self.last_error.lock() {
+            *last_error = Some(error);
+        }
+    }
+
+    fn dir(&self) -> Result<PathBuf, String> {
+        self.dir.lock()
+            .map_err(|e| format!("Failed to lock custom icon store: {}", e))?
+            .clone()
+            .ok_or_else(|| "Custom icon store is not open".to_string())
+    }
+
+    // 裁剪为正方形并保存各标准尺寸，返回写入 icon_path 的路径
+    pub fn import(&self, image: DynamicImage, crop: Option<IconCrop>) -> Result<String, String> {
+        let dir = self.dir()?;
+        let image = square_crop(image, crop)?;
+        let icon_id = Uuid::new_v4().to_string();
+
+        for size in STANDARD_SIZES {
+            let png_data = crate::icon_extractor::encode_png(image.clone(), size)?;
+            if let Err(e) = fs::write(dir.join(file_name(&icon_id, size)), png_data) {
+                self.remove(&icon_id);
+                return Err(format!("Failed to write custom icon: {}", e));
+            }
+        }
+
+        let largest = STANDARD_SIZES[STANDARD_SIZES.len() - 1];
+        Ok(dir.join(file_name(&icon_id, largest)).to_string_lossy().to_string())
+    }
+
+    // 图标 ID；icon_path 不是本存储管理的文件时返回 None（例如旧数据中的 data URI）
+    fn icon_id(&self, icon_path: &str) -> Option<String> {
+        let dir = self.dir().ok()?;
+        let path = Path::new(icon_path);
+        if path.parent()? != dir {
+            return None;
+        }
+        let stem = path.file_stem()?.to_str()?;
+        let (icon_id, _) = stem.rsplit_once('_')?;
+        Uuid::parse_str(icon_id).ok()?;
+        Some(icon_id.to_string())
+    }
+
+    // icon_path 是存在的自定义图标时返回该路径
+    pub fn resolve(&self, icon_path: &str) -> Option<String> {
+        self.icon_id(icon_path)?;
+        Path::new(icon_path).is_file().then(|| icon_path.to_string())
+    }
+
+    pub fn is_custom_icon(&self, icon_path: &str) -> bool {
+        self.icon_id(icon_path).is_some()
+    }
+
+    // 读取最接近请求尺寸的一张，不小于请求尺寸的优先，再缩放到精确像素
+    pub fn load(&self, icon_path: &str, pixels: u32) -> Result<IconResult, String> {
+        let icon_id = self.icon_id(icon_path).ok_or("Not a custom icon")?;
+        let dir = self.dir()?;
+
+        let size = STANDARD_SIZES.iter()
+            .copied()
+            .find(|size| *size >= pixels)
+            .unwrap_or(STANDARD_SIZES[STANDARD_SIZES.len() - 1]);
+        let data = fs::read(dir.join(file_name(&icon_id, size)))
+            .map_err(|e| format!("Failed to read custom icon: {}", e))?;
+
+        let png_data = if size == pixels {
+            data
+        } else {
+            let image = image::load_from_memory_with_format(&data, image::ImageFormat::Png)
+                .map_err(|e| format!("Failed to decode custom icon: {}", e))?;
+            crate::icon_extractor::encode_png(image, pixels)?
+        };
+
+        Ok(IconResult {
+            icon_data: format!("data:image/png;base64,{}", general_purpose::STANDARD.encode(png_data)),
+            icon_format: "png".to_string(),
+            from_cache: false,
+            file_hash: Some(icon_id),
+            size: pixels,
+            colors: None,
+        })
+    }
+
+    fn remove(&self, icon_id: &str) {
+        let Ok(dir) = self.dir() else { return };
+        for size in STANDARD_SIZES {
+            let _ = fs::remove_file(dir.join(file_name(icon_id, size)));
+        }
+    }
+
+    // 删除没有被任何快捷方式引用的图标文件，返回删除的图标数量
+    pub fn collect_garbage<'a>(&self, icon_paths: impl IntoIterator<Item = &'a str>) -> Result<usize, String> {
+        let dir = self.dir()?;
+        let referenced: HashSet<String> = icon_paths.into_iter()
+            .filter_map(|path| self.icon_id(path))
+            .collect();
+
+        let read_dir = match fs::read_dir(&dir) {
+            Ok(read_dir) => read_dir,
+            Err(e) => {
+                let error = format!("Failed to read custom icon directory: {}", e);
+                self.record_error(error.clone());
+                return Err(error);
+            }
+        };
+
+        let now = SystemTime::now();
+        let mut removed = HashSet::new();
+        for entry in read_dir.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let icon_id = name.rsplit_once('_').map(|(id, _)| id).unwrap_or(&name);
+            let recent = entry.metadata()
+                .and_then(|m| m.modified())
+                .is_ok_and(|modified| now.duration_since(modified).unwrap_or_default() < IMPORT_GRACE_PERIOD);
+            if !referenced.contains(icon_id) && !recent && fs::remove_file(entry.path()).is_ok() {
+                removed.insert(icon_id.to_string());
+            }
+        }
+
+        Ok(removed.len())
+    }
+}
+
+fn file_name(icon_id: &str, size: u32) -> String {
+    format!("{}_{}.png", icon_id, size)
+}
+
+// 按指定区域裁剪，区域超出图片时截到边界内；未指定时取居中的最大正方形
+fn square_crop(image: DynamicImage, crop: Option<IconCrop>) -> Result<DynamicImage, String> {
+    let (width, height) = (image.width(), image.height());
+    if width == 0 || height == 0 {
+        return Err("Image is empty".to_string());
+    }
+
+    let (x, y, side) = match crop {
+        Some(crop) => {
+            let x = crop.x.min(width - 1);
+            let y = crop.y.min(height - 1);
+            let side = crop.size.max(1).min(width - x).min(height - y);
+            (x, y, side)
+        }
+        None => {
+            let side = width.min(height);
+            ((width - side) / 2, (height - side) / 2, side)
+        }
+    };
+
+    Ok(image.crop_imm(x, y, side, side))
+}
+
+// 从图片文件解码，格式按文件内容判断；.icns 和程序文件中的图标也可以作为来源
+pub fn decode_image_file(path: &Path) -> Result<DynamicImage, String> {
+    let extension = path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase())
+        .unwrap_or_default();
+
+    // 多尺寸图标取最接近最大标准尺寸的一张
+    let largest = STANDARD_SIZES[STANDARD_SIZES.len() - 1];
+    let png_data = if extension == "icns" {
+        Some(crate::app_bundle::extract_icns_file(path, largest)?)
+    } else if crate::pe_icon::is_supported_extension(&extension) {
+        Some(crate::pe_icon::extract_from_file(path, largest)?)
+    } else {
+        None
+    };
+    if let Some(png_data) = png_data {
+        return image::load_from_memory_with_format(&png_data, image::ImageFormat::Png)
+            .map_err(|e| format!("Failed to decode icon: {}", e));
+    }
+
+    image::ImageReader::open(path)
+        .map_err(|e| format!("Failed to open image: {}", e))?
+        .with_guessed_format()
+        .map_err(|e| format!("Failed to read image: {}", e))?
+        .decode()
+        .map_err(|e| format!("Unsupported image: {}", e))
+}
+
+// 读取已安装应用的图标：Linux 上是图标主题中的名称或图标文件路径，其他平台是图标文件路径
+pub fn decode_app_icon(icon: &str) -> Result<DynamicImage, String> {
+    #[cfg(target_os = "linux")]
+    {
+        let largest = STANDARD_SIZES[STANDARD_SIZES.len() - 1];
+        let path = crate::icon_theme::GLOBAL_ICON_THEME.find_icon(icon, largest, 1)
+            .ok_or_else(|| format!("Icon '{}' not found", icon))?;
+        let png_data = crate::icon_theme::render_icon(&path, largest)?;
+        image::load_from_memory_with_format(&png_data, image::ImageFormat::Png)
+            .map_err(|e| format!("Failed to decode icon: {}", e))
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        decode_image_file(Path::new(icon))
+    }
+}
+
+// 从剪贴板读取图片
+pub fn read_clipboard_image() -> Result<DynamicImage, String> {
+    let mut clipboard = arboard::Clipboard::new()
+        .map_err(|e| format!("Failed to open clipboard: {}", e))?;
+    let data = clipboard.get_image()
+        .map_err(|e| format!("Clipboard does not contain an image: {}", e))?;
+
+    let image = image::RgbaImage::from_raw(data.width as u32, data.height as u32, data.bytes.into_owned())
+        .ok_or("Invalid clipboard image")?;
+    Ok(DynamicImage::ImageRgba8(image))
+}
+
+// 把提取到的图标结果解码为图片，文字形式的默认图标不能作为自定义图标
+pub fn decode_icon_result(icon: &IconResult) -> Result<DynamicImage, String> {
+    if icon.icon_format == "text" {
+        return Err("Source has no image icon".to_string());
+    }
+
+    let payload = match icon.icon_data.split_once(',') {
+        Some((head, payload)) if head.starts_with("data:") => payload,
+        _ => icon.icon_data.as_str(),
+    };
+    let bytes = general_purpose::STANDARD.decode(payload)
+        .map_err(|e| format!("Failed to decode icon data: {}", e))?;
+    image::load_from_memory(&bytes)
+        .map_err(|e| format!("Failed to decode icon: {}", e))
+}
+
+lazy_static::lazy_static! {
+    pub static ref GLOBAL_CUSTOM_ICONS: CustomIconStore = CustomIconStore::new();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::GenericImageView;
+
+    fn open_store(dir: &Path) -> CustomIconStore {
+        let store = CustomIconStore::new();
+        store.open(dir).unwrap();
+        store
+    }
+
+    fn crop(x: u32, y: u32, size: u32) -> Option<IconCrop> {
+        Some(IconCrop { x, y, size })
+    }
+
+    fn blank(width: u32, height: u32) -> DynamicImage {
+        DynamicImage::ImageRgba8(image::RgbaImage::new(width, height))
+    }
+
+    // 把图标文件的修改时间改到清理的宽限期之前
+    fn age(path: &Path) {
+        let old = SystemTime::now() - IMPORT_GRACE_PERIOD * 2;
+        fs::File::options().write(true).open(path).unwrap().set_modified(old).unwrap();
+    }
+
+    #[test]
+    fn crops_the_centered_square_by_default() {
+        let image = square_crop(blank(40, 20), None).unwrap();
+        assert_eq!(image.dimensions(), (20, 20));
+        let image = square_crop(blank(20, 40), None).unwrap();
+        assert_eq!(image.dimensions(), (20, 20));
+    }
+
+    #[test]
+    fn clamps_crop_regions_to_the_image() {
+        let cases = [
+            (crop(0, 0, 10), 10),
+            (crop(30, 0, 20), 10),   // 超出右边界
+            (crop(0, 15, 20), 5),    // 超出下边界
+            (crop(100, 100, 20), 1), // 起点在图片外，截到最后一个像素
+            (crop(0, 0, 0), 1),      // 边长至少为 1
+            (crop(0, 0, 500), 20),
+        ];
+        for (crop, side) in cases {
+            let image = square_crop(blank(40, 20), crop).unwrap();
+            assert_eq!(image.dimensions(), (side, side), "{:?}", crop.map(|c| (c.x, c.y, c.size)));
+        }
+        assert!(square_crop(blank(0, 0), None).is_err());
+    }
+
+    #[test]
+    fn imports_every_standard_size_and_recognizes_its_own_paths() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let store = open_store(data_dir.path());
+
+        let icon_path = store.import(blank(300, 300), None).unwrap();
+        let icon_id = store.icon_id(&icon_path).unwrap();
+        assert!(Uuid::parse_str(&icon_id).is_ok());
+        for size in STANDARD_SIZES {
+            assert!(data_dir.path().join(CUSTOM_ICON_DIR_NAME).join(file_name(&icon_id, size)).is_file());
+        }
+        assert_eq!(store.resolve(&icon_path), Some(icon_path.clone()));
+
+        // 其他目录中的文件、不是 UUID 的文件名和 data URI 都不属于本存储
+        let elsewhere = data_dir.path().join(format!("{}_256.png", icon_id));
+        assert_eq!(store.icon_id(&elsewhere.to_string_lossy()), None);
+        let not_uuid = data_dir.path().join(CUSTOM_ICON_DIR_NAME).join("icon_256.png");
+        assert_eq!(store.icon_id(&not_uuid.to_string_lossy()), None);
+        assert_eq!(store.icon_id("data:image/png;base64,AAAA"), None);
+    }
+
+    #[test]
+    fn collects_only_old_unreferenced_icons() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let store = open_store(data_dir.path());
+        let kept = store.import(blank(64, 64), None).unwrap();
+        let orphan = store.import(blank(64, 64), None).unwrap();
+        let fresh = store.import(blank(64, 64), None).unwrap();
+
+        for entry in fs::read_dir(data_dir.path().join(CUSTOM_ICON_DIR_NAME)).unwrap().flatten() {
+            if !entry.path().to_string_lossy().contains(&store.icon_id(&fresh).unwrap()) {
+                age(&entry.path());
+            }
+        }
+
+        assert_eq!(store.collect_garbage([kept.as_str()]).unwrap(), 1);
+        assert!(store.resolve(&kept).is_some());
+        assert!(store.resolve(&orphan).is_none());
+        // 刚导入、还没写入快捷方式的图标保留
+        assert!(store.resolve(&fresh).is_some());
+    }
+
+    #[test]
+    fn reports_operations_on_a_store_that_is_not_open() {
+        let store = CustomIconStore::new();
+        assert!(store.import(blank(16, 16), None).is_err());
+        assert!(store.collect_garbage([]).is_err());
+        assert!(!store.status().unwrap().open);
+    }
+}
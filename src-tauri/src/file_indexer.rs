@@ -0,0 +1,624 @@
+use crate::models::{IndexRoot, IndexedFile, IndexerConfig};
+use ignore::gitignore::GitignoreBuilder;
+use ignore::overrides::{Override, OverrideBuilder};
+use ignore::WalkBuilder;
+use notify::event::ModifyKind;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, UNIX_EPOCH};
+
+// 索引文件名（位于应用缓存目录）
+const INDEX_FILE_NAME: &str = "file_index.json";
+
+// 有未保存的修改时，最多间隔多久写一次磁盘
+const SAVE_INTERVAL: Duration = Duration::from_secs(5);
+
+// 状态中保留的最近错误条数
+const MAX_ERRORS: usize = 20;
+
+// 根目录的过滤规则：include/exclude glob、深度限制和 .gitignore
+struct RootFilter {
+    config: IndexRoot,
+    root: PathBuf,
+    overrides: Override,
+}
+
+impl RootFilter {
+    fn new(config: &IndexRoot) -> Result<Self, String> {
+        let root = PathBuf::from(&config.path);
+
+        let mut builder = OverrideBuilder::new(&root);
+        for pattern in config.include.iter() {
+            builder.add(pattern)
+                .map_err(|e| format!("Invalid include pattern '{}': {}", pattern, e))?;
+        }
+        for pattern in config.exclude.iter() {
+            builder.add(&format!("!{}", pattern))
+                .map_err(|e| format!("Invalid exclude pattern '{}': {}", pattern, e))?;
+        }
+        let overrides = builder.build()
+            .map_err(|e| format!("Failed to build index patterns: {}", e))?;
+
+        Ok(Self { config: config.clone(), root, overrides })
+    }
+
+    // 判断单个路径是否应当出现在索引中，结果与完整扫描一致：
+    // 上层目录被排除时其中的内容也被排除，.gitignore 逐级读取，下层的规则优先
+    fn accepts(&self, path: &Path, is_dir: bool) -> bool {
+        let relative = match path.strip_prefix(&self.root) {
+            Ok(relative) if !relative.as_os_str().is_empty() => relative,
+            _ => return false,
+        };
+
+        if self.config.max_depth.is_some_and(|depth| relative.components().count() > depth) {
+            return false;
+        }
+
+        if relative.components().any(|c| c.as_os_str() == ".git") {
+            return false;
+        }
+
+        let parents: Vec<&Path> = path.ancestors()
+            .skip(1)
+            .take_while(|dir| dir.starts_with(&self.root) && *dir != self.root)
+            .collect();
+        if self.overrides.matched(path, is_dir).is_ignore()
+            || parents.iter().any(|dir| self.overrides.matched(dir, true).is_ignore())
+        {
+            return false;
+        }
+
+        !self.config.respect_gitignore || !self.gitignored(path, is_dir)
+    }
+
+    // 从路径所在目录向上到根目录，第一个有匹配规则的 .gitignore（或 .ignore）决定结果
+    fn gitignored(&self, path: &Path, is_dir: bool) -> bool {
+        for dir in path.ancestors().skip(1).take_while(|dir| dir.starts_with(&self.root)) {
+            let mut builder = GitignoreBuilder::new(dir);
+            let mut found = false;
+            for name in [".gitignore", ".ignore"] {
+                let file = dir.join(name);
+                if file.is_file() {
+                    builder.add(file);
+                    found = true;
+                }
+            }
+            if !found {
+                continue;
+            }
+            let Ok(gitignore) = builder.build() else { continue };
+            match gitignore.matched_path_or_any_parents(path, is_dir) {
+                ignore::Match::Ignore(_) => return true,
+                ignore::Match::Whitelist(_) => return false,
+                ignore::Match::None => {}
+            }
+        }
+        false
+    }
+
+    // 用与完整扫描相同的规则遍历 start（根目录或其下的目录），不包含 start 本身
+    fn walk(&self, start: &Path) -> Vec<IndexedFile> {
+        let Ok(relative) = start.strip_prefix(&self.root) else { return Vec::new() };
+        let start_depth = relative.components().count();
+        if self.config.max_depth.is_some_and(|depth| start_depth >= depth) {
+            return Vec::new();
+        }
+
+        let respect_gitignore = self.config.respect_gitignore;
+        let walker = WalkBuilder::new(start)
+            .hidden(false)
+            .git_ignore(respect_gitignore)
+            .git_exclude(respect_gitignore)
+            .git_global(respect_gitignore)
+            .ignore(respect_gitignore)
+            .require_git(false)
+            .max_depth(self.config.max_depth.map(|depth| depth - start_depth))
+            .overrides(self.overrides.clone())
+            .filter_entry(|entry| entry.file_name() != ".git")
+            .build();
+
+        walker.flatten()
+            .filter(|entry| entry.depth() > 0)
+            .filter_map(|entry| {
+                let metadata = entry.metadata().ok()?;
+                to_indexed_file(entry.path(), &self.root, &metadata)
+            })
+            .collect()
+    }
+}
+
+// 索引状态统计
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexerStatus {
+    pub enabled: bool,
+    pub indexing: bool,
+    pub total_files: usize,
+    pub roots: usize,
+    pub watching: bool,
+    pub errors: Vec<String>,  // 最近的错误（无效的规则、无法监听的目录等）
+}
+
+struct IndexerState {
+    config: IndexerConfig,
+    files: HashMap<String, IndexedFile>,
+    index_path: Option<PathBuf>,
+    watcher: Option<RecommendedWatcher>,
+    indexing: bool,
+    dirty: bool,
+    errors: Vec<String>,
+    // 配置变化时递增，旧的后台任务据此退出
+    generation: u64,
+}
+
+#[derive(Clone)]
+pub struct FileIndexer {
+    state: Arc<Mutex<IndexerState>>,
+}
+
+impl FileIndexer {
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(IndexerState {
+                config: IndexerConfig::default(),
+                files: HashMap::new(),
+                index_path: None,
+                watcher: None,
+                indexing: false,
+                dirty: false,
+                errors: Vec::new(),
+                generation: 0,
+            })),
+        }
+    }
+
+    // 启动索引：加载磁盘上的索引，然后在后台重新扫描并开始监听文件变化
+    pub fn start(&self, cache_dir: &Path, config: IndexerConfig) -> Result<(), String> {
+        let index_path = cache_dir.join(INDEX_FILE_NAME);
+
+        {
+            let mut state = self.lock()?;
+            state.index_path = Some(index_path.clone());
+
+            if state.files.is_empty() {
+                if let Ok(content) = fs::read_to_string(&index_path) {
+                    if let Ok(files) = serde_json::from_str::<Vec<IndexedFile>>(&content) {
+                        state.files = files.into_iter().map(|f| (f.path.clone(), f)).collect();
+                    }
+                }
+            }
+        }
+
+        self.configure(config)
+    }
+
+    // 应用新的配置；根目录或规则变化时重新扫描
+    pub fn configure(&self, config: IndexerConfig) -> Result<(), String> {
+        self.apply_config(config, false)
+    }
+
+    // 强制重新扫描全部根目录
+    pub fn rebuild(&self) -> Result<(), String> {
+        let config = self.lock()?.config.clone();
+        self.apply_config(config, true)
+    }
+
+    fn apply_config(&self, config: IndexerConfig, force: bool) -> Result<(), String> {
+        // 先校验全部 glob，避免启动后才失败；错误同时记录在状态中
+        let filters = match build_filters(&config) {
+            Ok(filters) => filters,
+            Err(e) => {
+                self.lock()?.errors = vec![e.clone()];
+                return Err(e);
+            }
+        };
+
+        let generation = {
+            let mut state = self.lock()?;
+            let running = state.indexing || state.watcher.is_some();
+            let unchanged = state.config.enabled == config.enabled
+                && state.config.roots == config.roots
+                && (running || !config.enabled);
+            state.config = config.clone();
+
+            if unchanged && !force {
+                return Ok(());
+            }
+
+            state.generation += 1;
+            state.errors.clear();
+            state.watcher = None;
+            state.indexing = config.enabled;
+
+            if !config.enabled {
+                state.files.clear();
+                state.dirty = true;
+            }
+
+            state.generation
+        };
+
+        if !config.enabled {
+            self.save()?;
+            return Ok(());
+        }
+
+        let indexer = self.clone();
+        std::thread::spawn(move || {
+            indexer.run(generation, config, filters);
+        });
+
+        Ok(())
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, IndexerState>, String> {
+        self.state.lock().map_err(|e| format!("Failed to lock file indexer: {}", e))
+    }
+
+    fn is_current(&self, generation: u64) -> bool {
+        self.lock().map(|s| s.generation == generation).unwrap_or(false)
+    }
+
+    // 记录后台任务的错误，配置已变化时丢弃
+    fn record_error(&self, generation: u64, error: String) {
+        if let Ok(mut state) = self.lock() {
+            if state.generation == generation {
+                if state.errors.len() >= MAX_ERRORS {
+                    state.errors.remove(0);
+                }
+                state.errors.push(error);
+            }
+        }
+    }
+
+    // 后台任务：完整扫描一次，然后处理文件系统通知直到配置变化
+    fn run(&self, generation: u64, config: IndexerConfig, filters: Vec<RootFilter>) {
+        let (sender, receiver) = mpsc::channel::<notify::Result<notify::Event>>();
+        let watcher = notify::recommended_watcher(move |event| {
+            let _ = sender.send(event);
+        });
+
+        let mut watcher = match watcher {
+            Ok(watcher) => Some(watcher),
+            Err(e) => {
+                self.record_error(generation, format!("Failed to create file watcher: {}", e));
+                None
+            }
+        };
+
+        if let Some(watcher) = watcher.as_mut() {
+            for filter in filters.iter() {
+                if let Err(e) = watcher.watch(&filter.root, RecursiveMode::Recursive) {
+                    self.record_error(generation, format!("Failed to watch {}: {}", filter.root.display(), e));
+                }
+            }
+        }
+
+        // 先开始监听再扫描，扫描期间的变化不会丢失
+        let scanned = scan_roots(&config.roots);
+
+        match self.lock() {
+            Ok(mut state) if state.generation == generation => {
+                state.files = scanned;
+                state.indexing = false;
+                state.dirty = true;
+                state.watcher = watcher;
+            }
+            _ => return,
+        }
+        let _ = self.save();
+
+        loop {
+            match receiver.recv_timeout(SAVE_INTERVAL) {
+                Ok(Ok(event)) => self.apply_event(&event, &filters),
+                Ok(Err(e)) => self.record_error(generation, format!("File watcher error: {}", e)),
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            }
+
+            if !self.is_current(generation) {
+                break;
+            }
+
+            let dirty = self.lock().map(|s| s.dirty).unwrap_or(false);
+            if dirty {
+                let _ = self.save();
+            }
+        }
+    }
+
+    // 根据文件系统通知增量更新索引；读取元数据和遍历新目录在锁外进行
+    fn apply_event(&self, event: &notify::Event, filters: &[RootFilter]) {
+        let relevant = matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_));
+        if !relevant {
+            return;
+        }
+        // 新建或移入的目录只报告目录本身，其中的内容需要遍历
+        let appeared = matches!(event.kind, EventKind::Create(_) | EventKind::Modify(ModifyKind::Name(_)));
+
+        let mut removed = Vec::new();
+        let mut inserted = Vec::new();
+        for path in event.paths.iter() {
+            let filter = filters.iter().find(|f| path.starts_with(&f.root));
+            match (fs::metadata(path), filter) {
+                (Ok(metadata), Some(filter)) if filter.accepts(path, metadata.is_dir()) => {
+                    inserted.extend(to_indexed_file(path, &filter.root, &metadata));
+                    if appeared && metadata.is_dir() {
+                        inserted.extend(filter.walk(path));
+                    }
+                }
+                // 路径已不存在（删除或移走）或不再符合规则，连同其子项一起移除
+                _ => removed.push(path.to_string_lossy().to_string()),
+            }
+        }
+
+        let mut state = match self.lock() {
+            Ok(state) => state,
+            Err(_) => return,
+        };
+
+        for key in removed {
+            let prefix = format!("{}{}", key, std::path::MAIN_SEPARATOR);
+            state.files.retain(|p, _| p != &key && !p.starts_with(&prefix));
+        }
+        for file in inserted {
+            state.files.insert(file.path.clone(), file);
+        }
+
+        state.dirty = true;
+    }
+
+    // 将索引写入磁盘
+    pub fn save(&self) -> Result<(), String> {
+        let (path, files) = {
+            let mut state = self.lock()?;
+            let path = match state.index_path.clone() {
+                Some(path) => path,
+                None => return Ok(()),
+            };
+            state.dirty = false;
+            (path, state.files.values().cloned().collect::<Vec<_>>())
+        };
+
+        let content = serde_json::to_string(&files)
+            .map_err(|e| format!("Failed to serialize file index: {}", e))?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create cache directory: {}", e))?;
+        }
+
+        fs::write(&path, content)
+            .map_err(|e| format!("Failed to write file index: {}", e))
+    }
+
+    pub fn status(&self) -> Result<IndexerStatus, String> {
+        let state = self.lock()?;
+        Ok(IndexerStatus {
+            enabled: state.config.enabled,
+            indexing: state.indexing,
+            total_files: state.files.len(),
+            roots: state.config.roots.len(),
+            watching: state.watcher.is_some(),
+            errors: state.errors.clone(),
+        })
+    }
+
+    pub fn max_results(&self) -> usize {
+        self.lock().map(|s| s.config.max_results).unwrap_or(0)
+    }
+
+    pub fn get(&self, path: &str) -> Option<IndexedFile> {
+        self.lock().ok()?.files.get(path).cloned()
+    }
+
+    // 对索引中的文件调用回调，索引未启用时不做任何事
+    pub fn for_each<F: FnMut(&IndexedFile)>(&self, f: F) {
+        if let Ok(state) = self.lock() {
+            if state.config.enabled {
+                state.files.values().for_each(f);
+            }
+        }
+    }
+}
+
+// 校验索引配置中的全部 glob，保存配置前调用
+pub fn validate(config: &IndexerConfig) -> Result<(), String> {
+    build_filters(config).map(|_| ())
+}
+
+fn build_filters(config: &IndexerConfig) -> Result<Vec<RootFilter>, String> {
+    config.roots.iter()
+        .map(RootFilter::new)
+        .collect()
+}
+
+// 全局文件索引实例
+lazy_static::lazy_static! {
+    pub static ref GLOBAL_FILE_INDEXER: FileIndexer = FileIndexer::new();
+}
+
+fn to_indexed_file(path: &Path, root: &Path, metadata: &fs::Metadata) -> Option<IndexedFile> {
+    let name = path.file_name()?.to_string_lossy().to_string();
+    let modified = metadata.modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    Some(IndexedFile {
+        path: path.to_string_lossy().to_string(),
+        name,
+        root: root.to_string_lossy().to_string(),
+        is_dir: metadata.is_dir(),
+        modified,
+    })
+}
+
+// 完整扫描全部根目录
+fn scan_roots(roots: &[IndexRoot]) -> HashMap<String, IndexedFile> {
+    roots.iter()
+        .filter_map(|config| RootFilter::new(config).ok())
+        .flat_map(|filter| filter.walk(&filter.root))
+        .map(|file| (file.path.clone(), file))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{CreateKind, RemoveKind};
+    use std::collections::HashSet;
+
+    fn touch(path: &Path) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, b"").unwrap();
+    }
+
+    fn root_config(root: &Path) -> IndexRoot {
+        IndexRoot {
+            path: root.to_string_lossy().to_string(),
+            include: Vec::new(),
+            exclude: Vec::new(),
+            max_depth: None,
+            respect_gitignore: true,
+        }
+    }
+
+    // 根目录和 docs 下各有一个 .gitignore，docs 中的规则可以重新包含根目录排除的文件
+    fn layout() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::write(root.join(".gitignore"), "*.log\nbuild/\n").unwrap();
+        fs::create_dir_all(root.join("docs")).unwrap();
+        fs::write(root.join("docs/.gitignore"), "draft.md\n!keep.log\n").unwrap();
+        for file in [
+            "src/main.rs", "src/debug.log", "build/out.bin", "target/x.o", ".git/config",
+            "docs/readme.md", "docs/draft.md", "docs/keep.log", "docs/deep/a/b/c.txt",
+        ] {
+            touch(&root.join(file));
+        }
+        dir
+    }
+
+    fn all_paths(dir: &Path, paths: &mut Vec<(PathBuf, bool)>) {
+        for entry in fs::read_dir(dir).unwrap().flatten() {
+            let is_dir = entry.file_type().unwrap().is_dir();
+            paths.push((entry.path(), is_dir));
+            if is_dir {
+                all_paths(&entry.path(), paths);
+            }
+        }
+    }
+
+    fn accepted(filter: &RootFilter, relative: &str) -> bool {
+        let path = filter.root.join(relative);
+        filter.accepts(&path, path.is_dir())
+    }
+
+    #[test]
+    fn applies_nested_gitignore_files() {
+        let dir = layout();
+        let filter = RootFilter::new(&root_config(dir.path())).unwrap();
+
+        let cases = [
+            ("src/main.rs", true),
+            ("src/debug.log", false),
+            ("build", false),
+            ("build/out.bin", false),
+            ("docs/readme.md", true),
+            ("docs/draft.md", false),
+            ("docs/keep.log", true),
+            (".git", false),
+            (".git/config", false),
+            ("target/x.o", true),
+        ];
+        for (relative, expected) in cases {
+            assert_eq!(accepted(&filter, relative), expected, "{}", relative);
+        }
+        assert!(!filter.accepts(dir.path(), true));
+        assert!(!filter.accepts(Path::new("/elsewhere/file"), false));
+    }
+
+    #[test]
+    fn applies_patterns_depth_and_gitignore_switch() {
+        let dir = layout();
+        let mut config = root_config(dir.path());
+        config.exclude = vec!["target".to_string()];
+        config.max_depth = Some(3);
+        let filter = RootFilter::new(&config).unwrap();
+        assert!(!accepted(&filter, "target"));
+        assert!(!accepted(&filter, "target/x.o"));
+        assert!(accepted(&filter, "docs/deep/a"));
+        assert!(!accepted(&filter, "docs/deep/a/b"));
+
+        config.exclude.clear();
+        config.max_depth = None;
+        config.include = vec!["*.rs".to_string(), "*.md".to_string()];
+        let filter = RootFilter::new(&config).unwrap();
+        assert!(accepted(&filter, "src"));
+        assert!(accepted(&filter, "src/main.rs"));
+        assert!(!accepted(&filter, "docs/keep.log"));
+
+        config.include.clear();
+        config.respect_gitignore = false;
+        let filter = RootFilter::new(&config).unwrap();
+        assert!(accepted(&filter, "src/debug.log"));
+        assert!(accepted(&filter, "docs/draft.md"));
+        assert!(!accepted(&filter, ".git/config"));
+
+        config.include = vec!["[".to_string()];
+        assert!(RootFilter::new(&config).is_err());
+    }
+
+    #[test]
+    fn walking_agrees_with_single_path_checks() {
+        let dir = layout();
+        let configs = [
+            root_config(dir.path()),
+            IndexRoot { exclude: vec!["target".to_string()], max_depth: Some(3), ..root_config(dir.path()) },
+            IndexRoot { include: vec!["*.md".to_string()], respect_gitignore: false, ..root_config(dir.path()) },
+        ];
+        let mut paths = Vec::new();
+        all_paths(dir.path(), &mut paths);
+
+        for config in configs {
+            let filter = RootFilter::new(&config).unwrap();
+            let walked: HashSet<String> = filter.walk(&filter.root).into_iter().map(|f| f.path).collect();
+            let expected: HashSet<String> = paths.iter()
+                .filter(|(path, is_dir)| filter.accepts(path, *is_dir))
+                .map(|(path, _)| path.to_string_lossy().to_string())
+                .collect();
+            assert_eq!(walked, expected, "{:?}", config);
+        }
+    }
+
+    #[test]
+    fn indexes_directories_moved_into_a_root() {
+        let dir = layout();
+        let outside = tempfile::tempdir().unwrap();
+        touch(&outside.path().join("project/lib/util.rs"));
+        touch(&outside.path().join("project/trace.log"));
+
+        let filters = vec![RootFilter::new(&root_config(dir.path())).unwrap()];
+        let indexer = FileIndexer::new();
+        let moved = dir.path().join("project");
+        fs::rename(outside.path().join("project"), &moved).unwrap();
+        indexer.apply_event(&notify::Event::new(EventKind::Create(CreateKind::Folder)).add_path(moved.clone()), &filters);
+
+        let indexed = |relative: &str| indexer.get(&moved.join(relative).to_string_lossy()).is_some();
+        assert!(indexer.get(&moved.to_string_lossy()).is_some());
+        assert!(indexed("lib"));
+        assert!(indexed("lib/util.rs"));
+        assert!(!indexed("trace.log"));
+
+        fs::remove_dir_all(&moved).unwrap();
+        indexer.apply_event(&notify::Event::new(EventKind::Remove(RemoveKind::Folder)).add_path(moved.clone()), &filters);
+        assert!(indexer.get(&moved.to_string_lossy()).is_none());
+        assert!(!indexed("lib/util.rs"));
+    }
+}
@@ -0,0 +1,175 @@
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+// 文件指纹，文本形式为 <种类>:<文件大小>:<十六进制摘要>，可以保存到数据文件和缓存键中
+//   meta     路径、大小和修改时间的 SHA-256，不读取内容，用于判断文件是否变化（图标缓存）
+//   partial  大小和文件头、中、尾各一段内容的 SHA-256，用于大文件
+//   sha256   全部内容的 SHA-256
+// 内容指纹不含路径，文件被移动或复制后仍然相同，用于完整性检查和查找被移动的目标
+// 摘要都使用 SHA-256，不随 Rust 版本变化
+
+// 超过该大小的文件默认只计算部分内容
+const PARTIAL_THRESHOLD: u64 = 8 * 1024 * 1024;
+const PARTIAL_SAMPLE: u64 = 64 * 1024;
+// 元数据指纹只保留前 32 位十六进制（128 位），缓存文件名不至于过长
+const META_DIGEST_LEN: usize = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FingerprintKind {
+    Meta,
+    Partial,
+    Sha256,
+}
+
+impl FingerprintKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            FingerprintKind::Meta => "meta",
+            FingerprintKind::Partial => "partial",
+            FingerprintKind::Sha256 => "sha256",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "meta" => Some(FingerprintKind::Meta),
+            "partial" => Some(FingerprintKind::Partial),
+            "sha256" => Some(FingerprintKind::Sha256),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Fingerprint {
+    pub kind: FingerprintKind,
+    pub size: u64,
+    pub digest: String,
+}
+
+impl Fingerprint {
+    // 解析文本形式；无法识别的旧格式返回 None
+    pub fn parse(value: &str) -> Option<Self> {
+        let mut parts = value.splitn(3, ':');
+        let kind = FingerprintKind::parse(parts.next()?)?;
+        let size = parts.next()?.parse().ok()?;
+        let digest = parts.next()?.to_string();
+        (!digest.is_empty() && digest.chars().all(|c| c.is_ascii_hexdigit()))
+            .then_some(Self { kind, size, digest })
+    }
+
+    // 文件当前是否与该指纹一致，按同一种类重新计算
+    pub fn matches(&self, path: &Path) -> bool {
+        let same_size = fs::metadata(path).is_ok_and(|m| m.len() == self.size);
+        same_size && compute(path, self.kind).is_ok_and(|current| &current == self)
+    }
+}
+
+impl std::fmt::Display for Fingerprint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{}", self.kind.as_str(), self.size, self.digest)
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// 路径、大小和修改时间（精确到纳秒）的指纹，目录也可以计算
+pub fn metadata(path: &Path) -> Result<Fingerprint, String> {
+    let metadata = fs::metadata(path)
+        .map_err(|e| format!("Failed to get file metadata: {}", e))?;
+    let modified = metadata.modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .unwrap_or_default();
+
+    let mut hasher = Sha256::new();
+    hasher.update(path.to_string_lossy().as_bytes());
+    hasher.update([0u8]);
+    hasher.update(metadata.len().to_le_bytes());
+    hasher.update(modified.as_secs().to_le_bytes());
+    hasher.update(modified.subsec_nanos().to_le_bytes());
+    hasher.update([metadata.is_dir() as u8]);
+
+    let mut digest = hex(&hasher.finalize());
+    digest.truncate(META_DIGEST_LEN);
+    Ok(Fingerprint { kind: FingerprintKind::Meta, size: metadata.len(), digest })
+}
+
+// 内容指纹：小文件计算全部内容，大文件只计算部分内容
+pub fn content(path: &Path) -> Result<Fingerprint, String> {
+    let size = fs::metadata(path)
+        .map_err(|e| format!("Failed to get file metadata: {}", e))?
+        .len();
+    let kind = if size > PARTIAL_THRESHOLD { FingerprintKind::Partial } else { FingerprintKind::Sha256 };
+    compute(path, kind)
+}
+
+// 按指定种类计算
+pub fn compute(path: &Path, kind: FingerprintKind) -> Result<Fingerprint, String> {
+    match kind {
+        FingerprintKind::Meta => metadata(path),
+        FingerprintKind::Partial => partial_hash(path),
+        FingerprintKind::Sha256 => full_hash(path),
+    }
+}
+
+fn open_file(path: &Path) -> Result<(fs::File, u64), String> {
+    let file = fs::File::open(path)
+        .map_err(|e| format!("Failed to open file: {}", e))?;
+    let metadata = file.metadata()
+        .map_err(|e| format!("Failed to get file metadata: {}", e))?;
+    if !metadata.is_file() {
+        return Err("Not a regular file".to_string());
+    }
+    Ok((file, metadata.len()))
+}
+
+fn full_hash(path: &Path) -> Result<Fingerprint, String> {
+    let (mut file, size) = open_file(path)?;
+
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; PARTIAL_SAMPLE as usize];
+    loop {
+        let read = file.read(&mut buffer)
+            .map_err(|e| format!("Failed to read file: {}", e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(Fingerprint { kind: FingerprintKind::Sha256, size, digest: hex(&hasher.finalize()) })
+}
+
+// 文件大小加上头、中、尾三段内容；文件不大于三段之和时等同于读取全部内容
+fn partial_hash(path: &Path) -> Result<Fingerprint, String> {
+    let (mut file, size) = open_file(path)?;
+
+    let mut hasher = Sha256::new();
+    hasher.update(size.to_le_bytes());
+
+    let sample = PARTIAL_SAMPLE.min(size);
+    let offsets = if size <= PARTIAL_SAMPLE * 3 {
+        vec![(0, size)]
+    } else {
+        vec![(0, sample), ((size - sample) / 2, sample), (size - sample, sample)]
+    };
+
+    let mut buffer = Vec::new();
+    for (offset, length) in offsets {
+        buffer.resize(length as usize, 0);
+        file.seek(SeekFrom::Start(offset))
+            .and_then(|_| file.read_exact(&mut buffer))
+            .map_err(|e| format!("Failed to read file: {}", e))?;
+        hasher.update(&buffer);
+    }
+
+    Ok(Fingerprint { kind: FingerprintKind::Partial, size, digest: hex(&hasher.finalize()) })
+}
@@ -0,0 +1,164 @@
+use crate::models::{AppData, Shortcut};
+use chrono::{DateTime, Utc};
+
+// 半衰期：每过 14 天，一次启动的权重减半
+const HALF_LIFE_DAYS: f64 = 14.0;
+
+// 每次启动增加的分值
+const LAUNCH_WEIGHT: f64 = 1.0;
+
+fn days_between(from: DateTime<Utc>, to: DateTime<Utc>) -> f64 {
+    (to - from).num_seconds().max(0) as f64 / 86_400.0
+}
+
+// 经过指定天数后的衰减系数
+fn decay(days: f64) -> f64 {
+    0.5f64.powf(days / HALF_LIFE_DAYS)
+}
+
+// 计算快捷方式在指定时刻的频率分值（frecency）
+pub fn score_at(shortcut: &Shortcut, now: DateTime<Utc>) -> f64 {
+    match shortcut.frecency_updated_at {
+        Some(updated_at) => shortcut.frecency * decay(days_between(updated_at, now)),
+        None => 0.0,
+    }
+}
+
+// 记录一次启动：先把旧分值衰减到当前时刻，再加上本次启动的权重
+pub fn record_launch(shortcut: &mut Shortcut, now: DateTime<Utc>) {
+    shortcut.frecency = score_at(shortcut, now) + LAUNCH_WEIGHT;
+    shortcut.frecency_updated_at = Some(now);
+}
+
+// 根据旧数据的 usage_count 估算初始分值：
+// 假设历史启动均匀分布在创建时间和最后使用时间之间，对每次启动的衰减求平均
+fn estimate_from_usage(shortcut: &Shortcut, now: DateTime<Utc>) -> f64 {
+    let count = shortcut.usage_count as f64;
+    let last_used = match shortcut.last_used {
+        Some(last_used) => last_used,
+        None => return 0.0,
+    };
+
+    let first_used = shortcut.created_at.min(last_used);
+    let span_days = days_between(first_used, last_used);
+    let newest = decay(days_between(last_used, now));
+
+    if span_days < 1.0 {
+        return count * LAUNCH_WEIGHT * newest;
+    }
+
+    let oldest = decay(days_between(first_used, now));
+    let average = (newest - oldest) * HALF_LIFE_DAYS / (std::f64::consts::LN_2 * span_days);
+
+    count * LAUNCH_WEIGHT * average
+}
+
+// 为尚未记录频率分值的快捷方式补齐数据，返回是否有修改
+pub fn migrate(data: &mut AppData) -> bool {
+    let now = Utc::now();
+    let mut changed = false;
+
+    for shortcut in data.shortcuts.iter_mut() {
+        if shortcut.frecency_updated_at.is_none() && shortcut.usage_count > 0 {
+            shortcut.frecency = estimate_from_usage(shortcut, now);
+            shortcut.frecency_updated_at = Some(now);
+            changed = true;
+        }
+    }
+
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn shortcut() -> Shortcut {
+        Shortcut::new("app".to_string(), "/usr/bin/app".to_string(), None)
+    }
+
+    fn close(actual: f64, expected: f64) -> bool {
+        (actual - expected).abs() < 1e-6
+    }
+
+    #[test]
+    fn halves_the_score_every_half_life() {
+        let now = Utc::now();
+        let mut shortcut = shortcut();
+        assert_eq!(score_at(&shortcut, now), 0.0);
+
+        shortcut.frecency = 8.0;
+        shortcut.frecency_updated_at = Some(now);
+        assert!(close(score_at(&shortcut, now), 8.0));
+        assert!(close(score_at(&shortcut, now + Duration::days(14)), 4.0));
+        assert!(close(score_at(&shortcut, now + Duration::days(28)), 2.0));
+        assert!(close(score_at(&shortcut, now + Duration::days(7)), 8.0 / 2f64.sqrt()));
+        // 时钟回拨时不增长
+        assert!(close(score_at(&shortcut, now - Duration::days(3)), 8.0));
+    }
+
+    #[test]
+    fn accumulates_decayed_launches() {
+        let start = Utc::now();
+        let mut shortcut = shortcut();
+
+        record_launch(&mut shortcut, start);
+        assert!(close(shortcut.frecency, 1.0));
+        record_launch(&mut shortcut, start);
+        assert!(close(shortcut.frecency, 2.0));
+
+        let later = start + Duration::days(14);
+        record_launch(&mut shortcut, later);
+        assert!(close(shortcut.frecency, 2.0));
+        assert_eq!(shortcut.frecency_updated_at, Some(later));
+
+        // 最近常用的快捷方式超过很久以前更常用的
+        let mut old_favorite = self::shortcut();
+        for _ in 0..10 {
+            record_launch(&mut old_favorite, start - Duration::days(90));
+        }
+        assert!(score_at(&shortcut, later) > score_at(&old_favorite, later));
+    }
+
+    #[test]
+    fn migrates_legacy_usage_counts() {
+        let now = Utc::now();
+        let mut recent = shortcut();
+        recent.usage_count = 6;
+        recent.last_used = Some(now);
+        recent.created_at = now - Duration::hours(2);
+
+        let mut spread = shortcut();
+        spread.usage_count = 6;
+        spread.last_used = Some(now - Duration::days(14));
+        spread.created_at = now - Duration::days(42);
+
+        let mut unused = shortcut();
+        unused.usage_count = 0;
+
+        let mut already = shortcut();
+        already.usage_count = 50;
+        already.last_used = Some(now);
+        already.frecency = 3.0;
+        already.frecency_updated_at = Some(now - Duration::days(1));
+
+        let mut data = AppData {
+            shortcuts: vec![recent, spread, unused, already.clone()],
+            ..AppData::default()
+        };
+        assert!(migrate(&mut data));
+
+        let [recent, spread, unused, migrated_already] = &data.shortcuts[..] else { panic!() };
+        // 同一天内的启动不衰减
+        assert!((recent.frecency - 6.0).abs() < 0.01, "{}", recent.frecency);
+        // 均匀分布在 14 到 42 天前：介于最旧和最新一次启动的衰减之间
+        assert!(spread.frecency > 6.0 * 0.25 && spread.frecency < 6.0 * 0.5, "{}", spread.frecency);
+        assert!(spread.frecency_updated_at.is_some());
+        assert!(unused.frecency_updated_at.is_none());
+        assert_eq!(migrated_already.frecency, already.frecency);
+        assert_eq!(migrated_already.frecency_updated_at, already.frecency_updated_at);
+
+        assert!(!migrate(&mut data));
+    }
+}
@@ -0,0 +1,404 @@
+use crate::commands::DataManagerState;
+use crate::fingerprint::{Fingerprint, FingerprintKind};
+use crate::models::ShortcutHealthEvent;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+// 后台按 AdvancedConfig.file_check_interval 定期检查快捷方式的目标是否存在：
+//   本地路径逐个检查，每次检查之间稍作停顿，避免集中访问磁盘
+//   网络和可移动设备上的路径在单独的线程中检查，超时视为未知，不改变状态；
+//   同一挂载点超时一次后，本轮不再检查其上的其他路径
+// 本地文件没有内容指纹或大小已变化时重新计算指纹，目标丢失后用于查找被移动的文件
+// 状态变化时更新 file_exists 和 missing_since，并发送事件：
+//   shortcut-broken     目标从存在变为不存在
+//   shortcut-recovered  目标重新出现
+pub const BROKEN_EVENT: &str = "shortcut-broken";
+pub const RECOVERED_EVENT: &str = "shortcut-recovered";
+
+const CHECK_DELAY: Duration = Duration::from_millis(20);
+const SLOW_CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+
+// 检查状态
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthMonitorStatus {
+    pub interval: u64,
+    pub last_check: Option<DateTime<Utc>>,  // 最近一轮检查完成的时间
+    pub last_error: Option<String>,         // 最近一轮检查失败的原因，成功后清除
+}
+
+struct MonitorState {
+    app_handle: Option<AppHandle>,
+    interval: u64,  // 秒，0 表示关闭
+    // 间隔变化时递增，旧的后台任务据此退出
+    generation: u64,
+    check_requested: bool,
+    last_check: Option<DateTime<Utc>>,
+    last_error: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct HealthMonitor {
+    state: Arc<Mutex<MonitorState>>,
+    wake: Arc<Condvar>,
+}
+
+impl HealthMonitor {
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(MonitorState {
+                app_handle: None,
+                interval: 0,
+                generation: 0,
+                check_requested: false,
+                last_check: None,
+                last_error: None,
+            })),
+            wake: Arc::new(Condvar::new()),
+        }
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, MonitorState>, String> {
+        self.state.lock().map_err(|e| format!("Failed to lock health monitor: {}", e))
+    }
+
+    // 启动后台检查，启动时立即检查一轮
+    pub fn start(&self, app_handle: AppHandle, interval: u32) -> Result<(), String> {
+        self.lock()?.app_handle = Some(app_handle);
+        self.configure(interval)
+    }
+
+    // 修改检查间隔，变化时重新启动后台任务
+    pub fn configure(&self, interval: u32) -> Result<(), String> {
+        let generation = {
+            let mut state = self.lock()?;
+            if state.app_handle.is_none() || (state.interval == interval as u64 && state.generation > 0) {
+                state.interval = interval as u64;
+                return Ok(());
+            }
+            state.interval = interval as u64;
+            state.generation += 1;
+            state.generation
+        };
+        self.wake.notify_all();
+
+        if interval > 0 {
+            let monitor = self.clone();
+            std::thread::spawn(move || {
+                monitor.run(generation);
+            });
+        }
+
+        Ok(())
+    }
+
+    // 不等到下一个间隔，立即检查一轮
+    pub fn check_now(&self) -> Result<(), String> {
+        self.lock()?.check_requested = true;
+        self.wake.notify_all();
+        Ok(())
+    }
+
+    pub fn status(&self) -> Result<HealthMonitorStatus, String> {
+        let state = self.lock()?;
+        Ok(HealthMonitorStatus {
+            interval: state.interval,
+            last_check: state.last_check,
+            last_error: state.last_error.clone(),
+        })
+    }
+
+    fn is_current(&self, generation: u64) -> bool {
+        self.lock().map(|s| s.generation == generation).unwrap_or(false)
+    }
+
+    fn run(&self, generation: u64) {
+        loop {
+            let Some(app_handle) = self.lock().ok().and_then(|s| s.app_handle.clone()) else { return };
+            let result = self.sweep(&app_handle, generation);
+            if let Ok(mut state) = self.lock() {
+                match result {
+                    Ok(()) => {
+                        state.last_check = Some(Utc::now());
+                        state.last_error = None;
+                    }
+                    Err(e) => state.last_error = Some(format!("Failed to check shortcut targets: {}", e)),
+                }
+            }
+
+            // 等待下一个间隔，期间可以被 check_now 或配置变化唤醒
+            let Ok(mut state) = self.lock() else { return };
+            let timeout = Duration::from_secs(state.interval);
+            while !state.check_requested && state.generation == generation {
+                let (guard, result) = match self.wake.wait_timeout(state, timeout) {
+                    Ok(waited) => waited,
+                    Err(_) => return,
+                };
+                state = guard;
+                if result.timed_out() {
+                    break;
+                }
+            }
+            if state.generation != generation {
+                return;
+            }
+            state.check_requested = false;
+        }
+    }
+
+    // 检查一轮：先取出目标列表再逐个检查，检查期间不占用数据锁
+    fn sweep(&self, app_handle: &AppHandle, generation: u64) -> Result<(), String> {
+        let data_state = app_handle.state::<DataManagerState>();
+        let targets: Vec<(String, String, Option<String>)> = {
+            let mut manager_guard = data_state.lock().map_err(|e| format!("Failed to lock state: {}", e))?;
+            let Some(manager) = manager_guard.as_mut() else { return Ok(()) };
+            manager.get_data()?.shortcuts.iter()
+                .filter(|s| !crate::utils::is_url(&s.file_path))
+                .map(|s| (s.id.clone(), s.file_path.clone(), s.target_fingerprint.clone()))
+                .collect()
+        };
+
+        let mounts = MountTable::load();
+        let mut unreachable: HashSet<PathBuf> = HashSet::new();
+        let mut results = Vec::with_capacity(targets.len());
+        let mut fingerprints = Vec::new();
+
+        for (id, file_path, fingerprint) in targets {
+            if !self.is_current(generation) {
+                return Ok(());
+            }
+
+            let exists = match mounts.slow_mount(Path::new(&file_path)) {
+                None => {
+                    // 慢速挂载点上的文件不计算指纹
+                    let metadata = std::fs::metadata(&file_path).ok();
+                    let stale = fingerprint.as_deref()
+                        .and_then(Fingerprint::parse)
+                        .is_none_or(|f| f.kind == FingerprintKind::Meta || metadata.as_ref().is_some_and(|m| m.len() != f.size));
+                    if stale && metadata.as_ref().is_some_and(|m| m.is_file()) {
+                        if let Ok(fingerprint) = crate::fingerprint::content(Path::new(&file_path)) {
+                            fingerprints.push((id.clone(), fingerprint.to_string()));
+                        }
+                    }
+                    Some(metadata.is_some())
+                }
+                Some(mount) if unreachable.contains(&mount) => None,
+                Some(mount) => {
+                    let exists = exists_with_timeout(&file_path);
+                    if exists.is_none() {
+                        unreachable.insert(mount);
+                    }
+                    exists
+                }
+            };
+
+            if let Some(exists) = exists {
+                results.push((id, exists));
+            }
+            std::thread::sleep(CHECK_DELAY);
+        }
+
+        let changes = {
+            let mut manager_guard = data_state.lock().map_err(|e| format!("Failed to lock state: {}", e))?;
+            let Some(manager) = manager_guard.as_mut() else { return Ok(()) };
+            manager.record_target_fingerprints(&fingerprints)?;
+            manager.update_target_status(&results)?
+        };
+
+        for shortcut in changes {
+            let event = if shortcut.file_exists { RECOVERED_EVENT } else { BROKEN_EVENT };
+            let _ = app_handle.emit(event, ShortcutHealthEvent {
+                shortcut_id: shortcut.id,
+                name: shortcut.name,
+                file_path: shortcut.file_path,
+                file_exists: shortcut.file_exists,
+                missing_since: shortcut.missing_since,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+// 在单独的线程中检查，超时返回 None；超时的线程会在系统调用返回后自行结束
+fn exists_with_timeout(file_path: &str) -> Option<bool> {
+    let (sender, receiver) = mpsc::channel();
+    let path = file_path.to_string();
+    std::thread::spawn(move || {
+        let _ = sender.send(Path::new(&path).exists());
+    });
+    receiver.recv_timeout(SLOW_CHECK_TIMEOUT).ok()
+}
+
+// 挂载信息，用于判断路径是否位于网络或可移动设备上，每轮检查读取一次
+struct MountTable {
+    #[cfg(target_os = "linux")]
+    slow_mounts: Vec<PathBuf>,
+}
+
+impl MountTable {
+    #[cfg(target_os = "linux")]
+    fn load() -> Self {
+        Self::parse(&std::fs::read_to_string("/proc/mounts").unwrap_or_default())
+    }
+
+    // 解析 /proc/mounts 的内容：网络文件系统和可移动设备的常见挂载目录视为慢速挂载点
+    #[cfg(target_os = "linux")]
+    fn parse(content: &str) -> Self {
+        const SLOW_FILESYSTEMS: &[&str] = &[
+            "nfs", "nfs4", "cifs", "smb3", "smbfs", "sshfs", "fuse.sshfs", "davfs", "fuse.davfs2",
+            "fuse.rclone", "fuse.gvfsd-fuse", "9p", "afs", "ceph", "glusterfs", "fuse.glusterfs",
+        ];
+        const REMOVABLE_ROOTS: &[&str] = &["/media/", "/run/media/", "/mnt/"];
+
+        let slow_mounts = content.lines()
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                let mount_point = unescape_mount_path(fields.nth(1)?);
+                let fs_type = fields.next()?;
+                let removable = REMOVABLE_ROOTS.iter().any(|root| mount_point.starts_with(root));
+                (SLOW_FILESYSTEMS.contains(&fs_type) || removable).then(|| PathBuf::from(mount_point))
+            })
+            .collect();
+
+        Self { slow_mounts }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn load() -> Self {
+        Self {}
+    }
+
+    // 路径所在的慢速挂载点，本地路径返回 None
+    #[cfg(target_os = "linux")]
+    fn slow_mount(&self, path: &Path) -> Option<PathBuf> {
+        self.slow_mounts.iter()
+            .filter(|mount| path.starts_with(mount))
+            .max_by_key(|mount| mount.as_os_str().len())
+            .cloned()
+    }
+
+    // macOS 上外置磁盘和网络共享都挂载在 /Volumes 下
+    #[cfg(target_os = "macos")]
+    fn slow_mount(&self, path: &Path) -> Option<PathBuf> {
+        let mut components = path.components();
+        let is_volume = components.next() == Some(std::path::Component::RootDir)
+            && components.next().is_some_and(|c| c.as_os_str() == "Volumes");
+        let name = components.next()?;
+        is_volume.then(|| Path::new("/Volumes").join(name))
+    }
+
+    // UNC 路径和网络、可移动驱动器
+    #[cfg(target_os = "windows")]
+    fn slow_mount(&self, path: &Path) -> Option<PathBuf> {
+        use std::ffi::OsStr;
+        use std::os::windows::ffi::OsStrExt;
+        use std::path::{Component, Prefix};
+        use winapi::um::fileapi::GetDriveTypeW;
+        use winapi::um::winbase::{DRIVE_CDROM, DRIVE_REMOTE, DRIVE_REMOVABLE};
+
+        let Some(Component::Prefix(prefix)) = path.components().next() else { return None };
+        match prefix.kind() {
+            Prefix::UNC(..) | Prefix::VerbatimUNC(..) => Some(PathBuf::from(prefix.as_os_str())),
+            Prefix::Disk(_) | Prefix::VerbatimDisk(_) => {
+                let root: Vec<u16> = OsStr::new(&format!("{}\\", prefix.as_os_str().to_string_lossy()))
+                    .encode_wide()
+                    .chain(std::iter::once(0))
+                    .collect();
+                let drive_type = unsafe { GetDriveTypeW(root.as_ptr()) };
+                matches!(drive_type, DRIVE_REMOTE | DRIVE_REMOVABLE | DRIVE_CDROM)
+                    .then(|| PathBuf::from(prefix.as_os_str()))
+            }
+            _ => None,
+        }
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+    fn slow_mount(&self, _path: &Path) -> Option<PathBuf> {
+        None
+    }
+}
+
+// /proc/mounts 中的空白和反斜杠写成三位八进制转义，例如 \040 表示空格
+#[cfg(target_os = "linux")]
+fn unescape_mount_path(field: &str) -> String {
+    let bytes = field.as_bytes();
+    let mut unescaped = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escape = bytes.get(index + 1..index + 4)
+            .filter(|digits| bytes[index] == b'\\' && digits.iter().all(|d| (b'0'..=b'7').contains(d)))
+            .and_then(|digits| u8::from_str_radix(std::str::from_utf8(digits).ok()?, 8).ok());
+        match escape {
+            Some(byte) => {
+                unescaped.push(byte);
+                index += 4;
+            }
+            None => {
+                unescaped.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&unescaped).into_owned()
+}
+
+lazy_static::lazy_static! {
+    pub static ref GLOBAL_HEALTH_MONITOR: HealthMonitor = HealthMonitor::new();
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    const MOUNTS: &str = "\
+/dev/nvme0n1p2 / ext4 rw,relatime 0 0
+proc /proc proc rw,nosuid,nodev,noexec,relatime 0 0
+server:/export /srv/nfs nfs4 rw,relatime 0 0
+//nas/share /home/user/NAS\\040Drive cifs rw 0 0
+/dev/sdb1 /media/user/USB\\040Stick vfat rw 0 0
+/dev/sdc1 /run/media/user/Backup ext4 rw 0 0
+/dev/sdd1 /data ext4 rw 0 0
+user@host:/ /home/user/remote fuse.sshfs rw 0 0
+broken-line
+";
+
+    #[test]
+    fn unescapes_octal_sequences() {
+        assert_eq!(unescape_mount_path("/media/USB\\040Stick"), "/media/USB Stick");
+        assert_eq!(unescape_mount_path("/a\\011b\\134c"), "/a\tb\\c");
+        // 不完整或非八进制的转义原样保留
+        assert_eq!(unescape_mount_path("/a\\04"), "/a\\04");
+        assert_eq!(unescape_mount_path("/a\\089"), "/a\\089");
+        assert_eq!(unescape_mount_path("/plain"), "/plain");
+    }
+
+    #[test]
+    fn classifies_slow_mounts() {
+        let table = MountTable::parse(MOUNTS);
+        let slow = |path: &str| table.slow_mount(Path::new(path)).map(|p| p.to_string_lossy().to_string());
+
+        assert_eq!(slow("/srv/nfs/tools/app"), Some("/srv/nfs".to_string()));
+        assert_eq!(slow("/home/user/NAS Drive/app.exe"), Some("/home/user/NAS Drive".to_string()));
+        assert_eq!(slow("/media/user/USB Stick/tool"), Some("/media/user/USB Stick".to_string()));
+        assert_eq!(slow("/run/media/user/Backup"), Some("/run/media/user/Backup".to_string()));
+        assert_eq!(slow("/home/user/remote/bin/tool"), Some("/home/user/remote".to_string()));
+
+        assert_eq!(slow("/usr/bin/app"), None);
+        assert_eq!(slow("/data/app"), None);
+        assert_eq!(slow("/home/user/NAS"), None);
+        assert_eq!(slow("/srv/nfs2/app"), None);
+    }
+
+    #[test]
+    fn prefers_the_deepest_slow_mount() {
+        let table = MountTable::parse("/dev/sdb1 /mnt/disk ext4 rw 0 0\nserver:/x /mnt/disk/share nfs rw 0 0\n");
+        assert_eq!(table.slow_mount(Path::new("/mnt/disk/share/app")), Some(PathBuf::from("/mnt/disk/share")));
+        assert_eq!(table.slow_mount(Path::new("/mnt/disk/app")), Some(PathBuf::from("/mnt/disk")));
+    }
+}
@@ -0,0 +1,399 @@
+use crate::models::{HotkeyConfig, HotkeyState, HotkeyStatus};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Modifiers, Shortcut, ShortcutEvent, ShortcutState};
+
+// 快捷键：
+//   global_hotkey  注册为系统全局快捷键，按下时显示并聚焦启动器窗口，窗口已在前台时隐藏
+//   quick_search   窗口内的快捷键，只校验格式和冲突，由界面处理
+// 配置变化时重新注册；格式错误、冲突和注册失败通过 hotkey-status 事件报告给界面
+// 全局快捷键显示窗口后发送 global-hotkey-activated，界面据此聚焦搜索框
+pub const STATUS_EVENT: &str = "hotkey-status";
+pub const ACTIVATED_EVENT: &str = "global-hotkey-activated";
+
+const MAIN_WINDOW: &str = "main";
+
+// 解析后的快捷键，例如 "ctrl + space" → Ctrl+Space
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Accelerator {
+    modifiers: Modifiers,
+    key: Code,
+    key_name: String,
+}
+
+impl Accelerator {
+    pub fn parse(accelerator: &str) -> Result<Self, String> {
+        let parts: Vec<&str> = accelerator.split('+').map(|part| part.trim()).collect();
+        if accelerator.trim().is_empty() {
+            return Err("Hotkey is empty".to_string());
+        }
+        if parts.iter().any(|part| part.is_empty()) {
+            return Err(format!("Invalid hotkey '{}': empty key between '+'", accelerator));
+        }
+
+        let mut modifiers = Modifiers::empty();
+        let mut key: Option<(Code, String)> = None;
+        for part in parts {
+            if let Some(modifier) = parse_modifier(part) {
+                if modifiers.contains(modifier) {
+                    return Err(format!("Invalid hotkey '{}': modifier '{}' is repeated", accelerator, part));
+                }
+                modifiers |= modifier;
+                continue;
+            }
+
+            let parsed = parse_key(part)
+                .ok_or_else(|| format!("Invalid hotkey '{}': unknown key '{}'", accelerator, part))?;
+            if let Some((_, existing)) = &key {
+                return Err(format!("Invalid hotkey '{}': only one key is allowed besides modifiers, found '{}' and '{}'", accelerator, existing, part));
+            }
+            key = Some(parsed);
+        }
+
+        let (key, key_name) = key
+            .ok_or_else(|| format!("Invalid hotkey '{}': missing a key after the modifiers", accelerator))?;
+        Ok(Self { modifiers, key, key_name })
+    }
+
+    // 全局快捷键必须带修饰键（F1-F24 除外），否则会占用普通按键
+    fn check_global(&self) -> Result<(), String> {
+        let function_key = self.key_name.len() > 1 && self.key_name.starts_with('F')
+            && self.key_name[1..].chars().all(|c| c.is_ascii_digit());
+        if self.modifiers.is_empty() && !function_key {
+            return Err(format!("Global hotkey '{}' needs at least one modifier (Ctrl, Alt, Shift or Super)", self));
+        }
+        Ok(())
+    }
+
+    fn shortcut(&self) -> Shortcut {
+        Shortcut::new((!self.modifiers.is_empty()).then_some(self.modifiers), self.key)
+    }
+}
+
+impl std::fmt::Display for Accelerator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let names = [
+            (Modifiers::CONTROL, "Ctrl"),
+            (Modifiers::ALT, "Alt"),
+            (Modifiers::SHIFT, "Shift"),
+            (Modifiers::SUPER, "Super"),
+        ];
+        for (modifier, name) in names {
+            if self.modifiers.contains(modifier) {
+                write!(f, "{}+", name)?;
+            }
+        }
+        write!(f, "{}", self.key_name)
+    }
+}
+
+fn parse_modifier(name: &str) -> Option<Modifiers> {
+    match name.to_lowercase().as_str() {
+        "ctrl" | "control" => Some(Modifiers::CONTROL),
+        "alt" | "option" => Some(Modifiers::ALT),
+        "shift" => Some(Modifiers::SHIFT),
+        "super" | "meta" | "cmd" | "command" | "win" => Some(Modifiers::SUPER),
+        // macOS 上为 Command，其他平台为 Ctrl
+        "cmdorctrl" | "commandorcontrol" => Some(if cfg!(target_os = "macos") { Modifiers::SUPER } else { Modifiers::CONTROL }),
+        _ => None,
+    }
+}
+
+// 按键及其规范名称
+fn parse_key(name: &str) -> Option<(Code, String)> {
+    const LETTERS: [Code; 26] = [
+        Code::KeyA, Code::KeyB, Code::KeyC, Code::KeyD, Code::KeyE, Code::KeyF, Code::KeyG,
+        Code::KeyH, Code::KeyI, Code::KeyJ, Code::KeyK, Code::KeyL, Code::KeyM, Code::KeyN,
+        Code::KeyO, Code::KeyP, Code::KeyQ, Code::KeyR, Code::KeyS, Code::KeyT, Code::KeyU,
+        Code::KeyV, Code::KeyW, Code::KeyX, Code::KeyY, Code::KeyZ,
+    ];
+    const DIGITS: [Code; 10] = [
+        Code::Digit0, Code::Digit1, Code::Digit2, Code::Digit3, Code::Digit4,
+        Code::Digit5, Code::Digit6, Code::Digit7, Code::Digit8, Code::Digit9,
+    ];
+    const FUNCTION_KEYS: [Code; 24] = [
+        Code::F1, Code::F2, Code::F3, Code::F4, Code::F5, Code::F6, Code::F7, Code::F8,
+        Code::F9, Code::F10, Code::F11, Code::F12, Code::F13, Code::F14, Code::F15, Code::F16,
+        Code::F17, Code::F18, Code::F19, Code::F20, Code::F21, Code::F22, Code::F23, Code::F24,
+    ];
+
+    let lower = name.to_lowercase();
+    let mut chars = lower.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        if c.is_ascii_lowercase() {
+            return Some((LETTERS[(c as u8 - b'a') as usize], c.to_ascii_uppercase().to_string()));
+        }
+        if c.is_ascii_digit() {
+            return Some((DIGITS[(c as u8 - b'0') as usize], c.to_string()));
+        }
+    }
+    if let Some(number) = lower.strip_prefix('f').and_then(|n| n.parse::<usize>().ok()) {
+        return (1..=24).contains(&number).then(|| (FUNCTION_KEYS[number - 1], format!("F{}", number)));
+    }
+
+    let (code, canonical) = match lower.as_str() {
+        "space" => (Code::Space, "Space"),
+        "enter" | "return" => (Code::Enter, "Enter"),
+        "tab" => (Code::Tab, "Tab"),
+        "esc" | "escape" => (Code::Escape, "Escape"),
+        "backspace" => (Code::Backspace, "Backspace"),
+        "delete" | "del" => (Code::Delete, "Delete"),
+        "insert" | "ins" => (Code::Insert, "Insert"),
+        "home" => (Code::Home, "Home"),
+        "end" => (Code::End, "End"),
+        "pageup" | "pgup" => (Code::PageUp, "PageUp"),
+        "pagedown" | "pgdn" => (Code::PageDown, "PageDown"),
+        "up" | "arrowup" => (Code::ArrowUp, "Up"),
+        "down" | "arrowdown" => (Code::ArrowDown, "Down"),
+        "left" | "arrowleft" => (Code::ArrowLeft, "Left"),
+        "right" | "arrowright" => (Code::ArrowRight, "Right"),
+        "," | "comma" => (Code::Comma, ","),
+        "." | "period" => (Code::Period, "."),
+        "/" | "slash" => (Code::Slash, "/"),
+        ";" | "semicolon" => (Code::Semicolon, ";"),
+        "'" | "quote" => (Code::Quote, "'"),
+        "[" | "bracketleft" => (Code::BracketLeft, "["),
+        "]" | "bracketright" => (Code::BracketRight, "]"),
+        "\\" | "backslash" => (Code::Backslash, "\\"),
+        "-" | "minus" => (Code::Minus, "-"),
+        "=" | "equal" | "plus" => (Code::Equal, "="),
+        "`" | "backquote" => (Code::Backquote, "`"),
+        _ => return None,
+    };
+    Some((code, canonical.to_string()))
+}
+
+struct HotkeyRegistry {
+    registered: Option<Accelerator>,
+    status: HotkeyStatus,
+}
+
+pub struct HotkeyManager {
+    state: Mutex<HotkeyRegistry>,
+    registered_id: AtomicU32,  // 已注册的全局快捷键 ID，0 表示没有
+}
+
+impl HotkeyManager {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(HotkeyRegistry {
+                registered: None,
+                status: HotkeyStatus::default(),
+            }),
+            registered_id: AtomicU32::new(0),
+        }
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, HotkeyRegistry>, String> {
+        self.state.lock().map_err(|e| format!("Failed to lock hotkeys: {}", e))
+    }
+
+    pub fn status(&self) -> Result<HotkeyStatus, String> {
+        Ok(self.lock()?.status.clone())
+    }
+
+    // 按配置注册全局快捷键，未变化时不重新注册；格式错误或注册失败时保留原来的快捷键，并报告状态
+    pub fn apply(&self, app_handle: &AppHandle, config: &HotkeyConfig) -> Result<HotkeyStatus, String> {
+        let mut registry = self.lock()?;
+        let global_shortcut = app_handle.global_shortcut();
+
+        let mut global_state = HotkeyState::new(&config.global_hotkey);
+        let configured = parse_optional(&config.global_hotkey).and_then(|accelerator| {
+            if let Some(accelerator) = &accelerator {
+                accelerator.check_global()?;
+            }
+            Ok(accelerator)
+        });
+        let wanted = match &configured {
+            Ok(accelerator) => accelerator.clone(),
+            Err(e) => {
+                global_state.error = Some(e.clone());
+                registry.registered.clone()
+            }
+        };
+
+        if wanted != registry.registered {
+            let previous = registry.registered.take();
+            if let Some(previous) = &previous {
+                let _ = global_shortcut.unregister(previous.shortcut());
+            }
+            if let Some(accelerator) = wanted {
+                match global_shortcut.register(accelerator.shortcut()) {
+                    Ok(()) => registry.registered = Some(accelerator),
+                    Err(e) => {
+                        global_state.error = Some(format!(
+                            "Failed to register '{}', it may already be used by another application: {}", accelerator, e
+                        ));
+                        registry.registered = previous.filter(|p| global_shortcut.register(p.shortcut()).is_ok());
+                    }
+                }
+            }
+        }
+        self.registered_id.store(registry.registered.as_ref().map(|a| a.shortcut().id()).unwrap_or(0), Ordering::SeqCst);
+
+        if let Ok(Some(accelerator)) = &configured {
+            global_state.normalized = Some(accelerator.to_string());
+            global_state.registered = registry.registered.as_ref() == Some(accelerator);
+        }
+
+        let mut quick_search_state = HotkeyState::new(&config.quick_search);
+        match parse_optional(&config.quick_search) {
+            Ok(Some(accelerator)) => {
+                if matches!(&configured, Ok(Some(global)) if global == &accelerator) {
+                    quick_search_state.error = Some(format!("'{}' is already used as the global hotkey", accelerator));
+                }
+                quick_search_state.normalized = Some(accelerator.to_string());
+            }
+            Ok(None) => {}
+            Err(e) => quick_search_state.error = Some(e),
+        }
+
+        registry.status = HotkeyStatus {
+            global_hotkey: global_state,
+            quick_search: quick_search_state,
+        };
+        let status = registry.status.clone();
+        drop(registry);
+
+        let _ = app_handle.emit(STATUS_EVENT, status.clone());
+        Ok(status)
+    }
+
+    // 全局快捷键的回调：窗口在前台时隐藏，否则显示并聚焦
+    // 回调在主线程上执行，注册时也可能需要主线程，因此这里不取锁，只比较已注册的快捷键 ID
+    pub fn handle(&self, app_handle: &AppHandle, shortcut: &Shortcut, event: ShortcutEvent) {
+        if event.state() != ShortcutState::Pressed || shortcut.id() != self.registered_id.load(Ordering::SeqCst) {
+            return;
+        }
+
+        // 失败时记入全局快捷键的状态并推送；锁被 apply 占用时不等待，apply 完成后会推送新的状态
+        if let Err(e) = toggle_window(app_handle) {
+            let Ok(mut registry) = self.state.try_lock() else { return };
+            registry.status.global_hotkey.error = Some(format!("Failed to toggle launcher window: {}", e));
+            let status = registry.status.clone();
+            drop(registry);
+            let _ = app_handle.emit(STATUS_EVENT, status);
+        }
+    }
+}
+
+// 只校验格式和两个快捷键之间的冲突，用于保存配置前检查
+pub fn validate(config: &HotkeyConfig) -> Result<(), String> {
+    let global = parse_optional(&config.global_hotkey)?;
+    if let Some(global) = &global {
+        global.check_global()?;
+    }
+    let quick_search = parse_optional(&config.quick_search)?;
+    if let (Some(global), Some(quick_search)) = (&global, &quick_search) {
+        if global == quick_search {
+            return Err(format!("Global hotkey and quick search hotkey are both '{}'", global));
+        }
+    }
+    Ok(())
+}
+
+// 空字符串表示不使用该快捷键
+fn parse_optional(accelerator: &str) -> Result<Option<Accelerator>, String> {
+    if accelerator.trim().is_empty() {
+        return Ok(None);
+    }
+    Accelerator::parse(accelerator).map(Some)
+}
+
+fn toggle_window(app_handle: &AppHandle) -> Result<(), String> {
+    let window = app_handle.get_webview_window(MAIN_WINDOW)
+        .ok_or("Launcher window not found")?;
+
+    let visible = window.is_visible().unwrap_or(false);
+    let minimized = window.is_minimized().unwrap_or(false);
+    let focused = window.is_focused().unwrap_or(false);
+    if visible && !minimized && focused {
+        return window.hide().map_err(|e| format!("Failed to hide window: {}", e));
+    }
+
+    window.show().map_err(|e| format!("Failed to show window: {}", e))?;
+    if minimized {
+        window.unminimize().map_err(|e| format!("Failed to restore window: {}", e))?;
+    }
+    window.set_focus().map_err(|e| format!("Failed to focus window: {}", e))?;
+    let _ = app_handle.emit(ACTIVATED_EVENT, ());
+    Ok(())
+}
+
+lazy_static::lazy_static! {
+    pub static ref GLOBAL_HOTKEYS: HotkeyManager = HotkeyManager::new();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(global_hotkey: &str, quick_search: &str) -> HotkeyConfig {
+        HotkeyConfig { global_hotkey: global_hotkey.to_string(), quick_search: quick_search.to_string() }
+    }
+
+    #[test]
+    fn parses_and_normalizes_accelerators() {
+        let cases = [
+            ("ctrl + space", "Ctrl+Space"),
+            ("Shift+Alt+K", "Alt+Shift+K"),
+            ("control+option+meta+pgdn", "Ctrl+Alt+Super+PageDown"),
+            ("super+f12", "Super+F12"),
+            ("Ctrl+plus", "Ctrl+="),
+            ("ctrl+\\", "Ctrl+\\"),
+            ("7", "7"),
+        ];
+        for (accelerator, normalized) in cases {
+            let parsed = Accelerator::parse(accelerator).unwrap_or_else(|e| panic!("{}: {}", accelerator, e));
+            assert_eq!(parsed.to_string(), normalized);
+        }
+        assert_eq!(Accelerator::parse("ctrl + space"), Accelerator::parse("Control+SPACE"));
+    }
+
+    #[test]
+    fn rejects_malformed_accelerators() {
+        let cases = [
+            ("", "empty"),
+            ("   ", "empty"),
+            ("Ctrl+", "empty key"),
+            ("+K", "empty key"),
+            ("Ctrl++K", "empty key"),
+            ("Ctrl+Control+K", "repeated"),
+            ("Ctrl+A+B", "only one key"),
+            ("Ctrl+Shift", "missing a key"),
+            ("Ctrl+Hyper", "unknown key"),
+            ("F25", "unknown key"),
+            ("Ctrl+F0", "unknown key"),
+        ];
+        for (accelerator, reason) in cases {
+            let error = Accelerator::parse(accelerator).unwrap_err();
+            assert!(error.contains(reason), "{}: {}", accelerator, error);
+        }
+    }
+
+    #[test]
+    fn global_hotkeys_need_a_modifier_unless_they_are_function_keys() {
+        let check = |accelerator: &str| Accelerator::parse(accelerator).unwrap().check_global();
+        assert!(check("K").is_err());
+        assert!(check("Space").is_err());
+        assert!(check("1").is_err());
+        assert!(check("F").is_err());
+        assert!(check("Ctrl+K").is_ok());
+        assert!(check("F1").is_ok());
+        assert!(check("f24").is_ok());
+    }
+
+    #[test]
+    fn validates_hotkey_configs() {
+        assert!(validate(&config("Alt+Space", "Ctrl+K")).is_ok());
+        assert!(validate(&config("", "")).is_ok());
+        assert!(validate(&config("F5", "K")).is_ok());
+
+        assert!(validate(&config("K", "")).unwrap_err().contains("modifier"));
+        assert!(validate(&config("Alt+Space", "Ctrl+")).unwrap_err().contains("empty key"));
+
+        let clash = validate(&config("alt + space", "Alt+SPACE")).unwrap_err();
+        assert!(clash.contains("both 'Alt+Space'"), "{}", clash);
+    }
+}
@@ -0,0 +1,258 @@
+use crate::models::{BadgeConfig, Category, IconResult, IconSize, Shortcut, ShortcutIcon};
+use base64::{Engine as _, engine::general_purpose};
+use image::{DynamicImage, RgbaImage};
+
+// 在图标上叠加状态角标：
+//   右上  红色圆点：目标不存在
+//   左下  箭头：网址或符号链接
+//   底部  绿色圆点：目标程序正在运行
+//   左上  三角：分类颜色
+// 合成结果按角标状态作为图标变体放入内存缓存，状态不变时不再重复绘制
+
+const MISSING_COLOR: [u8; 4] = [0xEF, 0x44, 0x44, 0xFF];
+const RUNNING_COLOR: [u8; 4] = [0x22, 0xC5, 0x5E, 0xFF];
+const ARROW_COLOR: [u8; 4] = [0x25, 0x63, 0xEB, 0xFF];
+const PLACEHOLDER_COLOR: [u8; 4] = [0x9C, 0xA3, 0xAF, 0xFF];
+const WHITE: [u8; 4] = [0xFF, 0xFF, 0xFF, 0xFF];
+const OUTLINE: [u8; 4] = [0x00, 0x00, 0x00, 0x60];
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BadgeState {
+    pub missing: bool,
+    pub link: bool,
+    pub running: bool,
+    pub category_color: Option<[u8; 3]>,
+}
+
+impl BadgeState {
+    pub fn for_shortcut(shortcut: &Shortcut, categories: &[Category], config: &BadgeConfig) -> Self {
+        let is_url = crate::utils::is_url(&shortcut.file_path);
+        let missing = config.missing_target && !shortcut.file_exists;
+        let is_symlink = !is_url && std::fs::symlink_metadata(&shortcut.file_path)
+            .is_ok_and(|m| m.file_type().is_symlink());
+
+        let category_color = if config.category_tag {
+            shortcut.category_id.as_ref()
+                .and_then(|id| categories.iter().find(|c| &c.id == id))
+                .and_then(|category| crate::icon_colors::parse_hex_color(&category.color))
+        } else {
+            None
+        };
+
+        Self {
+            missing,
+            link: config.link_arrow && (is_url || is_symlink),
+            running: config.running_indicator && !is_url && !missing
+                && crate::process_monitor::GLOBAL_PROCESS_MONITOR.is_running(&shortcut.file_path),
+            category_color,
+        }
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        if self.missing {
+            names.push("missing".to_string());
+        }
+        if self.link {
+            names.push("link".to_string());
+        }
+        if self.running {
+            names.push("running".to_string());
+        }
+        if self.category_color.is_some() {
+            names.push("category".to_string());
+        }
+        names
+    }
+
+    // 缓存变体名，没有需要绘制的角标时为 None
+    fn variant(&self) -> Option<String> {
+        let mut names = self.names();
+        if names.is_empty() {
+            return None;
+        }
+        if let Some([r, g, b]) = self.category_color {
+            names.push(format!("{:02x}{:02x}{:02x}", r, g, b));
+        }
+        Some(format!("badge-{}", names.join("-")))
+    }
+}
+
+// 快捷方式的图标（包括自定义图标），按状态叠加角标
+pub fn shortcut_icon(shortcut: &Shortcut, categories: &[Category], config: &BadgeConfig, icon_size: IconSize) -> ShortcutIcon {
+    let state = BadgeState::for_shortcut(shortcut, categories, config);
+    let badges = state.names();
+    let base = crate::icon_cache::get_cached_icon(&shortcut.file_path, shortcut.icon_path.as_deref(), icon_size);
+
+    let (icon, error) = match state.variant() {
+        None => match base {
+            Ok(icon) => (Some(icon), None),
+            Err(e) => (None, Some(e)),
+        },
+        Some(variant) => match badged_icon(&shortcut.file_path, base, &state, &variant, icon_size.pixels()) {
+            Ok(icon) => (Some(icon), None),
+            Err(e) => (None, Some(e)),
+        },
+    };
+
+    ShortcutIcon {
+        shortcut_id: shortcut.id.clone(),
+        icon,
+        badges,
+        error,
+    }
+}
+
+// 图标本身取不到（例如目标已不存在或是网址）时画在占位图上
+fn badged_icon(file_path: &str, base: Result<IconResult, String>, state: &BadgeState, variant: &str, pixels: u32) -> Result<IconResult, String> {
+    let base = base.ok();
+    let file_hash = base.as_ref().and_then(|icon| icon.file_hash.clone());
+    let colors = base.as_ref().and_then(|icon| icon.colors.clone());
+
+    // 变体名带上底图的指纹，更换自定义图标后不会取到旧的合成结果
+    let variant = format!("{}@{}", variant, file_hash.as_deref().unwrap_or("placeholder"));
+    let cache = &crate::icon_cache::GLOBAL_ICON_CACHE;
+    if let Some(cached) = cache.get_variant(file_path, pixels, &variant) {
+        return Ok(cached);
+    }
+
+    let base_image = base.as_ref()
+        .and_then(|icon| crate::custom_icon::decode_icon_result(icon).ok());
+    let canvas = composite(base_image, state, pixels);
+
+    let mut png_data = Vec::new();
+    DynamicImage::ImageRgba8(canvas)
+        .write_to(&mut std::io::Cursor::new(&mut png_data), image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode icon: {}", e))?;
+
+    let icon = IconResult {
+        icon_data: format!("data:image/png;base64,{}", general_purpose::STANDARD.encode(png_data)),
+        icon_format: "png".to_string(),
+        from_cache: false,
+        file_hash,
+        size: pixels,
+        colors,
+    };
+    let _ = cache.set_variant(file_path, pixels, &variant, &icon);
+    Ok(icon)
+}
+
+fn composite(base: Option<DynamicImage>, state: &BadgeState, pixels: u32) -> RgbaImage {
+    let mut canvas = RgbaImage::new(pixels, pixels);
+    let s = pixels as f32;
+
+    match base {
+        Some(base) => {
+            let base = if base.width() > pixels || base.height() > pixels {
+                base.resize(pixels, pixels, image::imageops::FilterType::Lanczos3)
+            } else {
+                base
+            };
+            let x = (pixels - base.width()) / 2;
+            let y = (pixels - base.height()) / 2;
+            image::imageops::overlay(&mut canvas, &base.to_rgba8(), x as i64, y as i64);
+        }
+        None => {
+            let (inset, radius) = (s * 0.1, s * 0.15);
+            fill(&mut canvas, PLACEHOLDER_COLOR, |x, y| in_rounded_rect(x, y, inset, inset, s - inset, s - inset, radius));
+        }
+    }
+
+    let margin = s * 0.02;
+    let ring = (s * 0.025).max(1.0);
+
+    if let Some([r, g, b]) = state.category_color {
+        let side = s * 0.3;
+        fill(&mut canvas, [r, g, b, 0xFF], |x, y| x + y <= side);
+    }
+
+    if state.missing {
+        let radius = s * 0.17;
+        let (cx, cy) = (s - radius - margin - ring, radius + margin + ring);
+        fill(&mut canvas, WHITE, |x, y| in_circle(x, y, cx, cy, radius + ring));
+        fill(&mut canvas, MISSING_COLOR, |x, y| in_circle(x, y, cx, cy, radius));
+        let (arm, width) = (radius * 0.45, (radius * 0.14).max(0.6));
+        fill(&mut canvas, WHITE, |x, y| {
+            segment_distance(x, y, cx - arm, cy - arm, cx + arm, cy + arm) <= width
+                || segment_distance(x, y, cx - arm, cy + arm, cx + arm, cy - arm) <= width
+        });
+    }
+
+    if state.link {
+        let side = s * 0.36;
+        let (x0, y0) = (margin, s - side - margin);
+        let radius = side * 0.2;
+        fill(&mut canvas, OUTLINE, |x, y| in_rounded_rect(x, y, x0, y0, x0 + side, y0 + side, radius));
+        fill(&mut canvas, WHITE, |x, y| in_rounded_rect(x, y, x0 + ring * 0.5, y0 + ring * 0.5, x0 + side - ring * 0.5, y0 + side - ring * 0.5, radius));
+        // 箭头从左下指向右上
+        let point = |u: f32, v: f32| (x0 + side * u, y0 + side * v);
+        let (tail, tip, head_left, head_down) = (point(0.28, 0.72), point(0.72, 0.28), point(0.4, 0.28), point(0.72, 0.6));
+        let width = (side * 0.07).max(0.6);
+        fill(&mut canvas, ARROW_COLOR, |x, y| {
+            segment_distance(x, y, tail.0, tail.1, tip.0, tip.1) <= width
+                || segment_distance(x, y, head_left.0, head_left.1, tip.0, tip.1) <= width
+                || segment_distance(x, y, head_down.0, head_down.1, tip.0, tip.1) <= width
+        });
+    }
+
+    if state.running {
+        let radius = (s * 0.08).max(1.5);
+        let (cx, cy) = (s / 2.0, s - radius - margin - ring);
+        fill(&mut canvas, WHITE, |x, y| in_circle(x, y, cx, cy, radius + ring));
+        fill(&mut canvas, RUNNING_COLOR, |x, y| in_circle(x, y, cx, cy, radius));
+    }
+
+    canvas
+}
+
+// 按 4x4 超采样计算覆盖率，边缘抗锯齿，颜色按 alpha 叠加到画布上
+fn fill<F>(canvas: &mut RgbaImage, color: [u8; 4], inside: F)
+where
+    F: Fn(f32, f32) -> bool,
+{
+    const SAMPLES: u32 = 4;
+    for (px, py, pixel) in canvas.enumerate_pixels_mut() {
+        let mut covered = 0;
+        for sy in 0..SAMPLES {
+            for sx in 0..SAMPLES {
+                let x = px as f32 + (sx as f32 + 0.5) / SAMPLES as f32;
+                let y = py as f32 + (sy as f32 + 0.5) / SAMPLES as f32;
+                if inside(x, y) {
+                    covered += 1;
+                }
+            }
+        }
+        if covered == 0 {
+            continue;
+        }
+
+        let alpha = color[3] as f32 / 255.0 * covered as f32 / (SAMPLES * SAMPLES) as f32;
+        let dst_alpha = pixel[3] as f32 / 255.0;
+        let out_alpha = alpha + dst_alpha * (1.0 - alpha);
+        for (channel, &value) in color.iter().take(3).enumerate() {
+            let blended = value as f32 * alpha + pixel[channel] as f32 * dst_alpha * (1.0 - alpha);
+            pixel[channel] = (blended / out_alpha).round() as u8;
+        }
+        pixel[3] = (out_alpha * 255.0).round() as u8;
+    }
+}
+
+fn in_circle(x: f32, y: f32, cx: f32, cy: f32, radius: f32) -> bool {
+    (x - cx).powi(2) + (y - cy).powi(2) <= radius * radius
+}
+
+fn in_rounded_rect(x: f32, y: f32, x0: f32, y0: f32, x1: f32, y1: f32, radius: f32) -> bool {
+    if x < x0 || x > x1 || y < y0 || y > y1 {
+        return false;
+    }
+    let cx = x.clamp(x0 + radius, x1 - radius);
+    let cy = y.clamp(y0 + radius, y1 - radius);
+    in_circle(x, y, cx, cy, radius)
+}
+
+fn segment_distance(x: f32, y: f32, ax: f32, ay: f32, bx: f32, by: f32) -> f32 {
+    let (dx, dy) = (bx - ax, by - ay);
+    let length = dx * dx + dy * dy;
+    let t = if length > 0.0 { (((x - ax) * dx + (y - ay) * dy) / length).clamp(0.0, 1.0) } else { 0.0 };
+    ((x - ax - t * dx).powi(2) + (y - ay - t * dy).powi(2)).sqrt()
+}
@@ -0,0 +1,162 @@
+use crate::models::{IconBatchItem, IconBatchProgress, IconSize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use uuid::Uuid;
+
+// 批量提取图标：任务在有界的线程池中执行，经过内存和磁盘缓存
+// 后台任务通过事件推送结果：
+//   icon-batch-item      每个文件、每种尺寸一条，失败时带原因
+//   icon-batch-progress  进度，最多每 100ms 一次，结束时必定发送一次 finished = true
+pub const ITEM_EVENT: &str = "icon-batch-item";
+pub const PROGRESS_EVENT: &str = "icon-batch-progress";
+
+const MAX_WORKERS: usize = 4;
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+fn worker_count(tasks: usize) -> usize {
+    let available = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(2);
+    available.min(MAX_WORKERS).min(tasks).max(1)
+}
+
+// 请求的尺寸列表；未指定时按旧的大/小图标，相同像素尺寸只保留一个
+pub fn normalize_sizes(sizes: Option<Vec<IconSize>>, large_icon: bool) -> Vec<IconSize> {
+    let mut sizes = sizes
+        .filter(|sizes| !sizes.is_empty())
+        .unwrap_or_else(|| vec![IconSize::from_large(large_icon)]);
+    sizes.sort_by_key(|size| size.pixels());
+    sizes.dedup_by_key(|size| size.pixels());
+    sizes
+}
+
+// 执行一批任务（文件 × 尺寸），每完成一项调用 on_item，参数为任务序号；
+// 取消后尚未开始的任务不再执行，正在执行的任务会完成
+pub fn run_batch<F>(job_id: Option<&str>, file_paths: &[String], sizes: &[IconSize], cancelled: &AtomicBool, on_item: F)
+where
+    F: Fn(usize, IconBatchItem) + Sync,
+{
+    let tasks: Vec<(&String, IconSize)> = file_paths.iter()
+        .flat_map(|path| sizes.iter().map(move |size| (path, *size)))
+        .collect();
+    let next = AtomicUsize::new(0);
+
+    std::thread::scope(|scope| {
+        for _ in 0..worker_count(tasks.len()) {
+            scope.spawn(|| loop {
+                if cancelled.load(Ordering::Relaxed) {
+                    break;
+                }
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some((path, size)) = tasks.get(index) else { break };
+
+                let (icon, error) = match crate::icon_cache::get_cached_icon(path, None, *size) {
+                    Ok(icon) => (Some(icon), None),
+                    Err(e) => (None, Some(e)),
+                };
+
+                on_item(index, IconBatchItem {
+                    job_id: job_id.map(|id| id.to_string()),
+                    file_path: path.to_string(),
+                    size: size.pixels(),
+                    icon,
+                    error,
+                });
+            });
+        }
+    });
+}
+
+struct ProgressState {
+    completed: usize,
+    failed: usize,
+    last_emit: Option<Instant>,
+}
+
+pub struct IconBatchJobs {
+    jobs: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+impl IconBatchJobs {
+    pub fn new() -> Self {
+        Self {
+            jobs: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // 在后台启动批量提取，立即返回任务 ID，结果通过事件推送
+    // 事件可能先于返回值到达前端，调用方应自行生成任务 ID 并在调用前按它过滤事件；
+    // 未指定时生成一个新的 ID
+    pub fn start(&'static self, app_handle: AppHandle, job_id: Option<String>, file_paths: Vec<String>, sizes: Vec<IconSize>) -> Result<String, String> {
+        let job_id = match job_id {
+            Some(id) if id.trim().is_empty() => return Err("Job ID must not be empty".to_string()),
+            Some(id) => id,
+            None => Uuid::new_v4().to_string(),
+        };
+        let cancelled = Arc::new(AtomicBool::new(false));
+
+        {
+            let mut jobs = self.jobs.lock()
+                .map_err(|e| format!("Failed to lock icon batch jobs: {}", e))?;
+            if jobs.contains_key(&job_id) {
+                return Err(format!("Icon batch job already running: {}", job_id));
+            }
+            jobs.insert(job_id.clone(), cancelled.clone());
+        }
+
+        let id = job_id.clone();
+        std::thread::spawn(move || {
+            let total = file_paths.len() * sizes.len();
+            let progress = Mutex::new(ProgressState { completed: 0, failed: 0, last_emit: None });
+            let make_progress = |state: &ProgressState, finished: bool| IconBatchProgress {
+                job_id: id.clone(),
+                total,
+                completed: state.completed,
+                failed: state.failed,
+                finished,
+                cancelled: cancelled.load(Ordering::Relaxed),
+            };
+
+            run_batch(Some(&id), &file_paths, &sizes, &cancelled, |_, item| {
+                let failed = item.error.is_some();
+                let _ = app_handle.emit(ITEM_EVENT, item);
+
+                let Ok(mut state) = progress.lock() else { return };
+                state.completed += 1;
+                if failed {
+                    state.failed += 1;
+                }
+                if state.last_emit.is_none_or(|last| last.elapsed() >= PROGRESS_INTERVAL) {
+                    state.last_emit = Some(Instant::now());
+                    let _ = app_handle.emit(PROGRESS_EVENT, make_progress(&state, false));
+                }
+            });
+
+            if let Ok(mut jobs) = self.jobs.lock() {
+                jobs.remove(&id);
+            }
+            if let Ok(state) = progress.into_inner() {
+                let _ = app_handle.emit(PROGRESS_EVENT, make_progress(&state, true));
+            }
+        });
+
+        Ok(job_id)
+    }
+
+    // 取消任务；任务不存在（已结束或 ID 无效）时返回 false
+    pub fn cancel(&self, job_id: &str) -> bool {
+        let Ok(jobs) = self.jobs.lock() else { return false };
+        match jobs.get(job_id) {
+            Some(cancelled) => {
+                cancelled.store(true, Ordering::Relaxed);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+lazy_static::lazy_static! {
+    pub static ref GLOBAL_ICON_BATCH: IconBatchJobs = IconBatchJobs::new();
+}
@@ -1,7 +1,8 @@
-use crate::models::IconResult;
-use std::collections::HashMap;
+use crate::icon_disk_cache::{DiskIconCache, IconKey};
+use crate::models::{AdvancedConfig, IconColors, IconResult, IconSize};
+use std::collections::{BTreeMap, HashMap};
 use std::sync::{Arc, Mutex};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 
@@ -13,45 +14,256 @@ pub struct CachedIcon {
     pub cached_at: u64,
     pub file_size: u64,
     pub last_modified: u64,
+    pub colors: Option<IconColors>,
+}
+
+impl CachedIcon {
+    // 条目占用的内存，按字符串长度估算
+    fn bytes(&self, key: &str) -> usize {
+        key.len() + self.icon_data.len() + self.icon_format.len() + self.file_hash.len()
+    }
+}
+
+#[derive(Debug)]
+struct MemoryEntry {
+    icon: CachedIcon,
+    bytes: usize,
+    tick: u64,  // 最近访问的序号，越大越新
+}
+
+// 内存缓存：按总字节数限制容量，超出时淘汰最久未访问的条目
+// order 按访问序号排列，最小的即最久未访问
+#[derive(Debug, Default)]
+struct MemoryState {
+    entries: HashMap<String, MemoryEntry>,
+    order: BTreeMap<u64, String>,
+    next_tick: u64,
+    total_bytes: usize,
+    max_bytes: usize,
+    metrics: CacheMetrics,
+}
+
+#[derive(Debug, Default, Clone)]
+struct CacheMetrics {
+    hits: u64,
+    disk_hits: u64,
+    misses: u64,
+    evictions: u64,
+    extractions: u64,
+    extraction_micros: u64,
+}
+
+impl MemoryState {
+    fn touch(&mut self, key: &str) {
+        let tick = self.next_tick;
+        self.next_tick += 1;
+        if let Some(entry) = self.entries.get_mut(key) {
+            self.order.remove(&entry.tick);
+            entry.tick = tick;
+            self.order.insert(tick, key.to_string());
+        }
+    }
+
+    fn remove(&mut self, key: &str) -> Option<MemoryEntry> {
+        let entry = self.entries.remove(key)?;
+        self.order.remove(&entry.tick);
+        self.total_bytes -= entry.bytes;
+        Some(entry)
+    }
+
+    fn insert(&mut self, key: String, icon: CachedIcon) {
+        let bytes = icon.bytes(&key);
+        self.remove(&key);
+        if bytes > self.max_bytes {
+            return;
+        }
+
+        let tick = self.next_tick;
+        self.next_tick += 1;
+        self.order.insert(tick, key.clone());
+        self.entries.insert(key, MemoryEntry { icon, bytes, tick });
+        self.total_bytes += bytes;
+        self.evict();
+    }
+
+    fn evict(&mut self) {
+        while self.total_bytes > self.max_bytes {
+            let Some((_, key)) = self.order.pop_first() else { break };
+            if let Some(entry) = self.entries.remove(&key) {
+                self.total_bytes -= entry.bytes;
+                self.metrics.evictions += 1;
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct IconCache {
-    memory_cache: Arc<Mutex<HashMap<String, CachedIcon>>>,
-    max_memory_size: usize,
+    memory_cache: Arc<Mutex<MemoryState>>,
     cache_duration: u64, // 缓存持续时间（秒）
+    disk_cache: DiskIconCache,
+}
+
+// 图标本身的变体，带角标等加工后的图标使用其他变体名
+pub const DEFAULT_VARIANT: &str = "default";
+
+// 内存缓存的键：同一文件的不同尺寸、不同变体分开缓存
+fn memory_key(file_path: &str, size: u32, variant: &str) -> String {
+    format!("{}|{}|{}", file_path, size, variant)
 }
 
 impl IconCache {
-    pub fn new(max_memory_size: usize, cache_duration: u64) -> Self {
+    pub fn new(max_memory_bytes: usize, cache_duration: u64, max_disk_bytes: u64) -> Self {
         Self {
-            memory_cache: Arc::new(Mutex::new(HashMap::new())),
-            max_memory_size,
+            memory_cache: Arc::new(Mutex::new(MemoryState {
+                max_bytes: max_memory_bytes,
+                ..Default::default()
+            })),
             cache_duration,
+            disk_cache: DiskIconCache::new(max_disk_bytes),
         }
     }
 
-    // 获取缓存的图标
-    pub fn get(&self, file_path: &str) -> Option<IconResult> {
-        let cache = self.memory_cache.lock().ok()?;
-        
-        if let Some(cached_icon) = cache.get(file_path) {
-            // 检查缓存是否过期
-            if self.is_cache_valid(file_path, cached_icon) {
-                return Some(IconResult {
-                    icon_data: cached_icon.icon_data.clone(),
-                    icon_format: cached_icon.icon_format.clone(),
-                    from_cache: true,
-                    file_hash: Some(cached_icon.file_hash.clone()),
-                });
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, MemoryState>, String> {
+        self.memory_cache.lock().map_err(|e| format!("Failed to lock cache: {}", e))
+    }
+
+    // 按配置调整内存和磁盘缓存的容量，超出的部分立即淘汰
+    pub fn configure(&self, config: &AdvancedConfig) -> Result<(), String> {
+        let mut cache = self.lock()?;
+        cache.max_bytes = config.icon_cache_memory_mb as usize * 1024 * 1024;
+        cache.evict();
+        drop(cache);
+
+        self.disk_cache.set_max_bytes(config.icon_cache_disk_mb as u64 * 1024 * 1024)
+    }
+
+    // 打开磁盘缓存，并在后台用最近使用的图标预热内存缓存
+    pub fn open_disk_cache(&self, cache_dir: &Path) -> Result<(), String> {
+        self.disk_cache.open(cache_dir)?;
+
+        let cache = self.clone();
+        std::thread::spawn(move || {
+            cache.warm_start();
+        });
+
+        Ok(())
+    }
+
+    // 预热最多占用一半的内存容量，给新的请求留出空间
+    fn warm_start(&self) {
+        let current_time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let Ok(budget) = self.lock().map(|c| c.max_bytes / 2) else { return };
+        let mut used = 0;
+
+        for entry in self.disk_cache.recent_entries(usize::MAX) {
+            if used >= budget {
+                break;
             }
+
+            // 文件已修改的条目不预热，等下次请求时重新提取
+            let Ok((file_size, last_modified)) = self.get_file_metadata(&entry.file_path) else { continue };
+            if file_size != entry.file_size || last_modified != entry.last_modified {
+                continue;
+            }
+
+            let key = IconKey::new(&entry.fingerprint, entry.size, &entry.variant);
+            let Some(icon) = self.disk_cache.get(&key) else { continue };
+
+            let key = memory_key(&entry.file_path, entry.size, &entry.variant);
+            let Ok(mut cache) = self.lock() else { return };
+            if cache.entries.contains_key(&key) {
+                continue;
+            }
+            let cached_icon = CachedIcon {
+                icon_data: icon.icon_data,
+                icon_format: icon.icon_format,
+                file_hash: entry.fingerprint,
+                cached_at: current_time,
+                file_size,
+                last_modified,
+                colors: icon.colors,
+            };
+            used += cached_icon.bytes(&key);
+            cache.insert(key, cached_icon);
+        }
+    }
+
+    // 从磁盘缓存读取，命中后放入内存缓存
+    pub fn get_from_disk(&self, file_path: &str, fingerprint: &str, size: u32) -> Option<IconResult> {
+        let icon = self.disk_cache.get(&IconKey::new(fingerprint, size, DEFAULT_VARIANT))?;
+        if let Ok(mut cache) = self.lock() {
+            cache.metrics.disk_hits += 1;
+        }
+        let _ = self.set(file_path, size, &icon);
+        Some(icon)
+    }
+
+    // 写入磁盘缓存
+    pub fn put_to_disk(&self, file_path: &str, fingerprint: &str, size: u32, icon_result: &IconResult) -> Result<(), String> {
+        self.disk_cache.put(&IconKey::new(fingerprint, size, DEFAULT_VARIANT), file_path, icon_result)
+    }
+
+    // 磁盘缓存使用的文件指纹
+    pub fn disk_fingerprint(&self, file_path: &str) -> Option<String> {
+        self.disk_cache.fingerprint(file_path)
+    }
+
+    // 把磁盘缓存的索引写回磁盘，退出前调用
+    pub fn flush_disk_cache(&self) -> Result<(), String> {
+        self.disk_cache.flush()
+    }
+
+    // 获取缓存的图标
+    pub fn get(&self, file_path: &str, size: u32) -> Option<IconResult> {
+        self.get_variant(file_path, size, DEFAULT_VARIANT)
+    }
+
+    // 获取缓存的图标变体，仍按来源文件的修改判断是否过期
+    pub fn get_variant(&self, file_path: &str, size: u32, variant: &str) -> Option<IconResult> {
+        let key = memory_key(file_path, size, variant);
+
+        // 锁内只取出记录的时间和文件元数据，读取文件元数据和查询目标监听都在锁外进行
+        let recorded = {
+            let cache = self.lock().ok()?;
+            let icon = &cache.entries.get(&key)?.icon;
+            (icon.cached_at, icon.file_size, icon.last_modified)
+        };
+        let valid = self.is_cache_valid(file_path, recorded);
+
+        let mut cache = self.lock().ok()?;
+        let entry = &cache.entries.get(&key)?.icon;
+        let current = (entry.cached_at, entry.file_size, entry.last_modified);
+
+        // 检查缓存是否过期，过期的条目直接移除；检查期间条目已被重新写入时保留新的条目
+        if !valid && current == recorded {
+            cache.remove(&key);
+            return None;
         }
         
-        None
+        cache.touch(&key);
+        cache.metrics.hits += 1;
+        let cached_icon = &cache.entries.get(&key)?.icon;
+
+        Some(IconResult {
+            icon_data: cached_icon.icon_data.clone(),
+            icon_format: cached_icon.icon_format.clone(),
+            from_cache: true,
+            file_hash: Some(cached_icon.file_hash.clone()),
+            size,
+            colors: cached_icon.colors.clone(),
+        })
     }
 
     // 缓存图标
-    pub fn set(&self, file_path: &str, icon_result: &IconResult) -> Result<(), String> {
+    pub fn set(&self, file_path: &str, size: u32, icon_result: &IconResult) -> Result<(), String> {
+        self.set_variant(file_path, size, DEFAULT_VARIANT, icon_result)
+    }
+
+    pub fn set_variant(&self, file_path: &str, size: u32, variant: &str, icon_result: &IconResult) -> Result<(), String> {
         let file_info = self.get_file_metadata(file_path)?;
         let current_time = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -62,41 +274,51 @@ impl IconCache {
             icon_data: icon_result.icon_data.clone(),
             icon_format: icon_result.icon_format.clone(),
             file_hash: icon_result.file_hash.clone().unwrap_or_else(|| {
-                crate::utils::get_file_hash(file_path).unwrap_or_default()
+                crate::fingerprint::metadata(Path::new(file_path)).map(|f| f.to_string()).unwrap_or_default()
             }),
             cached_at: current_time,
             file_size: file_info.0,
             last_modified: file_info.1,
+            colors: icon_result.colors.clone(),
         };
 
-        let mut cache = self.memory_cache.lock()
-            .map_err(|e| format!("Failed to lock cache: {}", e))?;
-        
-        // 如果缓存已满，清理旧的条目
-        if cache.len() >= self.max_memory_size {
-            self.cleanup_old_entries(&mut cache);
-        }
-        
-        cache.insert(file_path.to_string(), cached_icon);
+        self.lock()?.insert(memory_key(file_path, size, variant), cached_icon);
         
         Ok(())
     }
 
+    // 记录一次缓存未命中后的提取耗时
+    fn record_extraction(&self, started: Instant) {
+        if let Ok(mut cache) = self.lock() {
+            cache.metrics.misses += 1;
+            cache.metrics.extractions += 1;
+            cache.metrics.extraction_micros += started.elapsed().as_micros() as u64;
+        }
+    }
+
     // 检查缓存是否有效
-    fn is_cache_valid(&self, file_path: &str, cached_icon: &CachedIcon) -> bool {
+    // recorded 为缓存时记录的 (缓存时间, 文件大小, 修改时间)
+    fn is_cache_valid(&self, file_path: &str, recorded: (u64, u64, u64)) -> bool {
+        let (cached_at, file_size, last_modified) = recorded;
+
         // 检查时间是否过期
         let current_time = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .map(|d| d.as_secs())
             .unwrap_or(0);
         
-        if current_time - cached_icon.cached_at > self.cache_duration {
+        if current_time.saturating_sub(cached_at) > self.cache_duration {
             return false;
         }
 
+        // 目标所在目录被监听时，文件变化会通过通知使条目失效，不必再检查元数据
+        if crate::target_watcher::GLOBAL_TARGET_WATCHER.covers(file_path) {
+            return true;
+        }
+
         // 检查文件是否被修改
         if let Ok(file_info) = self.get_file_metadata(file_path) {
-            if file_info.0 != cached_icon.file_size || file_info.1 != cached_icon.last_modified {
+            if file_info.0 != file_size || file_info.1 != last_modified {
                 return false;
             }
         } else {
@@ -123,60 +345,77 @@ impl IconCache {
         Ok((size, modified))
     }
 
-    // 清理旧的缓存条目
-    fn cleanup_old_entries(&self, cache: &mut HashMap<String, CachedIcon>) {
-        let current_time = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map(|d| d.as_secs())
-            .unwrap_or(0);
-        
-        // 移除过期的条目
-        cache.retain(|_, cached_icon| {
-            current_time - cached_icon.cached_at <= self.cache_duration
-        });
-        
-        // 如果还是太多，移除最旧的条目
-        if cache.len() >= self.max_memory_size {
-            let mut entries: Vec<_> = cache.iter().map(|(k, v)| (k.clone(), v.cached_at)).collect();
-            entries.sort_by_key(|(_, cached_at)| *cached_at);
-            
-            let to_remove = cache.len() - self.max_memory_size / 2;
-            for (path, _) in entries.iter().take(to_remove) {
-                cache.remove(path);
-            }
+    // 移除路径本身及其下所有路径在内存中的各尺寸、各变体条目
+    pub fn invalidate(&self, path: &Path) {
+        let Ok(mut cache) = self.lock() else { return };
+        let keys: Vec<String> = cache.entries.keys()
+            .filter(|key| {
+                let file_path = key.rsplitn(3, '|').nth(2).unwrap_or(key);
+                Path::new(file_path).starts_with(path)
+            })
+            .cloned()
+            .collect();
+        for key in keys {
+            cache.remove(&key);
         }
     }
 
-    // 清空缓存
+    // 只清空内存缓存，磁盘缓存按文件指纹区分，不受影响
+    pub fn clear_memory(&self) {
+        let Ok(mut cache) = self.lock() else { return };
+        cache.entries.clear();
+        cache.order.clear();
+        cache.total_bytes = 0;
+    }
+
+    // 清空缓存（内存和磁盘），统计数据保留
     pub fn clear(&self) -> Result<(), String> {
-        let mut cache = self.memory_cache.lock()
-            .map_err(|e| format!("Failed to lock cache: {}", e))?;
-        cache.clear();
-        Ok(())
+        let mut cache = self.lock()?;
+        cache.entries.clear();
+        cache.order.clear();
+        cache.total_bytes = 0;
+        drop(cache);
+        
+        self.disk_cache.clear()
     }
 
     // 获取缓存统计信息
     pub fn get_stats(&self) -> Result<CacheStats, String> {
-        let cache = self.memory_cache.lock()
-            .map_err(|e| format!("Failed to lock cache: {}", e))?;
+        let cache = self.lock()?;
+        let metrics = &cache.metrics;
+        
+        let (disk_entries, disk_bytes) = self.disk_cache.usage();
+        let lookups = metrics.hits + metrics.disk_hits + metrics.misses;
         
         Ok(CacheStats {
-            total_entries: cache.len(),
-            max_entries: self.max_memory_size,
+            total_entries: cache.entries.len(),
+            memory_bytes: cache.total_bytes as u64,
+            max_memory_bytes: cache.max_bytes as u64,
             cache_duration: self.cache_duration,
+            disk_entries,
+            disk_bytes,
+            max_disk_bytes: self.disk_cache.max_bytes(),
+            hits: metrics.hits,
+            disk_hits: metrics.disk_hits,
+            misses: metrics.misses,
+            evictions: metrics.evictions,
+            disk_evictions: self.disk_cache.evictions(),
+            disk_error: self.disk_cache.open_error(),
+            hit_rate: if lookups > 0 { (metrics.hits + metrics.disk_hits) as f64 / lookups as f64 } else { 0.0 },
+            average_extraction_ms: if metrics.extractions > 0 {
+                metrics.extraction_micros as f64 / metrics.extractions as f64 / 1000.0
+            } else {
+                0.0
+            },
         })
     }
 
-    // 预加载图标
-    pub fn preload_icons(&self, file_paths: Vec<String>) -> Result<(), String> {
-        for path in file_paths {
-            if self.get(&path).is_none() {
-                // 如果缓存中没有，尝试提取并缓存
-                if let Ok(icon_result) = crate::icon_extractor::extract_file_icon(&path, true) {
-                    let _ = self.set(&path, &icon_result);
-                }
-            }
-        }
+    // 在后台线程池中预加载图标，依次尝试内存、磁盘，都没有时提取并缓存
+    pub fn preload_icons(&self, file_paths: Vec<String>, sizes: Vec<IconSize>) -> Result<(), String> {
+        std::thread::spawn(move || {
+            let cancelled = std::sync::atomic::AtomicBool::new(false);
+            crate::icon_batch::run_batch(None, &file_paths, &sizes, &cancelled, |_, _| {});
+        });
         Ok(())
     }
 }
@@ -184,36 +423,81 @@ impl IconCache {
 #[derive(Debug, Serialize)]
 pub struct CacheStats {
     pub total_entries: usize,
-    pub max_entries: usize,
+    pub memory_bytes: u64,
+    pub max_memory_bytes: u64,
     pub cache_duration: u64,
+    pub disk_entries: usize,
+    pub disk_bytes: u64,
+    pub max_disk_bytes: u64,
+    pub hits: u64,  // 内存命中
+    pub disk_hits: u64,
+    pub misses: u64,  // 两级缓存都未命中，需要提取
+    pub evictions: u64,  // 内存缓存因容量淘汰的条目数
+    pub disk_evictions: u64,
+    pub disk_error: Option<String>,  // 磁盘缓存无法打开的原因
+    pub hit_rate: f64,
+    pub average_extraction_ms: f64,
 }
 
-// 全局缓存实例
+// 全局缓存实例，容量在加载配置后按 AdvancedConfig 调整
 lazy_static::lazy_static! {
-    pub static ref GLOBAL_ICON_CACHE: IconCache = IconCache::new(1000, 3600); // 1000个条目，1小时过期
+    pub static ref GLOBAL_ICON_CACHE: IconCache = IconCache::new(32 * 1024 * 1024, 3600, 64 * 1024 * 1024); // 内存32MB，1小时过期，磁盘64MB
 }
 
-// 带缓存的图标提取函数
-pub fn get_cached_icon(file_path: &str, large_icon: bool) -> Result<IconResult, String> {
-    // 先尝试从缓存获取
-    if let Some(cached_result) = GLOBAL_ICON_CACHE.get(file_path) {
+// 带缓存的图标提取函数，每种像素尺寸单独缓存；custom_icon 为快捷方式的 icon_path
+pub fn get_cached_icon(file_path: &str, custom_icon: Option<&str>, icon_size: IconSize) -> Result<IconResult, String> {
+    let size = icon_size.pixels();
+    
+    // 自定义图标优先，文件本身就在磁盘上，只经过内存缓存
+    let custom_icons = &crate::custom_icon::GLOBAL_CUSTOM_ICONS;
+    if let Some(icon_file) = custom_icon.and_then(|path| custom_icons.resolve(path)) {
+        if let Some(cached_result) = GLOBAL_ICON_CACHE.get(&icon_file, size) {
+            return Ok(cached_result);
+        }
+        let mut icon_result = custom_icons.load(&icon_file, size)?;
+        icon_result.colors = crate::icon_colors::analyze_icon(&icon_result);
+        let _ = GLOBAL_ICON_CACHE.set(&icon_file, size, &icon_result);
+        return Ok(icon_result);
+    }
+    
+    // 先尝试从内存缓存获取
+    if let Some(cached_result) = GLOBAL_ICON_CACHE.get(file_path, size) {
         return Ok(cached_result);
     }
     
+    // 再尝试磁盘缓存，按内容指纹查找，文件内容变化后自然失效，内容相同的文件共用缓存
+    let fingerprint = GLOBAL_ICON_CACHE.disk_fingerprint(file_path);
+    if let Some(fingerprint) = &fingerprint {
+        if let Some(cached_result) = GLOBAL_ICON_CACHE.get_from_disk(file_path, fingerprint, size) {
+            return Ok(cached_result);
+        }
+    }
+    
     // 缓存中没有，提取图标
+    let started = Instant::now();
     let icon_result = if Path::new(file_path).is_dir() {
-        crate::icon_extractor::extract_directory_icon(file_path, large_icon)?
+        crate::icon_extractor::extract_directory_icon(file_path, icon_size)
     } else {
-        crate::icon_extractor::extract_file_icon(file_path, large_icon)?
+        crate::icon_extractor::extract_file_icon(file_path, icon_size)
     };
+    GLOBAL_ICON_CACHE.record_extraction(started);
+    let mut icon_result = icon_result?;
+    
+    // 颜色和图标一起缓存，命中缓存时不再重新分析
+    icon_result.colors = crate::icon_colors::analyze_icon(&icon_result);
     
     // 缓存结果
-    let _ = GLOBAL_ICON_CACHE.set(file_path, &icon_result);
+    let _ = GLOBAL_ICON_CACHE.set(file_path, size, &icon_result);
+    if let Some(fingerprint) = &fingerprint {
+        let _ = GLOBAL_ICON_CACHE.put_to_disk(file_path, fingerprint, size, &icon_result);
+    }
     
     Ok(IconResult {
         icon_data: icon_result.icon_data,
         icon_format: icon_result.icon_format,
         from_cache: false,
         file_hash: icon_result.file_hash,
+        size: icon_result.size,
+        colors: icon_result.colors,
     })
-}
\ No newline at end of file
+}
@@ -0,0 +1,178 @@
+use crate::models::{CategoryColorSuggestion, IconColors, IconResult};
+use image::DynamicImage;
+use std::collections::HashMap;
+
+// 图标颜色分析：缩小到 32x32 后按颜色分桶统计，忽略接近透明的像素
+//   dominant  像素最多的颜色
+//   accent    饱和度加权后最突出、且与主色有明显差别的颜色，没有时等于主色
+// 文字颜色在黑白之间选择与背景对比度更高的一种（WCAG 对比度）
+
+const SAMPLE_SIZE: u32 = 32;
+const MIN_ALPHA: u8 = 128;
+const BUCKET_SHIFT: u8 = 4;  // 每通道保留 4 位，共 4096 个桶
+const MIN_ACCENT_DISTANCE: f32 = 60.0;
+
+#[derive(Default)]
+struct Bucket {
+    count: u32,
+    sum: [u64; 3],
+}
+
+impl Bucket {
+    fn add(&mut self, rgb: [u8; 3]) {
+        self.count += 1;
+        for (sum, value) in self.sum.iter_mut().zip(rgb) {
+            *sum += value as u64;
+        }
+    }
+
+    fn average(&self) -> [u8; 3] {
+        let count = self.count.max(1) as u64;
+        [(self.sum[0] / count) as u8, (self.sum[1] / count) as u8, (self.sum[2] / count) as u8]
+    }
+}
+
+fn bucket_key(rgb: [u8; 3]) -> u16 {
+    let [r, g, b] = rgb.map(|v| (v >> BUCKET_SHIFT) as u16);
+    (r << 8) | (g << 4) | b
+}
+
+// 饱和度 × 亮度，灰色、接近黑色和白色时接近 0
+fn vividness(rgb: [u8; 3]) -> f32 {
+    let max = *rgb.iter().max().unwrap_or(&0) as f32 / 255.0;
+    let min = *rgb.iter().min().unwrap_or(&0) as f32 / 255.0;
+    if max == 0.0 {
+        return 0.0;
+    }
+    (max - min) / max * max
+}
+
+fn distance(a: [u8; 3], b: [u8; 3]) -> f32 {
+    a.iter().zip(b.iter())
+        .map(|(x, y)| (*x as f32 - *y as f32).powi(2))
+        .sum::<f32>()
+        .sqrt()
+}
+
+pub fn analyze(image: &DynamicImage) -> Option<IconColors> {
+    let sample = image.thumbnail(SAMPLE_SIZE, SAMPLE_SIZE).to_rgba8();
+
+    let mut buckets: HashMap<u16, Bucket> = HashMap::new();
+    for pixel in sample.pixels() {
+        if pixel[3] < MIN_ALPHA {
+            continue;
+        }
+        let rgb = [pixel[0], pixel[1], pixel[2]];
+        buckets.entry(bucket_key(rgb)).or_default().add(rgb);
+    }
+
+    let dominant = buckets.values().max_by_key(|bucket| bucket.count)?.average();
+    let accent = buckets.values()
+        .map(|bucket| (bucket.average(), bucket.count as f32 * vividness(bucket.average())))
+        .filter(|(rgb, score)| *score > 0.0 && distance(*rgb, dominant) >= MIN_ACCENT_DISTANCE)
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(rgb, _)| rgb)
+        .unwrap_or(dominant);
+
+    Some(IconColors {
+        dominant: to_hex(dominant),
+        accent: to_hex(accent),
+        dominant_text: to_hex(text_color(dominant)),
+        accent_text: to_hex(text_color(accent)),
+    })
+}
+
+// 分析图标结果的颜色，文字形式的默认图标没有颜色
+pub fn analyze_icon(icon: &IconResult) -> Option<IconColors> {
+    let image = crate::custom_icon::decode_icon_result(icon).ok()?;
+    analyze(&image)
+}
+
+// 按分类中各图标的强调色推荐分类颜色：取出现最多的颜色区间，再取区间内的平均值
+pub fn suggest_category_color(colors: &[IconColors]) -> Option<CategoryColorSuggestion> {
+    let mut buckets: HashMap<u16, Bucket> = HashMap::new();
+    for rgb in colors.iter().filter_map(|c| parse_hex_color(&c.accent)) {
+        // 推荐颜色用更粗的区间，每通道 3 位
+        let [r, g, b] = rgb.map(|v| (v >> 5) as u16);
+        buckets.entry((r << 6) | (g << 3) | b).or_default().add(rgb);
+    }
+
+    // 数量相同时优先更鲜明的颜色
+    let bucket = buckets.values().max_by(|a, b| {
+        a.count.cmp(&b.count)
+            .then(vividness(a.average()).total_cmp(&vividness(b.average())))
+    })?;
+    let color = bucket.average();
+
+    Some(CategoryColorSuggestion {
+        color: to_hex(color),
+        text_color: to_hex(text_color(color)),
+        sample_count: colors.len(),
+    })
+}
+
+fn to_hex(rgb: [u8; 3]) -> String {
+    format!("#{:02X}{:02X}{:02X}", rgb[0], rgb[1], rgb[2])
+}
+
+// 解析 #RRGGBB 或 #RGB（# 可省略）；颜色可能来自用户输入，先确认全是 ASCII 十六进制数字再按字节切分
+pub fn parse_hex_color(color: &str) -> Option<[u8; 3]> {
+    let hex = color.trim();
+    let hex = hex.strip_prefix('#').unwrap_or(hex);
+    if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |s: &str| u8::from_str_radix(s, 16).ok();
+    match hex.len() {
+        6 => Some([channel(&hex[0..2])?, channel(&hex[2..4])?, channel(&hex[4..6])?]),
+        3 => {
+            let expand = |i: usize| channel(&hex[i..i + 1]).map(|v| v * 17);
+            Some([expand(0)?, expand(1)?, expand(2)?])
+        }
+        _ => None,
+    }
+}
+
+// sRGB 相对亮度
+fn luminance(rgb: [u8; 3]) -> f32 {
+    let linear = rgb.map(|v| {
+        let c = v as f32 / 255.0;
+        if c <= 0.03928 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+    });
+    0.2126 * linear[0] + 0.7152 * linear[1] + 0.0722 * linear[2]
+}
+
+fn contrast_ratio(a: [u8; 3], b: [u8; 3]) -> f32 {
+    let (la, lb) = (luminance(a), luminance(b));
+    (la.max(lb) + 0.05) / (la.min(lb) + 0.05)
+}
+
+fn text_color(background: [u8; 3]) -> [u8; 3] {
+    const BLACK: [u8; 3] = [0, 0, 0];
+    const WHITE: [u8; 3] = [255, 255, 255];
+    if contrast_ratio(background, WHITE) >= contrast_ratio(background, BLACK) {
+        WHITE
+    } else {
+        BLACK
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_hex_colors() {
+        assert_eq!(parse_hex_color("#3B82F6"), Some([0x3b, 0x82, 0xf6]));
+        assert_eq!(parse_hex_color(" 3b82f6 "), Some([0x3b, 0x82, 0xf6]));
+        assert_eq!(parse_hex_color("#fa0"), Some([0xff, 0xaa, 0x00]));
+        assert_eq!(parse_hex_color(&to_hex([1, 2, 3])), Some([1, 2, 3]));
+    }
+
+    #[test]
+    fn rejects_malformed_colors() {
+        for color in ["", "#", "#12345", "#1234567", "#ggg", "red", "+f+f+f", "€", "a€bc", "#ab€", "€€"] {
+            assert_eq!(parse_hex_color(color), None, "{}", color);
+        }
+    }
+}
@@ -0,0 +1,589 @@
+use crate::models::{IconColors, IconResult};
+use base64::{Engine as _, engine::general_purpose};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// 目录结构：<cache_dir>/icons/v<版本>/<指纹>_<尺寸>_<变体>.<格式> 以及 index.json
+// 提取逻辑变化时提升 icon_extractor::EXTRACTOR_VERSION，旧版本目录会在启动时整体删除
+const ICON_DIR_NAME: &str = "icons";
+const INDEX_FILE_NAME: &str = "index.json";
+
+// 访问时间只在内存中更新，间隔超过该秒数才写回索引
+const INDEX_FLUSH_INTERVAL: u64 = 30;
+
+// 图标先写入临时文件再改名，同一键的并发写入各用不同的临时文件
+static TEMP_FILE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+// 磁盘缓存的键：文件指纹 + 尺寸 + 变体；文件指纹由 fingerprint() 计算
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IconKey {
+    pub fingerprint: String,
+    pub size: u32,
+    pub variant: String,
+}
+
+impl IconKey {
+    pub fn new(fingerprint: &str, size: u32, variant: &str) -> Self {
+        Self {
+            fingerprint: fingerprint.to_string(),
+            size,
+            variant: variant.to_string(),
+        }
+    }
+
+    fn file_stem(&self) -> String {
+        format!("{}_{}_{}", crate::utils::sanitize_filename(&self.fingerprint), self.size, crate::utils::sanitize_filename(&self.variant))
+    }
+}
+
+// 索引中的一条记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskEntry {
+    pub file_path: String,  // 来源文件，用于启动时预热内存缓存
+    pub fingerprint: String,
+    pub size: u32,
+    pub variant: String,
+    pub icon_format: String,
+    #[serde(default)]
+    pub data_uri_prefix: Option<String>,  // 原始数据是 data URI 时的前缀，例如 data:image/png;base64,
+    pub bytes: u64,
+    pub file_size: u64,
+    pub last_modified: u64,
+    pub last_access: u64,  // 毫秒
+    #[serde(default)]
+    pub colors: Option<IconColors>,
+    #[serde(default)]
+    pub source_fingerprint: Option<String>,  // 写入时来源文件的元数据指纹，用于复用已计算的内容指纹
+}
+
+#[derive(Debug)]
+struct DiskState {
+    dir: Option<PathBuf>,
+    entries: HashMap<String, DiskEntry>,  // 键为 file_stem
+    by_source: HashMap<String, String>,  // 来源文件的元数据指纹 → 使用它的一个 file_stem
+    total_bytes: u64,
+    max_bytes: u64,
+    evictions: u64,
+    dirty: bool,
+    last_flush: u64,
+    open_error: Option<String>,  // 打开缓存目录失败的原因，此时只使用内存缓存
+}
+
+#[derive(Debug, Clone)]
+pub struct DiskIconCache {
+    state: Arc<Mutex<DiskState>>,
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn now_secs() -> u64 {
+    now_millis() / 1000
+}
+
+impl DiskIconCache {
+    pub fn new(max_bytes: u64) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(DiskState {
+                dir: None,
+                entries: HashMap::new(),
+                by_source: HashMap::new(),
+                total_bytes: 0,
+                max_bytes,
+                evictions: 0,
+                dirty: false,
+                last_flush: 0,
+                open_error: None,
+            })),
+        }
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, DiskState>, String> {
+        self.state.lock().map_err(|e| format!("Failed to lock disk icon cache: {}", e))
+    }
+
+    // 打开缓存目录；失败时记录原因，get/put 在目录未打开时不做任何事
+    pub fn open(&self, cache_dir: &Path) -> Result<(), String> {
+        let result = self.open_dir(cache_dir);
+        self.lock()?.open_error = result.as_ref().err().cloned();
+        result
+    }
+
+    // 删除旧版本的目录，加载索引并丢弃文件已不存在的记录
+    fn open_dir(&self, cache_dir: &Path) -> Result<(), String> {
+        let root = cache_dir.join(ICON_DIR_NAME);
+        let version_dir = format!("v{}", crate::icon_extractor::EXTRACTOR_VERSION);
+        let dir = root.join(&version_dir);
+
+        fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create icon cache directory: {}", e))?;
+
+        if let Ok(read_dir) = fs::read_dir(&root) {
+            for entry in read_dir.flatten() {
+                if entry.file_name() != version_dir.as_str() {
+                    let _ = fs::remove_dir_all(entry.path());
+                }
+            }
+        }
+
+        let mut entries: HashMap<String, DiskEntry> = fs::read_to_string(dir.join(INDEX_FILE_NAME))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        entries.retain(|stem, entry| dir.join(format!("{}.{}", stem, entry.icon_format)).exists());
+
+        // 删除索引中没有记录的文件（例如写入索引前程序退出）
+        if let Ok(read_dir) = fs::read_dir(&dir) {
+            for entry in read_dir.flatten() {
+                let name = entry.file_name().to_string_lossy().to_string();
+                let indexed = name.rsplit_once('.')
+                    .is_some_and(|(stem, ext)| entries.get(stem).is_some_and(|e| e.icon_format == ext));
+                if name != INDEX_FILE_NAME && !indexed {
+                    let _ = fs::remove_file(entry.path());
+                }
+            }
+        }
+
+        let mut state = self.lock()?;
+        state.total_bytes = entries.values().map(|e| e.bytes).sum();
+        state.by_source = entries.iter()
+            .filter_map(|(stem, entry)| Some((entry.source_fingerprint.clone()?, stem.clone())))
+            .collect();
+        state.entries = entries;
+        state.dir = Some(dir);
+        state.dirty = true;
+        state.last_flush = now_secs();
+        drop(state);
+
+        self.flush()
+    }
+
+    pub fn get(&self, key: &IconKey) -> Option<IconResult> {
+        let stem = key.file_stem();
+        let (path, format, prefix, colors) = {
+            let state = self.lock().ok()?;
+            let dir = state.dir.as_ref()?;
+            let entry = state.entries.get(&stem)?;
+            (
+                dir.join(format!("{}.{}", stem, entry.icon_format)),
+                entry.icon_format.clone(),
+                entry.data_uri_prefix.clone().unwrap_or_default(),
+                entry.colors.clone(),
+            )
+        };
+
+        let Ok(bytes) = fs::read(&path) else {
+            // 文件被外部删除，移除记录
+            self.remove(&stem);
+            return None;
+        };
+
+        let should_flush = {
+            let mut state = self.lock().ok()?;
+            if let Some(entry) = state.entries.get_mut(&stem) {
+                entry.last_access = now_millis();
+            }
+            state.dirty = true;
+            Self::flush_due(&state)
+        };
+        if should_flush {
+            let _ = self.flush();
+        }
+
+        Some(IconResult {
+            icon_data: format!("{}{}", prefix, general_purpose::STANDARD.encode(bytes)),
+            icon_format: format,
+            from_cache: true,
+            file_hash: Some(key.fingerprint.clone()),
+            size: key.size,
+            colors,
+        })
+    }
+
+    // 来源文件的缓存指纹：内容指纹加扩展名（同样内容的文件按扩展名可能显示不同的图标）；
+    // 元数据未变化时沿用索引中已记录的内容指纹，不再读取文件；目录等无法计算内容指纹时使用元数据指纹
+    pub fn fingerprint(&self, file_path: &str) -> Option<String> {
+        let path = Path::new(file_path);
+        let meta = crate::fingerprint::metadata(path).ok()?.to_string();
+
+        let known = {
+            let state = self.lock().ok()?;
+            state.by_source.get(&meta)
+                .and_then(|stem| state.entries.get(stem))
+                .map(|e| e.fingerprint.clone())
+        };
+        if known.is_some() {
+            return known;
+        }
+
+        let Ok(content) = crate::fingerprint::content(path) else { return Some(meta) };
+        let extension = path.extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        Some(format!("{}.{}", content, extension))
+    }
+
+    // 写入图标；超过容量时按最近访问时间淘汰
+    pub fn put(&self, key: &IconKey, file_path: &str, icon: &IconResult) -> Result<(), String> {
+        // 图标数据可能是纯 base64，也可能是完整的 data URI
+        let (prefix, payload) = match icon.icon_data.split_once(',') {
+            Some((head, payload)) if head.starts_with("data:") => (Some(format!("{},", head)), payload),
+            _ => (None, icon.icon_data.as_str()),
+        };
+        let bytes = general_purpose::STANDARD.decode(payload)
+            .map_err(|e| format!("Failed to decode icon data: {}", e))?;
+        if bytes.len() as u64 > self.max_bytes() {
+            return Ok(());
+        }
+
+        let (file_size, last_modified) = fs::metadata(file_path)
+            .map(|m| {
+                let modified = m.modified().ok()
+                    .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0);
+                (m.len(), modified)
+            })
+            .unwrap_or((0, 0));
+        let source_fingerprint = crate::fingerprint::metadata(Path::new(file_path)).ok().map(|f| f.to_string());
+
+        let stem = key.file_stem();
+        let format = crate::utils::sanitize_filename(&icon.icon_format);
+        let file_name = format!("{}.{}", stem, format);
+
+        // 在锁外写入临时文件，持锁时只做改名和索引更新；临时文件没有索引记录，残留时下次打开会被删除
+        let dir = self.lock()?.dir.clone().ok_or("Disk icon cache is not open")?;
+        let temp_path = dir.join(format!("{}.{}.tmp", file_name, TEMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)));
+        fs::write(&temp_path, &bytes)
+            .map_err(|e| format!("Failed to write icon cache file: {}", e))?;
+
+        let mut state = self.lock()?;
+        if state.dir.as_ref() != Some(&dir) {
+            let _ = fs::remove_file(&temp_path);
+            return Err("Disk icon cache was reopened".to_string());
+        }
+        if let Err(e) = fs::rename(&temp_path, dir.join(&file_name)) {
+            let _ = fs::remove_file(&temp_path);
+            return Err(format!("Failed to replace icon cache file: {}", e));
+        }
+
+        let entry = DiskEntry {
+            file_path: file_path.to_string(),
+            fingerprint: key.fingerprint.clone(),
+            size: key.size,
+            variant: key.variant.clone(),
+            icon_format: format.clone(),
+            data_uri_prefix: prefix,
+            bytes: bytes.len() as u64,
+            file_size,
+            last_modified,
+            last_access: now_millis(),
+            colors: icon.colors.clone(),
+            source_fingerprint: source_fingerprint.clone(),
+        };
+
+        if let Some(old) = Self::remove_entry(&mut state, &stem) {
+            if old.icon_format != format {
+                let _ = fs::remove_file(dir.join(format!("{}.{}", stem, old.icon_format)));
+            }
+        }
+        if let Some(source_fingerprint) = source_fingerprint {
+            state.by_source.insert(source_fingerprint, stem.clone());
+        }
+        state.entries.insert(stem.clone(), entry);
+        state.total_bytes += bytes.len() as u64;
+
+        Self::evict(&mut state, &dir, &stem);
+        state.dirty = true;
+        let should_flush = Self::flush_due(&state);
+        drop(state);
+
+        // 索引按间隔写回，退出时由 flush() 写入剩余的修改
+        if should_flush {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    // 修改容量，超出的部分立即淘汰
+    pub fn set_max_bytes(&self, max_bytes: u64) -> Result<(), String> {
+        let mut state = self.lock()?;
+        state.max_bytes = max_bytes;
+        if let Some(dir) = state.dir.clone() {
+            Self::evict(&mut state, &dir, "");
+            state.dirty = true;
+        }
+        drop(state);
+
+        self.flush()
+    }
+
+    // 淘汰最久未访问的条目直到低于容量，keep 为刚写入的条目
+    fn evict(state: &mut DiskState, dir: &Path, keep: &str) {
+        if state.total_bytes <= state.max_bytes {
+            return;
+        }
+
+        let mut by_access: Vec<(u64, String)> = state.entries.iter()
+            .filter(|(stem, _)| stem.as_str() != keep)
+            .map(|(stem, entry)| (entry.last_access, stem.clone()))
+            .collect();
+        by_access.sort();
+
+        for (_, stem) in by_access {
+            if state.total_bytes <= state.max_bytes {
+                break;
+            }
+            if let Some(entry) = Self::remove_entry(state, &stem) {
+                let _ = fs::remove_file(dir.join(format!("{}.{}", stem, entry.icon_format)));
+                state.evictions += 1;
+            }
+        }
+    }
+
+    // 从索引中移除一条记录，同时更新占用字节数和来源指纹的映射
+    fn remove_entry(state: &mut DiskState, stem: &str) -> Option<DiskEntry> {
+        let entry = state.entries.remove(stem)?;
+        state.total_bytes -= entry.bytes;
+        if let Some(source_fingerprint) = &entry.source_fingerprint {
+            if state.by_source.get(source_fingerprint).is_some_and(|s| s == stem) {
+                state.by_source.remove(source_fingerprint);
+            }
+        }
+        Some(entry)
+    }
+
+    fn remove(&self, stem: &str) {
+        if let Ok(mut state) = self.lock() {
+            if Self::remove_entry(&mut state, stem).is_some() {
+                state.dirty = true;
+            }
+        }
+    }
+
+    fn flush_due(state: &DiskState) -> bool {
+        now_secs().saturating_sub(state.last_flush) > INDEX_FLUSH_INTERVAL
+    }
+
+    // 把索引写回磁盘
+    pub fn flush(&self) -> Result<(), String> {
+        let mut state = self.lock()?;
+        let Some(dir) = state.dir.clone() else { return Ok(()) };
+        if !state.dirty {
+            return Ok(());
+        }
+
+        let content = serde_json::to_string(&state.entries)
+            .map_err(|e| format!("Failed to serialize icon cache index: {}", e))?;
+        let temp_path = dir.join(format!("{}.tmp", INDEX_FILE_NAME));
+        fs::write(&temp_path, content)
+            .map_err(|e| format!("Failed to write icon cache index: {}", e))?;
+        fs::rename(&temp_path, dir.join(INDEX_FILE_NAME))
+            .map_err(|e| format!("Failed to replace icon cache index: {}", e))?;
+
+        state.dirty = false;
+        state.last_flush = now_secs();
+        Ok(())
+    }
+
+    // 最近访问的条目，用于预热内存缓存
+    pub fn recent_entries(&self, limit: usize) -> Vec<DiskEntry> {
+        let Ok(state) = self.lock() else { return Vec::new() };
+        let mut entries: Vec<DiskEntry> = state.entries.values().cloned().collect();
+        entries.sort_by_key(|e| std::cmp::Reverse(e.last_access));
+        entries.truncate(limit);
+        entries
+    }
+
+    pub fn clear(&self) -> Result<(), String> {
+        let mut state = self.lock()?;
+        if let Some(dir) = state.dir.clone() {
+            for (stem, entry) in state.entries.drain() {
+                let _ = fs::remove_file(dir.join(format!("{}.{}", stem, entry.icon_format)));
+            }
+        }
+        state.by_source.clear();
+        state.total_bytes = 0;
+        state.dirty = true;
+        drop(state);
+
+        self.flush()
+    }
+
+    // (条目数, 占用字节数)
+    pub fn usage(&self) -> (usize, u64) {
+        self.lock().map(|s| (s.entries.len(), s.total_bytes)).unwrap_or((0, 0))
+    }
+
+    pub fn max_bytes(&self) -> u64 {
+        self.lock().map(|s| s.max_bytes).unwrap_or(0)
+    }
+
+    // 启动以来因容量淘汰的条目数
+    pub fn evictions(&self) -> u64 {
+        self.lock().map(|s| s.evictions).unwrap_or(0)
+    }
+
+    pub fn open_error(&self) -> Option<String> {
+        self.lock().ok().and_then(|s| s.open_error.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn icon(bytes: usize, data_uri: bool) -> IconResult {
+        let payload = general_purpose::STANDARD.encode(vec![7u8; bytes]);
+        IconResult {
+            icon_data: if data_uri { format!("data:image/png;base64,{}", payload) } else { payload },
+            icon_format: "png".to_string(),
+            from_cache: false,
+            file_hash: None,
+            size: 32,
+            colors: None,
+        }
+    }
+
+    fn source(dir: &Path, name: &str) -> String {
+        let path = dir.join(name);
+        fs::write(&path, name).unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    fn version_dir(cache_dir: &Path) -> PathBuf {
+        cache_dir.join(ICON_DIR_NAME).join(format!("v{}", crate::icon_extractor::EXTRACTOR_VERSION))
+    }
+
+    #[test]
+    fn stores_and_reads_icons() {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let cache = DiskIconCache::new(1024 * 1024);
+        let key = IconKey::new("abc.exe", 32, "plain");
+        let file = source(cache_dir.path(), "app.exe");
+
+        assert!(cache.put(&key, &file, &icon(100, true)).is_err());
+        cache.open(cache_dir.path()).unwrap();
+        assert!(cache.get(&key).is_none());
+
+        cache.put(&key, &file, &icon(100, true)).unwrap();
+        let cached = cache.get(&key).unwrap();
+        assert!(cached.from_cache);
+        assert_eq!(cached.icon_data, icon(100, true).icon_data);
+        assert_eq!(cached.file_hash.as_deref(), Some("abc.exe"));
+        assert_eq!(cache.usage(), (1, 100));
+        assert!(cache.get(&IconKey::new("abc.exe", 64, "plain")).is_none());
+
+        // 同一个键再次写入时替换原来的记录
+        cache.put(&key, &file, &icon(40, false)).unwrap();
+        assert_eq!(cache.get(&key).unwrap().icon_data, icon(40, false).icon_data);
+        assert_eq!(cache.usage(), (1, 40));
+
+        // 没有临时文件残留；文件被外部删除后记录也会移除
+        let files: Vec<_> = fs::read_dir(version_dir(cache_dir.path())).unwrap().flatten()
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .collect();
+        assert!(files.iter().all(|name| !name.ends_with(".tmp")), "{:?}", files);
+        fs::remove_file(version_dir(cache_dir.path()).join(format!("{}.png", key.file_stem()))).unwrap();
+        assert!(cache.get(&key).is_none());
+        assert_eq!(cache.usage(), (0, 0));
+    }
+
+    #[test]
+    fn reuses_recorded_fingerprints_for_unchanged_sources() {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let cache = DiskIconCache::new(1024 * 1024);
+        cache.open(cache_dir.path()).unwrap();
+        let file = source(cache_dir.path(), "tool.EXE");
+
+        let computed = cache.fingerprint(&file).unwrap();
+        assert!(computed.ends_with(".exe"), "{}", computed);
+
+        // 已有记录时直接使用其中的指纹，不再读取文件内容
+        cache.put(&IconKey::new("recorded", 32, "plain"), &file, &icon(10, false)).unwrap();
+        assert_eq!(cache.fingerprint(&file).as_deref(), Some("recorded"));
+
+        cache.clear().unwrap();
+        assert_eq!(cache.fingerprint(&file), Some(computed));
+    }
+
+    #[test]
+    fn evicts_least_recently_used_icons() {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let cache = DiskIconCache::new(250);
+        cache.open(cache_dir.path()).unwrap();
+        let file = source(cache_dir.path(), "app.exe");
+        let keys: Vec<IconKey> = (0..3).map(|i| IconKey::new(&format!("icon{}", i), 32, "plain")).collect();
+
+        cache.put(&keys[0], &file, &icon(100, false)).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        cache.put(&keys[1], &file, &icon(100, false)).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        // 访问第一个，使第二个成为最久未访问的
+        assert!(cache.get(&keys[0]).is_some());
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        cache.put(&keys[2], &file, &icon(100, false)).unwrap();
+
+        assert!(cache.get(&keys[0]).is_some());
+        assert!(cache.get(&keys[1]).is_none());
+        assert!(cache.get(&keys[2]).is_some());
+        assert_eq!(cache.usage(), (2, 200));
+        assert_eq!(cache.evictions(), 1);
+
+        // 超过容量的单个图标不写入
+        cache.put(&IconKey::new("huge", 32, "plain"), &file, &icon(300, false)).unwrap();
+        assert_eq!(cache.usage(), (2, 200));
+
+        cache.set_max_bytes(100).unwrap();
+        assert_eq!(cache.usage().0, 1);
+    }
+
+    #[test]
+    fn reopens_the_current_version_and_removes_stale_files() {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let file = source(cache_dir.path(), "app.exe");
+        let key = IconKey::new("abc", 32, "plain");
+        {
+            let cache = DiskIconCache::new(1024 * 1024);
+            cache.open(cache_dir.path()).unwrap();
+            cache.put(&key, &file, &icon(10, false)).unwrap();
+            cache.flush().unwrap();
+        }
+
+        let old_version = cache_dir.path().join(ICON_DIR_NAME).join("v0");
+        fs::create_dir_all(&old_version).unwrap();
+        fs::write(old_version.join("old_32_plain.png"), b"old").unwrap();
+        let stray = version_dir(cache_dir.path()).join("stray_32_plain.png.0.tmp");
+        fs::write(&stray, b"partial").unwrap();
+
+        let cache = DiskIconCache::new(1024 * 1024);
+        cache.open(cache_dir.path()).unwrap();
+        assert!(!old_version.exists());
+        assert!(!stray.exists());
+        assert!(cache.get(&key).is_some());
+        assert_eq!(cache.recent_entries(10).len(), 1);
+        assert_eq!(cache.fingerprint(&file).as_deref(), Some("abc"));
+    }
+
+    #[test]
+    fn records_open_failures() {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let blocker = cache_dir.path().join("file");
+        fs::write(&blocker, b"").unwrap();
+
+        let cache = DiskIconCache::new(1024);
+        assert!(cache.open(&blocker).is_err());
+        assert!(cache.open_error().is_some());
+        cache.open(cache_dir.path()).unwrap();
+        assert!(cache.open_error().is_none());
+    }
+}
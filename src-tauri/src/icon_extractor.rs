@@ -1,7 +1,24 @@
-use crate::models::IconResult;
+use crate::models::{IconResult, IconSize};
 use std::path::Path;
 use base64::{Engine as _, engine::general_purpose};
 
+// 提取逻辑的版本号，修改提取结果（尺寸、格式、算法）时递增，使磁盘缓存整体失效
+pub const EXTRACTOR_VERSION: u32 = 8;
+
+// 把解码后的图标按比例缩放到目标尺寸并编码为 PNG，同一尺寸的请求总是得到相同像素的结果
+pub fn encode_png(image: image::DynamicImage, size: u32) -> Result<Vec<u8>, String> {
+    let image = if image.width().max(image.height()) != size {
+        image.resize(size, size, image::imageops::FilterType::Lanczos3)
+    } else {
+        image
+    };
+
+    let mut png_data = Vec::new();
+    image.write_to(&mut std::io::Cursor::new(&mut png_data), image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode icon: {}", e))?;
+    Ok(png_data)
+}
+
 #[cfg(target_os = "windows")]
 mod windows_icon {
     use super::*;
@@ -16,7 +33,8 @@ mod windows_icon {
     use std::ptr;
     use std::mem;
 
-    pub fn extract_icon_windows(file_path: &str, large_icon: bool) -> Result<IconResult, String> {
+    // 系统接口只提供 16 和 32 像素两种尺寸，其他尺寸从较近的一种重采样
+    pub fn extract_icon_windows(file_path: &str, pixels: u32) -> Result<IconResult, String> {
         let wide_path: Vec<u16> = OsStr::new(file_path)
             .encode_wide()
             .chain(std::iter::once(0))
@@ -24,7 +42,7 @@ mod windows_icon {
 
         unsafe {
             let mut file_info: SHFILEINFOW = mem::zeroed();
-            let flags = SHGFI_ICON | if large_icon { SHGFI_LARGEICON } else { SHGFI_SMALLICON };
+            let flags = SHGFI_ICON | if pixels > 16 { SHGFI_LARGEICON } else { SHGFI_SMALLICON };
             
             let result = SHGetFileInfoW(
                 wide_path.as_ptr(),
@@ -43,21 +61,18 @@ mod windows_icon {
                 return Err("Icon handle is null".to_string());
             }
 
-            let icon_data = icon_to_base64(hicon)?;
+            let png_data = icon_to_png(hicon);
             
             // 清理资源
             DestroyIcon(hicon);
 
-            Ok(IconResult {
-                icon_data,
-                icon_format: "png".to_string(),
-                from_cache: false,
-                file_hash: None,
-            })
+            let image = image::load_from_memory_with_format(&png_data?, image::ImageFormat::Png)
+                .map_err(|e| format!("Failed to decode icon: {}", e))?;
+            Ok(png_icon_result(encode_png(image, pixels)?, pixels))
         }
     }
 
-    unsafe fn icon_to_base64(hicon: HICON) -> Result<String, String> {
+    unsafe fn icon_to_png(hicon: HICON) -> Result<Vec<u8>, String> {
         let mut icon_info: ICONINFO = mem::zeroed();
         if GetIconInfo(hicon, &mut icon_info) == 0 {
             return Err("Failed to get icon info".to_string());
@@ -116,10 +131,7 @@ mod windows_icon {
         DeleteObject(icon_info.hbmMask as *mut _);
 
         // 转换为PNG格式
-        let png_data = bitmap_to_png(buffer, width as u32, height as u32, is_top_down)?;
-        let base64_data = general_purpose::STANDARD.encode(&png_data);
-        
-        Ok(format!("data:image/png;base64,{}", base64_data))
+        bitmap_to_png(buffer, width as u32, height as u32, is_top_down)
     }
 
     fn bitmap_to_png(mut buffer: Vec<u8>, width: u32, height: u32, is_top_down: bool) -> Result<Vec<u8>, String> {
@@ -168,70 +180,155 @@ mod windows_icon {
     }
 }
 
+fn png_icon_result(png_data: Vec<u8>, pixels: u32) -> IconResult {
+    IconResult {
+        icon_data: format!("data:image/png;base64,{}", general_purpose::STANDARD.encode(png_data)),
+        icon_format: "png".to_string(),
+        from_cache: false,
+        file_hash: None,
+        size: pixels,
+        colors: None,
+    }
+}
+
+// 图标主题只支持整数缩放倍数，分数倍数按实际像素以 1 倍查找；
+// 尺寸从限制过范围的 pixels() 推算，请求中的原始 size 和 scale 可能任意大
+#[cfg(target_os = "linux")]
+fn theme_icon(path: &Path, size: IconSize) -> Result<IconResult, String> {
+    let pixels = size.pixels();
+    let scale = size.scale as u32;
+    let icon_data = if size.scale >= 1.0 && size.scale.fract() == 0.0 && pixels % scale == 0 {
+        crate::icon_theme::GLOBAL_ICON_THEME.file_icon(path, pixels / scale, scale)?
+    } else {
+        crate::icon_theme::GLOBAL_ICON_THEME.file_icon(path, pixels, 1)?
+    };
+
+    Ok(IconResult {
+        icon_data,
+        icon_format: "png".to_string(),
+        from_cache: false,
+        file_hash: None,
+        size: pixels,
+        colors: None,
+    })
+}
+
+// 文件内嵌的多尺寸图标（程序、.ico、.icns），选取最接近目标尺寸的一张
+fn embedded_icon(path: &Path, extension: &str, pixels: u32) -> Option<IconResult> {
+    let png_data = if crate::pe_icon::is_supported_extension(extension) {
+        crate::pe_icon::extract_from_file(path, pixels).ok()?
+    } else if extension == "icns" {
+        crate::app_bundle::extract_icns_file(path, pixels).ok()?
+    } else {
+        return None;
+    };
+    Some(png_icon_result(png_data, pixels))
+}
+
 // 主要的图标提取函数
-pub fn extract_file_icon(file_path: &str, large_icon: bool) -> Result<IconResult, String> {
+pub fn extract_file_icon(file_path: &str, size: IconSize) -> Result<IconResult, String> {
     let path = Path::new(file_path);
     
     if !path.exists() {
         return Err("File does not exist".to_string());
     }
+    
+    let pixels = size.pixels();
+    let extension = path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase())
+        .unwrap_or_default();
+
+    // 图片、视频和 PDF 优先使用内容缩略图，生成失败时按普通文件取图标
+    if let Some(kind) = crate::thumbnail::media_kind(&extension) {
+        if let Ok(png_data) = crate::thumbnail::generate(path, kind, pixels) {
+            return Ok(png_icon_result(png_data, pixels));
+        }
+    }
 
+    // Windows 上系统接口最大只有 32 像素，更大的尺寸优先使用文件内嵌的图标；
+    // 其他平台用纯 Rust 解析器读取，Windows 上也作为系统接口失败时的后备
+    #[cfg(target_os = "windows")]
+    let native_error = {
+        if pixels > 32 {
+            if let Some(icon) = embedded_icon(path, &extension, pixels) {
+                return Ok(icon);
+            }
+        }
+        match windows_icon::extract_icon_windows(file_path, pixels) {
+            Ok(icon) => return Ok(icon),
+            Err(e) => e,
+        }
+    };
+    
+    if let Some(icon) = embedded_icon(path, &extension, pixels) {
+        return Ok(icon);
+    }
+    
     #[cfg(target_os = "windows")]
     {
-        windows_icon::extract_icon_windows(file_path, large_icon)
+        Err(native_error)
+    }
+    
+    // Linux 按 freedesktop 图标主题查找，找不到时退回默认图标
+    #[cfg(target_os = "linux")]
+    if let Ok(icon) = theme_icon(path, size) {
+        return Ok(icon);
     }
     
     #[cfg(not(target_os = "windows"))]
     {
-        // 非Windows平台使用默认图标
-        let extension = path.extension()
-            .and_then(|ext| ext.to_str())
-            .unwrap_or("");
-        
-        let default_icon = crate::utils::get_default_icon_for_extension(extension);
+        // 其他平台使用默认图标
+        let default_icon = crate::utils::get_default_icon_for_extension(&extension);
         
         Ok(IconResult {
             icon_data: general_purpose::STANDARD.encode(default_icon.as_bytes()),
             icon_format: "text".to_string(),
             from_cache: false,
             file_hash: None,
+            size: pixels,
+            colors: None,
         })
     }
 }
 
 // 支持目录图标提取
-pub fn extract_directory_icon(dir_path: &str, large_icon: bool) -> Result<IconResult, String> {
+pub fn extract_directory_icon(dir_path: &str, size: IconSize) -> Result<IconResult, String> {
     let path = Path::new(dir_path);
     
     if !path.exists() || !path.is_dir() {
         return Err("Directory does not exist".to_string());
     }
+    
+    let pixels = size.pixels();
+    
+    // macOS 应用包读取 Info.plist 中声明的 .icns 图标，在所有平台上都可用
+    if crate::app_bundle::is_app_bundle(path) {
+        if let Ok(png_data) = crate::app_bundle::extract_bundle_icon(path, pixels) {
+            return Ok(png_icon_result(png_data, pixels));
+        }
+    }
 
     #[cfg(target_os = "windows")]
     {
-        windows_icon::extract_icon_windows(dir_path, large_icon)
+        windows_icon::extract_icon_windows(dir_path, pixels)
+    }
+    
+    #[cfg(target_os = "linux")]
+    if let Ok(icon) = theme_icon(path, size) {
+        return Ok(icon);
     }
     
     #[cfg(not(target_os = "windows"))]
     {
-        // 非Windows平台使用默认文件夹图标
+        // 其他平台使用默认文件夹图标
         Ok(IconResult {
             icon_data: general_purpose::STANDARD.encode("📁".as_bytes()),
             icon_format: "text".to_string(),
             from_cache: false,
             file_hash: None,
+            size: pixels,
+            colors: None,
         })
     }
 }
-
-// 批量提取图标
-pub fn extract_icons_batch(file_paths: Vec<String>, large_icon: bool) -> Vec<(String, Result<IconResult, String>)> {
-    file_paths.into_iter().map(|path| {
-        let result = if Path::new(&path).is_dir() {
-            extract_directory_icon(&path, large_icon)
-        } else {
-            extract_file_icon(&path, large_icon)
-        };
-        (path, result)
-    }).collect()
-}
\ No newline at end of file
@@ -0,0 +1,760 @@
+use base64::{Engine as _, engine::general_purpose};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+// 按 freedesktop Icon Theme 规范查找图标：
+//   1. 当前主题及其 Inherits 链
+//   2. hicolor
+//   3. 不属于任何主题的 pixmaps 目录
+// 文件本身的图标来自 .desktop 的 Icon=，或者根据 shared-mime-info 得到的 MIME 类型图标
+
+const FALLBACK_THEME: &str = "hicolor";
+const ICON_EXTENSIONS: &[&str] = &["png", "svg", "svgz"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DirectoryType {
+    Fixed,
+    Scalable,
+    Threshold,
+}
+
+#[derive(Debug, Clone)]
+struct ThemeDirectory {
+    size: u32,
+    scale: u32,
+    kind: DirectoryType,
+    min_size: u32,
+    max_size: u32,
+    threshold: u32,
+}
+
+impl ThemeDirectory {
+    fn matches_size(&self, size: u32, scale: u32) -> bool {
+        if self.scale != scale {
+            return false;
+        }
+        match self.kind {
+            DirectoryType::Fixed => self.size == size,
+            DirectoryType::Scalable => self.min_size <= size && size <= self.max_size,
+            DirectoryType::Threshold => {
+                self.size.saturating_sub(self.threshold) <= size && size <= self.size + self.threshold
+            }
+        }
+    }
+
+    fn size_distance(&self, size: u32, scale: u32) -> u32 {
+        let wanted = size * scale;
+        match self.kind {
+            DirectoryType::Fixed => (self.size * self.scale).abs_diff(wanted),
+            DirectoryType::Scalable => {
+                if wanted < self.min_size * self.scale {
+                    self.min_size * self.scale - wanted
+                } else {
+                    wanted.saturating_sub(self.max_size * self.scale)
+                }
+            }
+            DirectoryType::Threshold => {
+                if wanted < self.size.saturating_sub(self.threshold) * self.scale {
+                    (self.min_size * self.scale).saturating_sub(wanted)
+                } else if wanted > (self.size + self.threshold) * self.scale {
+                    wanted.saturating_sub(self.max_size * self.scale)
+                } else {
+                    0
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct IconTheme {
+    inherits: Vec<String>,
+    directories: Vec<ThemeDirectory>,
+    // 图标名 -> (目录序号, 文件路径)，加载主题时一次性扫描所有目录
+    icons: HashMap<String, Vec<(usize, PathBuf)>>,
+}
+
+// 解析 ini 格式的文件（index.theme、kdeglobals 等）
+fn parse_ini(content: &str) -> HashMap<String, HashMap<String, String>> {
+    let mut sections: HashMap<String, HashMap<String, String>> = HashMap::new();
+    let mut current = String::new();
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if line.starts_with('[') && line.ends_with(']') {
+            current = line[1..line.len() - 1].to_string();
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            sections.entry(current.clone())
+                .or_default()
+                .entry(key.trim().to_string())
+                .or_insert_with(|| value.trim().to_string());
+        }
+    }
+
+    sections
+}
+
+fn split_list(value: Option<&String>) -> Vec<String> {
+    value.map(|v| v.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect())
+        .unwrap_or_default()
+}
+
+// 读取 index.theme 并扫描主题目录中的全部图标文件
+fn load_theme(name: &str, base_dirs: &[PathBuf]) -> Option<IconTheme> {
+    let theme_dirs: Vec<PathBuf> = base_dirs.iter()
+        .map(|base| base.join(name))
+        .filter(|dir| dir.is_dir())
+        .collect();
+
+    let index_path = theme_dirs.iter()
+        .map(|dir| dir.join("index.theme"))
+        .find(|path| path.is_file())?;
+    let sections = parse_ini(&fs::read_to_string(index_path).ok()?);
+    let header = sections.get("Icon Theme")?;
+
+    let mut subdirs = split_list(header.get("Directories"));
+    subdirs.extend(split_list(header.get("ScaledDirectories")));
+
+    let mut directories = Vec::new();
+    let mut icons: HashMap<String, Vec<(usize, PathBuf)>> = HashMap::new();
+    let mut seen = HashSet::new();
+
+    for subdir in subdirs {
+        if !seen.insert(subdir.clone()) {
+            continue;
+        }
+        let Some(section) = sections.get(&subdir) else { continue };
+        let number = |key: &str| section.get(key).and_then(|v| v.parse::<u32>().ok());
+
+        let Some(size) = number("Size") else { continue };
+        let kind = match section.get("Type").map(|t| t.as_str()) {
+            Some("Fixed") => DirectoryType::Fixed,
+            Some("Scalable") => DirectoryType::Scalable,
+            _ => DirectoryType::Threshold,
+        };
+
+        let index = directories.len();
+        directories.push(ThemeDirectory {
+            size,
+            scale: number("Scale").unwrap_or(1),
+            kind,
+            min_size: number("MinSize").unwrap_or(size),
+            max_size: number("MaxSize").unwrap_or(size),
+            threshold: number("Threshold").unwrap_or(2),
+        });
+
+        for theme_dir in &theme_dirs {
+            let Ok(read_dir) = fs::read_dir(theme_dir.join(&subdir)) else { continue };
+            for entry in read_dir.flatten() {
+                let path = entry.path();
+                let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+                if !ICON_EXTENSIONS.contains(&extension) {
+                    continue;
+                }
+                if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+                    icons.entry(stem.to_string()).or_default().push((index, path.clone()));
+                }
+            }
+        }
+    }
+
+    Some(IconTheme {
+        inherits: split_list(header.get("Inherits")),
+        directories,
+        icons,
+    })
+}
+
+// 在单个主题中查找：优先尺寸完全匹配的目录，否则取尺寸差距最小的
+fn lookup_in_theme(theme: &IconTheme, icon: &str, size: u32, scale: u32) -> Option<PathBuf> {
+    let candidates = theme.icons.get(icon)?;
+
+    // 同一目录下按 png、svg 的顺序优先
+    let extension_rank = |path: &Path| {
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+        ICON_EXTENSIONS.iter().position(|e| *e == extension).unwrap_or(usize::MAX)
+    };
+
+    if let Some((_, path)) = candidates.iter()
+        .filter(|(index, _)| theme.directories[*index].matches_size(size, scale))
+        .min_by_key(|(index, path)| (*index, extension_rank(path))) {
+        return Some(path.clone());
+    }
+
+    candidates.iter()
+        .min_by_key(|(index, path)| (theme.directories[*index].size_distance(size, scale), *index, extension_rank(path)))
+        .map(|(_, path)| path.clone())
+}
+
+// 当前桌面环境使用的图标主题
+fn detect_theme_name() -> String {
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+
+    // GNOME 及其衍生桌面
+    if let Ok(output) = std::process::Command::new("gsettings")
+        .args(["get", "org.gnome.desktop.interface", "icon-theme"])
+        .output() {
+        let name = String::from_utf8_lossy(&output.stdout).trim().trim_matches('\'').to_string();
+        if output.status.success() && !name.is_empty() {
+            return name;
+        }
+    }
+
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| home.as_ref().map(|h| h.join(".config")));
+
+    if let Some(config_home) = config_home {
+        // KDE
+        if let Ok(content) = fs::read_to_string(config_home.join("kdeglobals")) {
+            if let Some(name) = parse_ini(&content).get("Icons").and_then(|s| s.get("Theme")) {
+                return name.clone();
+            }
+        }
+
+        // GTK 设置文件
+        for file in ["gtk-4.0/settings.ini", "gtk-3.0/settings.ini"] {
+            if let Ok(content) = fs::read_to_string(config_home.join(file)) {
+                if let Some(name) = parse_ini(&content).get("Settings").and_then(|s| s.get("gtk-icon-theme-name")) {
+                    return name.trim_matches('"').to_string();
+                }
+            }
+        }
+    }
+
+    FALLBACK_THEME.to_string()
+}
+
+// shared-mime-info 数据库中与图标相关的部分
+#[derive(Debug, Default)]
+struct MimeDatabase {
+    extensions: HashMap<String, (u32, String)>,  // 扩展名 -> (权重, MIME 类型)
+    file_names: HashMap<String, String>,  // 完整文件名 -> MIME 类型
+    generic_icons: HashMap<String, String>,
+    icons: HashMap<String, String>,
+}
+
+impl MimeDatabase {
+    fn load(data_dirs: &[PathBuf]) -> Self {
+        let mut database = MimeDatabase::default();
+
+        // 优先级高的目录在前，已有的记录不覆盖
+        for dir in data_dirs {
+            let mime_dir = dir.join("mime");
+
+            if let Ok(content) = fs::read_to_string(mime_dir.join("globs2")) {
+                for line in content.lines().filter(|l| !l.starts_with('#')) {
+                    let mut parts = line.split(':');
+                    let (Some(weight), Some(mime), Some(glob)) = (parts.next(), parts.next(), parts.next()) else { continue };
+                    let weight: u32 = weight.parse().unwrap_or(50);
+                    let case_sensitive = parts.any(|flag| flag.contains("cs"));
+
+                    if let Some(extension) = glob.strip_prefix("*.") {
+                        if extension.contains(['*', '?', '[']) {
+                            continue;
+                        }
+                        let extension = if case_sensitive { extension.to_string() } else { extension.to_lowercase() };
+                        let entry = database.extensions.entry(extension).or_insert((weight, mime.to_string()));
+                        if weight > entry.0 {
+                            *entry = (weight, mime.to_string());
+                        }
+                    } else if !glob.contains(['*', '?', '[']) {
+                        database.file_names.entry(glob.to_string()).or_insert_with(|| mime.to_string());
+                    }
+                }
+            }
+
+            for (file, map) in [("generic-icons", &mut database.generic_icons), ("icons", &mut database.icons)] {
+                if let Ok(content) = fs::read_to_string(mime_dir.join(file)) {
+                    for line in content.lines() {
+                        if let Some((mime, icon)) = line.split_once(':') {
+                            map.entry(mime.to_string()).or_insert_with(|| icon.to_string());
+                        }
+                    }
+                }
+            }
+        }
+
+        database
+    }
+
+    fn mime_type(&self, path: &Path) -> Option<&str> {
+        let file_name = path.file_name()?.to_str()?;
+        if let Some(mime) = self.file_names.get(file_name) {
+            return Some(mime);
+        }
+
+        // 支持 .tar.gz 这样的多段扩展名，取最长的匹配；
+        // 在原文件名上取后缀再转小写，转小写可能改变字节长度，不能用小写后的下标切原文件名
+        file_name.char_indices()
+            .filter(|(_, c)| *c == '.')
+            .find_map(|(i, _)| {
+                let extension = &file_name[i + 1..];
+                self.extensions.get(extension)
+                    .or_else(|| self.extensions.get(&extension.to_lowercase()))
+            })
+            .map(|(_, mime)| mime.as_str())
+    }
+
+    // MIME 类型对应的候选图标名，按规范的优先顺序
+    fn icon_names(&self, mime: &str) -> Vec<String> {
+        let mut names = Vec::new();
+        if let Some(icon) = self.icons.get(mime) {
+            names.push(icon.clone());
+        }
+        names.push(mime.replace('/', "-"));
+        if let Some(icon) = self.generic_icons.get(mime) {
+            names.push(icon.clone());
+        }
+        if let Some((media, _)) = mime.split_once('/') {
+            names.push(format!("{}-x-generic", media));
+        }
+        names
+    }
+}
+
+pub struct IconThemeResolver {
+    base_dirs: Vec<PathBuf>,
+    pixmap_dirs: Vec<PathBuf>,
+    theme_name: String,
+    themes: Mutex<HashMap<String, Option<Arc<IconTheme>>>>,
+    lookups: Mutex<HashMap<(String, u32, u32), Option<PathBuf>>>,
+    mime: MimeDatabase,
+}
+
+impl IconThemeResolver {
+    pub fn new() -> Self {
+        let data_dirs = crate::utils::xdg_data_dirs();
+
+        // 规范中的查找目录：$HOME/.icons、$XDG_DATA_DIRS/icons、/usr/share/pixmaps
+        let mut base_dirs = Vec::new();
+        if let Some(home) = std::env::var_os("HOME") {
+            base_dirs.push(PathBuf::from(home).join(".icons"));
+        }
+        base_dirs.extend(data_dirs.iter().map(|d| d.join("icons")));
+
+        let mut pixmap_dirs: Vec<PathBuf> = data_dirs.iter().map(|d| d.join("pixmaps")).collect();
+        pixmap_dirs.push(PathBuf::from("/usr/share/pixmaps"));
+        pixmap_dirs.dedup();
+
+        Self {
+            base_dirs,
+            pixmap_dirs,
+            theme_name: detect_theme_name(),
+            themes: Mutex::new(HashMap::new()),
+            lookups: Mutex::new(HashMap::new()),
+            mime: MimeDatabase::load(&data_dirs),
+        }
+    }
+
+    fn theme(&self, name: &str) -> Option<Arc<IconTheme>> {
+        let mut themes = self.themes.lock().ok()?;
+        themes.entry(name.to_string())
+            .or_insert_with(|| load_theme(name, &self.base_dirs).map(Arc::new))
+            .clone()
+    }
+
+    // 在主题及其继承链中查找，visited 防止循环继承
+    fn lookup_in_tree(&self, name: &str, icon: &str, size: u32, scale: u32, visited: &mut HashSet<String>) -> Option<PathBuf> {
+        if !visited.insert(name.to_string()) {
+            return None;
+        }
+        let theme = self.theme(name)?;
+        if let Some(path) = lookup_in_theme(&theme, icon, size, scale) {
+            return Some(path);
+        }
+        theme.inherits.iter()
+            .find_map(|parent| self.lookup_in_tree(parent, icon, size, scale, visited))
+    }
+
+    // 按图标名查找图标文件；名称是绝对路径时直接使用
+    pub fn find_icon(&self, icon: &str, size: u32, scale: u32) -> Option<PathBuf> {
+        if icon.is_empty() {
+            return None;
+        }
+        if Path::new(icon).is_absolute() {
+            return Path::new(icon).is_file().then(|| PathBuf::from(icon));
+        }
+
+        let key = (icon.to_string(), size, scale);
+        if let Some(cached) = self.lookups.lock().ok().and_then(|l| l.get(&key).cloned()) {
+            return cached;
+        }
+
+        let mut visited = HashSet::new();
+        let found = self.lookup_in_tree(&self.theme_name, icon, size, scale, &mut visited)
+            .or_else(|| self.lookup_in_tree(FALLBACK_THEME, icon, size, scale, &mut visited))
+            .or_else(|| {
+                self.pixmap_dirs.iter()
+                    .flat_map(|dir| ICON_EXTENSIONS.iter().map(move |ext| dir.join(format!("{}.{}", icon, ext))))
+                    .find(|path| path.is_file())
+            });
+
+        if let Ok(mut lookups) = self.lookups.lock() {
+            lookups.insert(key, found.clone());
+        }
+        found
+    }
+
+    // 文件对应的候选图标名
+    fn icon_names_for(&self, path: &Path) -> Vec<String> {
+        if path.is_dir() {
+            let home = std::env::var_os("HOME").map(PathBuf::from);
+            if home.as_deref() == Some(path) {
+                return vec!["user-home".to_string(), "folder".to_string()];
+            }
+            return vec!["folder".to_string()];
+        }
+
+        let extension = path.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase());
+        if extension.as_deref() == Some("desktop") {
+            let icon = fs::read_to_string(path).ok()
+                .and_then(|content| crate::app_catalog::parse_desktop_entry(&content, path, "desktop"))
+                .and_then(|app| app.icon);
+            if let Some(icon) = icon {
+                return vec![icon, "application-x-executable".to_string()];
+            }
+        }
+
+        let mut names = self.mime.mime_type(path)
+            .map(|mime| self.mime.icon_names(mime))
+            .unwrap_or_default();
+
+        if is_executable(path) {
+            names.push("application-x-executable".to_string());
+        }
+        names.push("text-x-generic".to_string());
+        names.push("unknown".to_string());
+        names
+    }
+
+    // 查找文件的图标并渲染为 PNG data URI
+    pub fn file_icon(&self, path: &Path, size: u32, scale: u32) -> Result<String, String> {
+        let icon_path = self.icon_names_for(path).iter()
+            .find_map(|name| self.find_icon(name, size, scale))
+            .ok_or_else(|| format!("No icon found for {}", path.display()))?;
+
+        let png = render_icon(&icon_path, size * scale)?;
+        Ok(format!("data:image/png;base64,{}", general_purpose::STANDARD.encode(png)))
+    }
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(_path: &Path) -> bool {
+    false
+}
+
+// 把图标文件渲染为指定像素尺寸的 PNG；SVG 用 resvg 栅格化，位图过大时缩小
+pub fn render_icon(path: &Path, pixels: u32) -> Result<Vec<u8>, String> {
+    let data = fs::read(path)
+        .map_err(|e| format!("Failed to read icon {}: {}", path.display(), e))?;
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+
+    if extension == "svg" || extension == "svgz" {
+        return render_svg(&data, pixels);
+    }
+
+    let image = image::load_from_memory(&data)
+        .map_err(|e| format!("Failed to decode icon {}: {}", path.display(), e))?;
+    crate::icon_extractor::encode_png(image, pixels)
+}
+
+// 保持宽高比缩放到 pixels x pixels 的画布中
+pub fn render_svg(data: &[u8], pixels: u32) -> Result<Vec<u8>, String> {
+    use resvg::{tiny_skia, usvg};
+
+    let tree = usvg::Tree::from_data(data, &usvg::Options::default())
+        .map_err(|e| format!("Failed to parse SVG: {}", e))?;
+
+    let size = tree.size();
+    let scale = pixels as f32 / size.width().max(size.height());
+    let mut pixmap = tiny_skia::Pixmap::new(pixels, pixels)
+        .ok_or("Invalid SVG render size")?;
+    resvg::render(&tree, tiny_skia::Transform::from_scale(scale, scale), &mut pixmap.as_mut());
+
+    pixmap.encode_png().map_err(|e| format!("Failed to encode SVG: {}", e))
+}
+
+// 全局图标主题解析器，首次使用时检测主题并加载 MIME 数据库
+lazy_static::lazy_static! {
+    pub static ref GLOBAL_ICON_THEME: IconThemeResolver = IconThemeResolver::new();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn directory(kind: DirectoryType, size: u32, min_size: u32, max_size: u32, threshold: u32) -> ThemeDirectory {
+        ThemeDirectory { size, scale: 1, kind, min_size, max_size, threshold }
+    }
+
+    // base1/Custom（继承 Parent）、base2/Custom 中的补充目录、base1/Parent 和 base2/hicolor
+    fn fixture(root: &Path) -> Vec<PathBuf> {
+        let base1 = root.join("base1");
+        let base2 = root.join("base2");
+
+        write(&base1.join("Custom/index.theme"), "\
+[Icon Theme]
+Name=Custom
+Inherits=Parent, Missing
+Directories=16x16/apps,48x48/apps,scalable/apps
+ScaledDirectories=48x48@2/apps
+
+[16x16/apps]
+Size=16
+Type=Fixed
+
+[48x48/apps]
+Size=48
+Type=Threshold
+
+[48x48@2/apps]
+Size=48
+Scale=2
+Type=Fixed
+
+[scalable/apps]
+Size=64
+MinSize=8
+MaxSize=512
+Type=Scalable
+");
+        write(&base1.join("Custom/16x16/apps/editor.png"), "");
+        write(&base1.join("Custom/48x48/apps/editor.svg"), "");
+        write(&base1.join("Custom/48x48@2/apps/editor.png"), "");
+        write(&base2.join("Custom/48x48/apps/editor.png"), "");
+        write(&base2.join("Custom/48x48/apps/readme.txt"), "");
+        write(&base1.join("Custom/scalable/apps/viewer.svg"), "");
+
+        write(&base1.join("Parent/index.theme"), "\
+[Icon Theme]
+Inherits=Custom
+Directories=32x32/apps
+
+[32x32/apps]
+Size=32
+Type=Fixed
+");
+        write(&base1.join("Parent/32x32/apps/terminal.png"), "");
+        write(&base1.join("Parent/32x32/apps/player.png"), "");
+
+        write(&base2.join("hicolor/index.theme"), "\
+[Icon Theme]
+Directories=48x48/apps
+
+[48x48/apps]
+Size=48
+Type=Fixed
+");
+        write(&base2.join("hicolor/48x48/apps/player.png"), "");
+        write(&base2.join("hicolor/48x48/apps/calculator.png"), "");
+
+        vec![base1, base2]
+    }
+
+    fn resolver(root: &Path) -> IconThemeResolver {
+        write(&root.join("pixmaps/legacy.png"), "");
+        IconThemeResolver {
+            base_dirs: fixture(root),
+            pixmap_dirs: vec![root.join("pixmaps")],
+            theme_name: "Custom".to_string(),
+            themes: Mutex::new(HashMap::new()),
+            lookups: Mutex::new(HashMap::new()),
+            mime: MimeDatabase::default(),
+        }
+    }
+
+    fn relative(root: &Path, path: Option<PathBuf>) -> Option<String> {
+        path.map(|p| p.strip_prefix(root).unwrap().to_string_lossy().replace('\\', "/"))
+    }
+
+    #[test]
+    fn parses_ini_sections() {
+        let sections = parse_ini("\
+# comment
+top=1
+[Icon Theme]
+; another comment
+Name = Adwaita 
+Name=Ignored
+Inherits=a,b
+
+[16x16/apps]
+Size=16
+");
+        assert_eq!(sections[""]["top"], "1");
+        assert_eq!(sections["Icon Theme"]["Name"], "Adwaita");
+        assert_eq!(split_list(sections["Icon Theme"].get("Inherits")), vec!["a", "b"]);
+        assert_eq!(sections["16x16/apps"]["Size"], "16");
+        assert_eq!(split_list(None), Vec::<String>::new());
+    }
+
+    #[test]
+    fn matches_directory_sizes() {
+        let fixed = directory(DirectoryType::Fixed, 48, 48, 48, 2);
+        assert!(fixed.matches_size(48, 1));
+        assert!(!fixed.matches_size(47, 1));
+        assert!(!fixed.matches_size(48, 2));
+
+        let scalable = directory(DirectoryType::Scalable, 64, 16, 256, 2);
+        assert!(scalable.matches_size(16, 1) && scalable.matches_size(256, 1));
+        assert!(!scalable.matches_size(15, 1) && !scalable.matches_size(257, 1));
+
+        let threshold = directory(DirectoryType::Threshold, 48, 48, 48, 2);
+        assert!(threshold.matches_size(46, 1) && threshold.matches_size(50, 1));
+        assert!(!threshold.matches_size(45, 1) && !threshold.matches_size(51, 1));
+    }
+
+    #[test]
+    fn measures_size_distance() {
+        let fixed = directory(DirectoryType::Fixed, 48, 48, 48, 2);
+        assert_eq!(fixed.size_distance(32, 1), 16);
+        assert_eq!(fixed.size_distance(64, 1), 16);
+        assert_eq!(fixed.size_distance(24, 2), 0);
+
+        let scalable = directory(DirectoryType::Scalable, 64, 16, 256, 2);
+        assert_eq!(scalable.size_distance(8, 1), 8);
+        assert_eq!(scalable.size_distance(100, 1), 0);
+        assert_eq!(scalable.size_distance(300, 1), 44);
+
+        let threshold = directory(DirectoryType::Threshold, 48, 48, 48, 2);
+        assert_eq!(threshold.size_distance(49, 1), 0);
+        assert_eq!(threshold.size_distance(32, 1), 16);
+        assert_eq!(threshold.size_distance(64, 1), 16);
+    }
+
+    #[test]
+    fn loads_theme_across_base_directories() {
+        let root = tempfile::tempdir().unwrap();
+        let base_dirs = fixture(root.path());
+
+        let theme = load_theme("Custom", &base_dirs).unwrap();
+        assert_eq!(theme.inherits, vec!["Parent", "Missing"]);
+        assert_eq!(theme.directories.len(), 4);
+        assert_eq!(theme.directories[3].scale, 2);
+        assert_eq!(theme.icons["editor"].len(), 4);
+        assert!(!theme.icons.contains_key("readme"));
+
+        assert!(load_theme("Missing", &base_dirs).is_none());
+    }
+
+    #[test]
+    fn looks_up_icons_in_a_theme() {
+        let root = tempfile::tempdir().unwrap();
+        let theme = load_theme("Custom", &fixture(root.path())).unwrap();
+        let lookup = |icon: &str, size: u32, scale: u32| relative(root.path(), lookup_in_theme(&theme, icon, size, scale));
+
+        // 精确匹配的目录，同一目录下 png 优先于 svg
+        assert_eq!(lookup("editor", 16, 1).as_deref(), Some("base1/Custom/16x16/apps/editor.png"));
+        assert_eq!(lookup("editor", 48, 1).as_deref(), Some("base2/Custom/48x48/apps/editor.png"));
+        assert_eq!(lookup("editor", 47, 1).as_deref(), Some("base2/Custom/48x48/apps/editor.png"));
+        // 缩放目录只匹配相同的缩放倍数
+        assert_eq!(lookup("editor", 48, 2).as_deref(), Some("base1/Custom/48x48@2/apps/editor.png"));
+        // 没有匹配的目录时取尺寸最接近的
+        assert_eq!(lookup("editor", 24, 1).as_deref(), Some("base1/Custom/16x16/apps/editor.png"));
+        assert_eq!(lookup("editor", 128, 1).as_deref(), Some("base1/Custom/48x48@2/apps/editor.png"));
+        assert_eq!(lookup("viewer", 1024, 1).as_deref(), Some("base1/Custom/scalable/apps/viewer.svg"));
+        assert_eq!(lookup("missing", 48, 1), None);
+    }
+
+    #[test]
+    fn follows_inheritance_then_hicolor_then_pixmaps() {
+        let root = tempfile::tempdir().unwrap();
+        let resolver = resolver(root.path());
+        let find = |icon: &str| relative(root.path(), resolver.find_icon(icon, 48, 1));
+
+        assert_eq!(find("editor").as_deref(), Some("base2/Custom/48x48/apps/editor.png"));
+        // 父主题中的图标优先于 hicolor 中更匹配的尺寸；循环继承不会死循环
+        assert_eq!(find("player").as_deref(), Some("base1/Parent/32x32/apps/player.png"));
+        assert_eq!(find("terminal").as_deref(), Some("base1/Parent/32x32/apps/terminal.png"));
+        assert_eq!(find("calculator").as_deref(), Some("base2/hicolor/48x48/apps/calculator.png"));
+        assert_eq!(find("legacy").as_deref(), Some("pixmaps/legacy.png"));
+        assert_eq!(find("nothing"), None);
+        assert_eq!(find(""), None);
+
+        let absolute = root.path().join("pixmaps/legacy.png");
+        assert_eq!(resolver.find_icon(&absolute.to_string_lossy(), 48, 1), Some(absolute));
+    }
+
+    fn mime_database(root: &Path) -> MimeDatabase {
+        let high = root.join("high");
+        let low = root.join("low");
+        write(&high.join("mime/globs2"), "\
+# comment
+50:text/plain:*.txt
+60:application/x-compressed-tar:*.tar.gz
+50:application/gzip:*.gz
+80:text/x-c++src:*.C:cs
+50:text/x-csrc:*.c
+40:text/x-makefile:Makefile
+50:text/x-log:*.log.[0-9]
+");
+        write(&low.join("mime/globs2"), "\
+90:text/x-other:*.txt
+10:text/markdown:*.md
+");
+        write(&high.join("mime/generic-icons"), "text/x-csrc:text-x-script\n");
+        write(&high.join("mime/icons"), "application/gzip:package-x-gzip\n");
+        write(&low.join("mime/icons"), "application/gzip:ignored\n");
+        MimeDatabase::load(&[high, low])
+    }
+
+    #[test]
+    fn resolves_mime_types_from_globs() {
+        let root = tempfile::tempdir().unwrap();
+        let database = mime_database(root.path());
+        let mime = |name: &str| database.mime_type(Path::new(name));
+
+        // 同一扩展名取权重最高的记录
+        assert_eq!(mime("notes.txt"), Some("text/x-other"));
+        assert_eq!(mime("README.MD"), Some("text/markdown"));
+        assert_eq!(mime("backup.tar.gz"), Some("application/x-compressed-tar"));
+        assert_eq!(mime("backup.gz"), Some("application/gzip"));
+        assert_eq!(mime("main.C"), Some("text/x-c++src"));
+        assert_eq!(mime("main.c"), Some("text/x-csrc"));
+        assert_eq!(mime("Makefile"), Some("text/x-makefile"));
+        assert_eq!(mime("server.log.1"), None);
+        assert_eq!(mime("noextension"), None);
+    }
+
+    #[test]
+    fn resolves_mime_types_for_non_ascii_names() {
+        let root = tempfile::tempdir().unwrap();
+        let database = mime_database(root.path());
+        let mime = |name: &str| database.mime_type(Path::new(name));
+
+        // 转小写后字节长度会变化的字符（开尔文符号变短，Ⱥ 变长）
+        assert_eq!(mime("\u{212A}.txt"), Some("text/x-other"));
+        assert_eq!(mime("ȺȺ.a"), None);
+        assert_eq!(mime("ȺȺ.TXT"), Some("text/x-other"));
+        assert_eq!(mime("文档.备份.tar.GZ"), Some("application/x-compressed-tar"));
+        assert_eq!(mime("ÄNDERUNG.\u{212A}"), None);
+    }
+
+    #[test]
+    fn lists_icon_names_for_mime_types() {
+        let root = tempfile::tempdir().unwrap();
+        let database = mime_database(root.path());
+
+        assert_eq!(database.icon_names("application/gzip"), vec!["package-x-gzip", "application-gzip", "application-x-generic"]);
+        assert_eq!(database.icon_names("text/x-csrc"), vec!["text-x-csrc", "text-x-script", "text-x-generic"]);
+    }
+}
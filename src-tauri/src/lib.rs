@@ -4,6 +4,31 @@ mod utils;
 mod commands;
 mod icon_extractor;
 mod icon_cache;
+mod icon_disk_cache;
+mod icon_batch;
+mod custom_icon;
+mod thumbnail;
+mod icon_badges;
+mod icon_colors;
+mod process_monitor;
+mod health_monitor;
+mod target_watcher;
+mod repair;
+mod fingerprint;
+mod hotkeys;
+mod pe_icon;
+mod app_bundle;
+#[cfg(target_os = "linux")]
+mod icon_theme;
+mod search;
+mod pinyin_match;
+mod frecency;
+mod query;
+mod app_catalog;
+mod file_indexer;
+mod calculator;
+mod providers;
+mod shortcut_params;
 
 use commands::*;
 use std::sync::Mutex;
@@ -21,6 +46,13 @@ pub fn run() {
         .manage(DataManagerState::new(None))
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_opener::init())
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(|app, shortcut, event| {
+                    hotkeys::GLOBAL_HOTKEYS.handle(app, shortcut, event);
+                })
+                .build(),
+        )
         .invoke_handler(tauri::generate_handler![
             greet,
             initialize_data_manager,
@@ -31,23 +63,58 @@ pub fn run() {
             update_shortcut,
             delete_shortcut,
             launch_shortcut,
+            get_shortcut_parameters,
             get_categories,
             create_category,
             update_category,
             delete_category,
+            suggest_category_color,
             validate_file_path_command,
             validate_directory_path_command,
             get_file_info_command,
             get_path_info_command,
             get_file_icon_command,
             get_directory_icon_command,
+            get_shortcut_icons,
+            set_shortcut_icon_from_file,
+            set_shortcut_icon_from_clipboard,
+            copy_shortcut_icon,
+            clear_shortcut_icon,
+            collect_orphaned_icons,
             get_icons_batch_command,
+            start_icon_batch,
+            cancel_icon_batch,
             check_file_exists_command,
+            check_shortcut_targets,
+            get_health_monitor_status,
+            get_path_suggestions,
+            apply_path_suggestion,
+            dismiss_path_suggestion,
+            get_custom_icon_status,
+            get_target_watcher_status,
+            suggest_repairs,
+            apply_repairs,
+            get_file_fingerprint,
+            check_target_integrity,
             get_app_config,
             update_app_config,
+            get_hotkey_status,
+            validate_hotkey,
             update_shortcuts_order,
             update_categories_order,
             search_shortcuts,
+            unified_search,
+            get_search_providers,
+            execute_provider_action,
+            validate_search_query,
+            find_alias_conflicts,
+            get_installed_apps,
+            refresh_installed_apps,
+            search_installed_apps,
+            add_installed_app_shortcut,
+            get_file_index_status,
+            rebuild_file_index,
+            pin_indexed_file,
             get_recent_shortcuts,
             get_popular_shortcuts,
             backup_data,
@@ -56,6 +123,12 @@ pub fn run() {
             get_cache_stats,
             preload_icons
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|_app, event| {
+            // 退出前写回磁盘图标缓存中按间隔延迟写入的索引
+            if let tauri::RunEvent::Exit = event {
+                let _ = icon_cache::GLOBAL_ICON_CACHE.flush_disk_cache();
+            }
+        });
 }
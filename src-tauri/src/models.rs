@@ -12,9 +12,23 @@ pub struct Shortcut {
     pub category_id: Option<String>,
     pub usage_count: u32,
     pub last_used: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub frecency: f64,  // 随时间衰减的使用分值，需结合 frecency_updated_at 计算当前值
+    #[serde(default)]
+    pub frecency_updated_at: Option<DateTime<Utc>>,
     pub sort_order: i32,
     pub is_active: bool,
     pub file_exists: bool,
+    #[serde(default)]
+    pub missing_since: Option<DateTime<Utc>>,  // 后台检查发现目标不存在的时间，恢复后清空
+    #[serde(default)]
+    pub target_fingerprint: Option<String>,  // 目标文件的内容指纹（fingerprint 模块的文本形式），用于完整性检查和查找被移动的文件
+    #[serde(default)]
+    pub aliases: Vec<String>,  // 别名，精确命中时排在搜索结果最前
+    #[serde(default)]
+    pub keywords: Vec<String>,  // 关键词，参与搜索匹配
+    #[serde(default)]
+    pub arguments: Option<String>,  // 启动参数，和 file_path 一样可以包含 {name} 占位符
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -30,9 +44,16 @@ impl Shortcut {
             category_id,
             usage_count: 0,
             last_used: None,
+            frecency: 0.0,
+            frecency_updated_at: None,
             sort_order: 0,
             is_active: true,
             file_exists: true,
+            missing_since: None,
+            target_fingerprint: None,
+            aliases: Vec::new(),
+            keywords: Vec::new(),
+            arguments: None,
             created_at: now,
             updated_at: now,
         }
@@ -76,6 +97,9 @@ pub struct CreateShortcutRequest {
     pub category_id: Option<String>,
     pub icon_path: Option<String>,
     pub sort_order: Option<i32>,
+    pub aliases: Option<Vec<String>>,
+    pub keywords: Option<Vec<String>>,
+    pub arguments: Option<String>,
 }
 
 // 更新快捷方式请求
@@ -87,6 +111,140 @@ pub struct UpdateShortcutRequest {
     pub icon_path: Option<String>,
     pub sort_order: Option<i32>,
     pub is_active: Option<bool>,
+    pub aliases: Option<Vec<String>>,
+    pub keywords: Option<Vec<String>>,
+    pub arguments: Option<String>,
+}
+
+// 别名冲突信息
+#[derive(Debug, Clone, Serialize)]
+pub struct AliasConflict {
+    pub alias: String,
+    pub shortcut_ids: Vec<String>,
+}
+
+// 快捷方式排序方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShortcutSortMode {
+    Manual,    // 按 sort_order
+    Name,      // 按名称（中文按拼音）
+    Frecency,  // 按使用频率和最近使用时间
+}
+
+// 搜索结果
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchResult {
+    pub shortcut: Shortcut,
+    pub score: f64,
+    pub matched_field: String,  // 命中字段：name / alias / keyword / path
+    pub match_ranges: Vec<(usize, usize)>,  // 命中字符区间 [start, end)，按字符计
+    pub matched_text: Option<String>,  // 命中别名或关键词时对应的文本
+    pub source: String,  // shortcut：已保存的快捷方式；file：文件索引中尚未保存的文件
+    pub is_saved: bool,
+    pub parameter_input: Option<String>,  // 带参数的快捷方式按名称前缀命中时，剩余的查询文本
+}
+
+// 快捷方式参数，来自 file_path 或 arguments 中的 {name} / {name:默认值} 占位符
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ShortcutParameter {
+    pub name: String,
+    pub default_value: Option<String>,
+}
+
+// 快捷方式目标失效或恢复时发送的事件
+#[derive(Debug, Clone, Serialize)]
+pub struct ShortcutHealthEvent {
+    pub shortcut_id: String,
+    pub name: String,
+    pub file_path: String,
+    pub file_exists: bool,
+    pub missing_since: Option<DateTime<Utc>>,
+}
+
+// 目标被移动后自动更新了 file_path
+#[derive(Debug, Clone, Serialize)]
+pub struct ShortcutPathEvent {
+    pub shortcut_id: String,
+    pub old_path: String,
+    pub new_path: String,
+}
+
+// 待确认的目标路径修复建议
+// reason: renamed（目标被改名）、moved（目标被移到其他位置）、replaced（同目录下出现了新版本）
+#[derive(Debug, Clone, Serialize)]
+pub struct PathSuggestion {
+    pub id: String,
+    pub shortcut_id: String,
+    pub shortcut_name: String,
+    pub old_path: String,
+    pub new_path: String,
+    pub reason: String,
+    pub created_at: DateTime<Utc>,
+}
+
+// 目标文件与记录的内容指纹的比对结果
+// status: unchanged（一致）、modified（内容已变化）、missing（目标不存在）、unrecorded（还没有记录指纹）
+#[derive(Debug, Clone, Serialize)]
+pub struct TargetIntegrity {
+    pub shortcut_id: String,
+    pub status: String,
+    pub recorded: Option<String>,
+    pub current: Option<String>,
+}
+
+// 目标丢失的快捷方式的一个可能的替代目标
+// sources: sibling（同级或带版本号的目录中的同名文件）、versioned（文件名只有版本号不同）、
+//          path（PATH 中的同名程序）、app_catalog（已安装应用）、fingerprint（文件内容相同）
+#[derive(Debug, Clone, Serialize)]
+pub struct RepairCandidate {
+    pub path: String,
+    pub confidence: f64,  // 0 到 1，多个来源指向同一路径时合并提高
+    pub sources: Vec<String>,
+}
+
+// 一个目标丢失的快捷方式及其候选目标，候选按可信度从高到低排列
+#[derive(Debug, Clone, Serialize)]
+pub struct RepairSuggestion {
+    pub shortcut_id: String,
+    pub shortcut_name: String,
+    pub old_path: String,
+    pub candidates: Vec<RepairCandidate>,
+}
+
+// 用户选定的修复
+#[derive(Debug, Clone, Deserialize)]
+pub struct RepairFix {
+    pub shortcut_id: String,
+    pub new_path: String,
+}
+
+// 启动结果：缺少必填参数时不会启动，由界面提示用户填写
+#[derive(Debug, Clone, Serialize)]
+pub struct LaunchOutcome {
+    pub launched: bool,
+    pub missing_parameters: Vec<ShortcutParameter>,
+}
+
+// 已安装应用
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstalledApp {
+    pub id: String,
+    pub name: String,
+    pub exec: String,
+    pub icon: Option<String>,  // 图标名称或路径（来自 .desktop 的 Icon=）
+    pub categories: Vec<String>,
+    pub keywords: Vec<String>,
+    pub source: String,  // desktop / flatpak / snap / start_menu / app_bundle
+    pub source_path: String,
+}
+
+// 已安装应用搜索结果
+#[derive(Debug, Clone, Serialize)]
+pub struct AppSearchResult {
+    pub app: InstalledApp,
+    pub score: f64,
+    pub match_ranges: Vec<(usize, usize)>,
 }
 
 // 创建分类请求
@@ -115,6 +273,7 @@ pub struct FileInfo {
     pub is_file: bool,
     pub is_directory: bool,
     pub file_name: String,
+    pub display_name: Option<String>,  // 应用包等声明的显示名称，用于创建快捷方式
     pub file_extension: Option<String>,
     pub file_size: Option<u64>,
     pub modified_time: Option<DateTime<Utc>>,
@@ -122,12 +281,97 @@ pub struct FileInfo {
 }
 
 // 图标结果
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct IconResult {
     pub icon_data: String,  // Base64编码的图标数据
     pub icon_format: String,  // 图标格式
     pub from_cache: bool,
     pub file_hash: Option<String>,
+    pub size: u32,  // 实际像素尺寸
+    pub colors: Option<IconColors>,  // 文字形式的默认图标没有颜色
+}
+
+// 图标的主色和强调色，以及在其上显示文字时对比度足够的颜色，均为 #RRGGBB
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IconColors {
+    pub dominant: String,
+    pub accent: String,
+    pub dominant_text: String,
+    pub accent_text: String,
+}
+
+// 根据分类中快捷方式的图标推荐的分类颜色
+#[derive(Debug, Clone, Serialize)]
+pub struct CategoryColorSuggestion {
+    pub color: String,
+    pub text_color: String,
+    pub sample_count: usize,  // 参与计算的图标数量
+}
+
+// 带状态角标的快捷方式图标，badges 为实际叠加的角标名称
+#[derive(Debug, Clone, Serialize)]
+pub struct ShortcutIcon {
+    pub shortcut_id: String,
+    pub icon: Option<IconResult>,
+    pub badges: Vec<String>,  // missing / link / running / category
+    pub error: Option<String>,
+}
+
+// 批量提取图标中一个文件、一种尺寸的结果，失败时 error 为原因
+#[derive(Debug, Clone, Serialize)]
+pub struct IconBatchItem {
+    pub job_id: Option<String>,  // 同步调用时为空
+    pub file_path: String,
+    pub size: u32,
+    pub icon: Option<IconResult>,
+    pub error: Option<String>,
+}
+
+// 批量提取图标的进度
+#[derive(Debug, Clone, Serialize)]
+pub struct IconBatchProgress {
+    pub job_id: String,
+    pub total: usize,
+    pub completed: usize,  // 已处理的数量，包含失败
+    pub failed: usize,
+    pub finished: bool,
+    pub cancelled: bool,
+}
+
+// 图标尺寸请求：逻辑尺寸 × 缩放倍数 = 输出的像素尺寸
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct IconSize {
+    pub size: u32,
+    #[serde(default = "default_icon_scale")]
+    pub scale: f32,
+}
+
+fn default_icon_scale() -> f32 {
+    1.0
+}
+
+impl IconSize {
+    pub fn new(size: u32, scale: f32) -> Self {
+        Self { size, scale }
+    }
+
+    // 兼容旧接口的大图标（32）和小图标（16）
+    pub fn from_large(large_icon: bool) -> Self {
+        Self::new(if large_icon { 32 } else { 16 }, 1.0)
+    }
+
+    pub fn pixels(&self) -> u32 {
+        let scale = if self.scale.is_finite() && self.scale > 0.0 { self.scale } else { 1.0 };
+        ((self.size as f32 * scale).round() as u32).clamp(8, 1024)
+    }
+}
+
+// 自定义图标的裁剪区域，单位为原图像素；超出图片的部分会被截掉
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct IconCrop {
+    pub x: u32,
+    pub y: u32,
+    pub size: u32,  // 正方形边长
 }
 
 // 应用配置
@@ -137,6 +381,10 @@ pub struct AppConfig {
     pub behavior: BehaviorConfig,
     pub hotkeys: HotkeyConfig,
     pub advanced: AdvancedConfig,
+    #[serde(default)]
+    pub indexer: IndexerConfig,
+    #[serde(default)]
+    pub search: SearchConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -147,6 +395,28 @@ pub struct UiConfig {
     pub theme: String,
     pub icon_size: u32,
     pub show_labels: bool,
+    #[serde(default)]
+    pub badges: BadgeConfig,
+}
+
+// 图标上叠加的状态角标，各项可单独关闭
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BadgeConfig {
+    pub missing_target: bool,  // 目标不存在时右上角的红色标记
+    pub link_arrow: bool,  // 网址和符号链接左下角的箭头
+    pub running_indicator: bool,  // 目标程序运行中时底部的圆点
+    pub category_tag: bool,  // 左上角的分类颜色
+}
+
+impl Default for BadgeConfig {
+    fn default() -> Self {
+        Self {
+            missing_target: true,
+            link_arrow: true,
+            running_indicator: true,
+            category_tag: false,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -163,12 +433,183 @@ pub struct HotkeyConfig {
     pub quick_search: String,
 }
 
+// 快捷键的注册状态，normalized 为解析后的规范写法，格式错误时为 None
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct HotkeyState {
+    pub accelerator: String,
+    pub normalized: Option<String>,
+    pub registered: bool,
+    pub error: Option<String>,
+}
+
+impl HotkeyState {
+    pub fn new(accelerator: &str) -> Self {
+        Self {
+            accelerator: accelerator.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct HotkeyStatus {
+    pub global_hotkey: HotkeyState,
+    pub quick_search: HotkeyState,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AdvancedConfig {
     pub max_recent_items: u32,
     pub file_check_interval: u32,
     pub backup_enabled: bool,
     pub log_level: String,
+    #[serde(default = "default_icon_cache_memory_mb")]
+    pub icon_cache_memory_mb: u32,  // 内存图标缓存容量
+    #[serde(default = "default_icon_cache_disk_mb")]
+    pub icon_cache_disk_mb: u32,  // 磁盘图标缓存容量
+}
+
+fn default_icon_cache_memory_mb() -> u32 {
+    32
+}
+
+fn default_icon_cache_disk_mb() -> u32 {
+    64
+}
+
+// 文件索引配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexerConfig {
+    pub enabled: bool,
+    pub roots: Vec<IndexRoot>,
+    pub max_results: usize,  // 合并到搜索结果中的文件数量上限
+}
+
+impl Default for IndexerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            roots: Vec::new(),
+            max_results: 20,
+        }
+    }
+}
+
+// 索引根目录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexRoot {
+    pub path: String,
+    #[serde(default)]
+    pub include: Vec<String>,  // 包含的 glob，为空表示全部
+    #[serde(default)]
+    pub exclude: Vec<String>,  // 排除的 glob
+    pub max_depth: Option<usize>,
+    #[serde(default = "default_true")]
+    pub respect_gitignore: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+// 统一搜索配置，providers 的顺序即同分时的展示优先级
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchConfig {
+    pub providers: Vec<ProviderSettings>,
+    pub web_search_engines: Vec<WebSearchEngine>,
+}
+
+impl Default for SearchConfig {
+    fn default() -> Self {
+        Self {
+            providers: vec![
+                ProviderSettings::new("shortcuts", 1.0),
+                ProviderSettings::new("calculator", 1.0),
+                ProviderSettings::new("web_search", 1.0),
+            ],
+            web_search_engines: vec![
+                WebSearchEngine::new("Google", "g", "https://www.google.com/search?q={query}", true),
+                WebSearchEngine::new("百度", "bd", "https://www.baidu.com/s?wd={query}", false),
+                WebSearchEngine::new("Bing", "bing", "https://www.bing.com/search?q={query}", false),
+                WebSearchEngine::new("GitHub", "gh", "https://github.com/search?q={query}", false),
+            ],
+        }
+    }
+}
+
+// 单个搜索提供者的设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderSettings {
+    pub id: String,
+    pub enabled: bool,
+    pub timeout_ms: u64,
+    pub weight: f64,  // 归一化得分的权重
+}
+
+impl ProviderSettings {
+    pub fn new(id: &str, weight: f64) -> Self {
+        Self {
+            id: id.to_string(),
+            enabled: true,
+            timeout_ms: 150,
+            weight,
+        }
+    }
+}
+
+// 网页搜索模板，url 中的 {query} 会被替换为编码后的搜索词
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebSearchEngine {
+    pub name: String,
+    pub keyword: String,
+    pub url: String,
+    #[serde(default)]
+    pub fallback: bool,  // 没有关键字前缀时也作为兜底结果出现
+}
+
+impl WebSearchEngine {
+    pub fn new(name: &str, keyword: &str, url: &str, fallback: bool) -> Self {
+        Self {
+            name: name.to_string(),
+            keyword: keyword.to_string(),
+            url: url.to_string(),
+            fallback,
+        }
+    }
+}
+
+// 搜索提供者返回的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderResult {
+    pub provider_id: String,
+    pub id: String,
+    pub title: String,
+    pub subtitle: Option<String>,
+    pub icon: Option<String>,
+    pub score: f64,  // 提供者内部得分，合并时归一化到 0..1 再乘以权重
+    #[serde(default)]
+    pub match_ranges: Vec<(usize, usize)>,
+    pub action: ProviderAction,
+}
+
+// 选中结果后执行的动作
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum ProviderAction {
+    LaunchShortcut { id: String, input: Option<String> },
+    OpenPath(String),
+    OpenUrl(String),
+    CopyText(String),
+}
+
+// 索引中的文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexedFile {
+    pub path: String,
+    pub name: String,
+    pub root: String,
+    pub is_dir: bool,
+    pub modified: u64,
 }
 
 impl Default for AppConfig {
@@ -181,6 +622,7 @@ impl Default for AppConfig {
                 theme: "light".to_string(),
                 icon_size: 64,
                 show_labels: true,
+                badges: BadgeConfig::default(),
             },
             behavior: BehaviorConfig {
                 auto_sort_enabled: false,
@@ -197,7 +639,11 @@ impl Default for AppConfig {
                 file_check_interval: 300,
                 backup_enabled: true,
                 log_level: "info".to_string(),
+                icon_cache_memory_mb: default_icon_cache_memory_mb(),
+                icon_cache_disk_mb: default_icon_cache_disk_mb(),
             },
+            indexer: IndexerConfig::default(),
+            search: SearchConfig::default(),
         }
     }
 }
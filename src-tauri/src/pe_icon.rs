@@ -0,0 +1,580 @@
+use std::collections::HashMap;
+
+// 纯 Rust 的图标提取，不依赖 WinAPI，任何平台都能处理 Windows 程序和图标文件：
+//   .exe / .dll 等 PE 文件：读取资源节中的 RT_GROUP_ICON 和 RT_ICON
+//   .ico 文件：直接读取图标目录
+// 从图标组中选出最合适的尺寸，统一转换为 PNG
+
+const RT_ICON: u32 = 3;
+const RT_GROUP_ICON: u32 = 14;
+const RESOURCE_DIRECTORY_INDEX: usize = 2;
+
+// 资源嵌套过深说明文件损坏，避免被构造的文件拖入无限递归
+const MAX_RESOURCE_DEPTH: usize = 3;
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16, String> {
+    data.get(offset..offset + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+        .ok_or_else(|| format!("Unexpected end of data at offset {}", offset))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, String> {
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(|| format!("Unexpected end of data at offset {}", offset))
+}
+
+// 图标目录中的一项（.ico 的 ICONDIRENTRY 和资源中的 GRPICONDIRENTRY 字段相同，只是最后一项含义不同）
+#[derive(Debug, Clone)]
+struct IconEntry {
+    width: u32,
+    height: u32,
+    color_count: u8,
+    planes: u16,
+    bit_count: u16,
+    bytes: u32,
+    // .ico 中是数据偏移，资源中是 RT_ICON 的 ID
+    reference: u32,
+}
+
+// 从候选中选择：优先不小于目标尺寸的最小图标，同尺寸取色深最高的；都比目标小时取最大的
+fn choose_entry(entries: &[IconEntry], size: u32) -> Option<&IconEntry> {
+    let depth = |e: &IconEntry| if e.bit_count > 0 { e.bit_count as u32 } else { 8 };
+
+    entries.iter()
+        .filter(|e| e.width >= size)
+        .min_by_key(|e| (e.width, std::cmp::Reverse(depth(e))))
+        .or_else(|| entries.iter().max_by_key(|e| (e.width, depth(e))))
+}
+
+// 把单个图标的数据包装成只有一项的 .ico，交给 image 解码（同时支持 PNG 和 BMP 格式的图标数据）
+fn decode_icon_image(entry: &IconEntry, image_data: &[u8], size: u32) -> Result<Vec<u8>, String> {
+    let image = if image_data.starts_with(b"\x89PNG") {
+        image::load_from_memory_with_format(image_data, image::ImageFormat::Png)
+    } else {
+        let mut ico = Vec::with_capacity(22 + image_data.len());
+        ico.extend_from_slice(&[0, 0, 1, 0, 1, 0]);
+        ico.push(if entry.width >= 256 { 0 } else { entry.width as u8 });
+        ico.push(if entry.height >= 256 { 0 } else { entry.height as u8 });
+        ico.push(entry.color_count);
+        ico.push(0);
+        ico.extend_from_slice(&entry.planes.to_le_bytes());
+        ico.extend_from_slice(&entry.bit_count.to_le_bytes());
+        ico.extend_from_slice(&(image_data.len() as u32).to_le_bytes());
+        ico.extend_from_slice(&22u32.to_le_bytes());
+        ico.extend_from_slice(image_data);
+        image::load_from_memory_with_format(&ico, image::ImageFormat::Ico)
+    }
+    .map_err(|e| format!("Failed to decode icon image: {}", e))?;
+
+    crate::icon_extractor::encode_png(image, size)
+}
+
+// 解析图标目录头（ICONDIR / GRPICONDIR），entry_size 为每项的字节数
+fn parse_icon_directory(data: &[u8], entry_size: usize) -> Result<Vec<IconEntry>, String> {
+    if read_u16(data, 0)? != 0 || read_u16(data, 2)? != 1 {
+        return Err("Not an icon directory".to_string());
+    }
+
+    let count = read_u16(data, 4)? as usize;
+    let mut entries = Vec::with_capacity(count);
+
+    for i in 0..count {
+        let offset = 6 + i * entry_size;
+        let dimension = |value: u8| if value == 0 { 256 } else { value as u32 };
+        let reference = if entry_size == 16 {
+            read_u32(data, offset + 12)?
+        } else {
+            read_u16(data, offset + 12)? as u32
+        };
+
+        entries.push(IconEntry {
+            width: dimension(*data.get(offset).ok_or("Truncated icon directory")?),
+            height: dimension(*data.get(offset + 1).ok_or("Truncated icon directory")?),
+            color_count: *data.get(offset + 2).ok_or("Truncated icon directory")?,
+            planes: read_u16(data, offset + 4)?,
+            bit_count: read_u16(data, offset + 6)?,
+            bytes: read_u32(data, offset + 8)?,
+            reference,
+        });
+    }
+
+    Ok(entries)
+}
+
+// 从 .ico 文件中提取最合适尺寸的图标
+pub fn extract_ico(data: &[u8], size: u32) -> Result<Vec<u8>, String> {
+    let entries = parse_icon_directory(data, 16)?;
+    let entry = choose_entry(&entries, size).ok_or("Icon file contains no images")?;
+
+    let start = entry.reference as usize;
+    let image_data = start.checked_add(entry.bytes as usize)
+        .and_then(|end| data.get(start..end))
+        .ok_or("Icon image data is out of bounds")?;
+
+    decode_icon_image(entry, image_data, size)
+}
+
+// PE 文件的节，用于把 RVA 转换为文件偏移
+struct Section {
+    virtual_address: u32,
+    virtual_size: u32,
+    raw_offset: u32,
+    raw_size: u32,
+}
+
+// 资源类型 -> [(资源 ID, 数据)]
+type ResourceTable<'a> = HashMap<u32, Vec<(u32, &'a [u8])>>;
+
+struct PeFile<'a> {
+    data: &'a [u8],
+    sections: Vec<Section>,
+    resource_rva: u32,
+}
+
+impl<'a> PeFile<'a> {
+    fn parse(data: &'a [u8]) -> Result<Self, String> {
+        if data.get(0..2) != Some(b"MZ") {
+            return Err("Not a PE file".to_string());
+        }
+
+        let pe_offset = read_u32(data, 0x3C)? as usize;
+        if data.get(pe_offset..pe_offset + 4) != Some(b"PE\0\0") {
+            return Err("Missing PE signature".to_string());
+        }
+
+        let coff = pe_offset + 4;
+        let section_count = read_u16(data, coff + 2)? as usize;
+        let optional_size = read_u16(data, coff + 16)? as usize;
+        let optional = coff + 20;
+
+        // PE32 和 PE32+ 的数据目录位置不同
+        let (count_offset, directories_offset) = match read_u16(data, optional)? {
+            0x10b => (optional + 92, optional + 96),
+            0x20b => (optional + 108, optional + 112),
+            magic => return Err(format!("Unknown optional header magic {:#x}", magic)),
+        };
+
+        let directory_count = read_u32(data, count_offset)? as usize;
+        if directory_count <= RESOURCE_DIRECTORY_INDEX {
+            return Err("PE file has no resource directory".to_string());
+        }
+        let resource_rva = read_u32(data, directories_offset + RESOURCE_DIRECTORY_INDEX * 8)?;
+        if resource_rva == 0 {
+            return Err("PE file has no resources".to_string());
+        }
+
+        let section_table = optional + optional_size;
+        let sections = (0..section_count)
+            .map(|i| {
+                let offset = section_table + i * 40;
+                Ok(Section {
+                    virtual_size: read_u32(data, offset + 8)?,
+                    virtual_address: read_u32(data, offset + 12)?,
+                    raw_size: read_u32(data, offset + 16)?,
+                    raw_offset: read_u32(data, offset + 20)?,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        Ok(Self { data, sections, resource_rva })
+    }
+
+    // 节头中的字段可能是任意值，计算时不能溢出
+    fn rva_to_offset(&self, rva: u32) -> Option<usize> {
+        self.sections.iter()
+            .find(|s| rva >= s.virtual_address && rva - s.virtual_address < s.virtual_size.max(s.raw_size))
+            .and_then(|s| ((rva - s.virtual_address) as usize).checked_add(s.raw_offset as usize))
+    }
+
+    fn slice(&self, rva: u32, size: u32) -> Option<&'a [u8]> {
+        let start = self.rva_to_offset(rva)?;
+        self.data.get(start..start.checked_add(size as usize)?)
+    }
+
+    // 遍历资源目录树；同一资源的多个语言版本只取第一个
+    fn resources(&self) -> Result<ResourceTable<'a>, String> {
+        let root = self.rva_to_offset(self.resource_rva)
+            .ok_or("Resource directory is outside every section")?;
+
+        let mut resources = ResourceTable::new();
+        for (type_id, type_offset) in self.directory_entries(root, root)? {
+            if type_id != Some(RT_ICON) && type_id != Some(RT_GROUP_ICON) {
+                continue;
+            }
+            let Some(type_offset) = type_offset.directory() else { continue };
+
+            // 命名资源没有数字 ID（图标组常见，RT_ICON 总是数字），记为 0
+            for (name_id, name_offset) in self.directory_entries(root, type_offset)? {
+                if let Some(data) = self.first_leaf(root, name_offset, MAX_RESOURCE_DEPTH - 1)? {
+                    resources.entry(type_id.unwrap_or(0))
+                        .or_default()
+                        .push((name_id.unwrap_or(0), data));
+                }
+            }
+        }
+
+        Ok(resources)
+    }
+
+    // 读取一层资源目录的条目：(数字 ID 或命名, 子节点)
+    fn directory_entries(&self, root: usize, offset: usize) -> Result<Vec<(Option<u32>, ResourceNode)>, String> {
+        let named = read_u16(self.data, offset + 12)? as usize;
+        let ids = read_u16(self.data, offset + 14)? as usize;
+
+        (0..named + ids)
+            .map(|i| {
+                let entry = offset + 16 + i * 8;
+                let name = read_u32(self.data, entry)?;
+                let target = read_u32(self.data, entry + 4)?;
+                let id = if name & 0x8000_0000 == 0 { Some(name) } else { None };
+                let node = if target & 0x8000_0000 != 0 {
+                    ResourceNode::Directory(root + (target & 0x7FFF_FFFF) as usize)
+                } else {
+                    ResourceNode::Data(root + target as usize)
+                };
+                Ok((id, node))
+            })
+            .collect()
+    }
+
+    fn first_leaf(&self, root: usize, node: ResourceNode, depth: usize) -> Result<Option<&'a [u8]>, String> {
+        match node {
+            ResourceNode::Data(offset) => {
+                let rva = read_u32(self.data, offset)?;
+                let size = read_u32(self.data, offset + 4)?;
+                Ok(self.slice(rva, size))
+            }
+            ResourceNode::Directory(_) if depth == 0 => Ok(None),
+            ResourceNode::Directory(offset) => {
+                for (_, child) in self.directory_entries(root, offset)? {
+                    if let Some(data) = self.first_leaf(root, child, depth - 1)? {
+                        return Ok(Some(data));
+                    }
+                }
+                Ok(None)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ResourceNode {
+    Directory(usize),
+    Data(usize),
+}
+
+impl ResourceNode {
+    fn directory(self) -> Option<usize> {
+        match self {
+            ResourceNode::Directory(offset) => Some(offset),
+            ResourceNode::Data(_) => None,
+        }
+    }
+}
+
+// 从 PE 文件中提取主图标（第一个图标组，与资源管理器的行为一致）
+pub fn extract_pe_icon(data: &[u8], size: u32) -> Result<Vec<u8>, String> {
+    let pe = PeFile::parse(data)?;
+    let resources = pe.resources()?;
+
+    let group = resources.get(&RT_GROUP_ICON)
+        .and_then(|groups| groups.first())
+        .map(|(_, data)| *data)
+        .ok_or("PE file has no icon group")?;
+    let icons: HashMap<u32, &[u8]> = resources.get(&RT_ICON)
+        .map(|icons| icons.iter().copied().collect())
+        .unwrap_or_default();
+
+    // 只保留图标组中确实存在的图标
+    let entries: Vec<IconEntry> = parse_icon_directory(group, 14)?
+        .into_iter()
+        .filter(|e| icons.contains_key(&e.reference))
+        .collect();
+    let entry = choose_entry(&entries, size).ok_or("Icon group references no icons")?;
+
+    decode_icon_image(entry, icons[&entry.reference], size)
+}
+
+// 按扩展名判断是否可以用本模块提取
+pub fn is_supported_extension(extension: &str) -> bool {
+    matches!(extension, "exe" | "dll" | "ico" | "cpl" | "scr" | "ocx" | "sys" | "mui")
+}
+
+// 读取文件并提取图标，返回 PNG 数据
+pub fn extract_from_file(path: &std::path::Path, size: u32) -> Result<Vec<u8>, String> {
+    let data = std::fs::read(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+
+    if data.starts_with(b"MZ") {
+        extract_pe_icon(&data, size)
+    } else {
+        extract_ico(&data, size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png_bytes(width: u32, height: u32) -> Vec<u8> {
+        let image = image::DynamicImage::ImageRgba8(image::RgbaImage::from_pixel(width, height, image::Rgba([255, 0, 0, 255])));
+        let mut data = Vec::new();
+        image.write_to(&mut std::io::Cursor::new(&mut data), image::ImageFormat::Png).unwrap();
+        data
+    }
+
+    // 32 位色深的 BMP 图标数据：BITMAPINFOHEADER（高度为两倍）、像素和 AND 掩码
+    fn bmp_icon_bytes(side: u32) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(&40u32.to_le_bytes());
+        data.extend_from_slice(&(side as i32).to_le_bytes());
+        data.extend_from_slice(&(side as i32 * 2).to_le_bytes());
+        data.extend_from_slice(&1u16.to_le_bytes());
+        data.extend_from_slice(&32u16.to_le_bytes());
+        data.extend_from_slice(&[0; 24]);
+        for _ in 0..side * side {
+            data.extend_from_slice(&[0, 255, 0, 255]);  // BGRA
+        }
+        let mask_row = side.div_ceil(32) * 4;
+        data.resize(data.len() + (mask_row * side) as usize, 0);
+        data
+    }
+
+    // (宽度, 色深, 图像数据)
+    fn ico_bytes(images: &[(u32, u16, Vec<u8>)]) -> Vec<u8> {
+        let mut data = vec![0, 0, 1, 0];
+        data.extend_from_slice(&(images.len() as u16).to_le_bytes());
+        let mut offset = 6 + 16 * images.len() as u32;
+        for (width, bit_count, image) in images {
+            let dimension = if *width >= 256 { 0 } else { *width as u8 };
+            data.extend_from_slice(&[dimension, dimension, 0, 0]);
+            data.extend_from_slice(&1u16.to_le_bytes());
+            data.extend_from_slice(&bit_count.to_le_bytes());
+            data.extend_from_slice(&(image.len() as u32).to_le_bytes());
+            data.extend_from_slice(&offset.to_le_bytes());
+            offset += image.len() as u32;
+        }
+        for (_, _, image) in images {
+            data.extend_from_slice(image);
+        }
+        data
+    }
+
+    fn group_bytes(width: u8, icon_id: u16, bytes: u32) -> Vec<u8> {
+        let mut data = vec![0, 0, 1, 0, 1, 0];
+        data.extend_from_slice(&[width, width, 0, 0]);
+        data.extend_from_slice(&1u16.to_le_bytes());
+        data.extend_from_slice(&32u16.to_le_bytes());
+        data.extend_from_slice(&bytes.to_le_bytes());
+        data.extend_from_slice(&icon_id.to_le_bytes());
+        data
+    }
+
+    fn put_u16(data: &mut [u8], offset: usize, value: u16) {
+        data[offset..offset + 2].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn put_u32(data: &mut [u8], offset: usize, value: u32) {
+        data[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    const PE_OFFSET: usize = 0x40;
+    const OPTIONAL_OFFSET: usize = PE_OFFSET + 24;
+    const SECTION_RVA: u32 = 0x1000;
+    const SECTION_OFFSET: usize = 0x200;
+    // 资源树：根目录 -> 类型目录 -> 名称目录 -> 语言目录 -> 数据项
+    const RESOURCE_TREE_SIZE: usize = 160;
+
+    // 只有一个资源节的最小 PE 文件，RT_ICON 1 和 RT_GROUP_ICON 1 各一项
+    fn pe_bytes(pe32_plus: bool, icon: &[u8], group: &[u8]) -> Vec<u8> {
+        let (optional_size, count_offset) = if pe32_plus { (240, 108) } else { (224, 92) };
+        let mut data = vec![0u8; SECTION_OFFSET];
+        data[0..2].copy_from_slice(b"MZ");
+        put_u32(&mut data, 0x3C, PE_OFFSET as u32);
+        data[PE_OFFSET..PE_OFFSET + 4].copy_from_slice(b"PE\0\0");
+        put_u16(&mut data, PE_OFFSET + 6, 1);
+        put_u16(&mut data, PE_OFFSET + 20, optional_size);
+        put_u16(&mut data, OPTIONAL_OFFSET, if pe32_plus { 0x20b } else { 0x10b });
+        put_u32(&mut data, OPTIONAL_OFFSET + count_offset, 16);
+        let directories = OPTIONAL_OFFSET + count_offset + 4;
+        put_u32(&mut data, directories + RESOURCE_DIRECTORY_INDEX * 8, SECTION_RVA);
+
+        let mut resources = vec![0u8; RESOURCE_TREE_SIZE];
+        let directory = |resources: &mut Vec<u8>, offset: usize, id: u32, target: u32| {
+            put_u16(resources, offset + 14, 1);
+            put_u32(resources, offset + 16, id);
+            put_u32(resources, offset + 20, target);
+        };
+        put_u16(&mut resources, 14, 2);
+        put_u32(&mut resources, 16, RT_ICON);
+        put_u32(&mut resources, 20, 0x8000_0000 | 32);
+        put_u32(&mut resources, 24, RT_GROUP_ICON);
+        put_u32(&mut resources, 28, 0x8000_0000 | 56);
+        directory(&mut resources, 32, 1, 0x8000_0000 | 80);
+        directory(&mut resources, 56, 1, 0x8000_0000 | 104);
+        directory(&mut resources, 80, 0x409, 128);
+        directory(&mut resources, 104, 0x409, 144);
+        let icon_rva = SECTION_RVA + RESOURCE_TREE_SIZE as u32;
+        put_u32(&mut resources, 128, icon_rva);
+        put_u32(&mut resources, 132, icon.len() as u32);
+        put_u32(&mut resources, 144, icon_rva + icon.len() as u32);
+        put_u32(&mut resources, 148, group.len() as u32);
+        resources.extend_from_slice(icon);
+        resources.extend_from_slice(group);
+
+        let section = OPTIONAL_OFFSET + optional_size as usize;
+        put_u32(&mut data, section + 8, resources.len() as u32);
+        put_u32(&mut data, section + 12, SECTION_RVA);
+        put_u32(&mut data, section + 16, resources.len() as u32);
+        put_u32(&mut data, section + 20, SECTION_OFFSET as u32);
+
+        data.extend_from_slice(&resources);
+        data
+    }
+
+    fn sample_pe() -> Vec<u8> {
+        let icon = png_bytes(48, 48);
+        let group = group_bytes(48, 1, icon.len() as u32);
+        pe_bytes(false, &icon, &group)
+    }
+
+    fn decoded_size(png: &[u8]) -> (u32, u32) {
+        let image = image::load_from_memory_with_format(png, image::ImageFormat::Png).unwrap();
+        (image.width(), image.height())
+    }
+
+    #[test]
+    fn extracts_png_image_from_ico() {
+        let ico = ico_bytes(&[(48, 32, png_bytes(48, 48))]);
+        assert_eq!(decoded_size(&extract_ico(&ico, 32).unwrap()), (32, 32));
+    }
+
+    #[test]
+    fn extracts_bmp_image_from_ico() {
+        let ico = ico_bytes(&[(16, 32, bmp_icon_bytes(16))]);
+        let png = extract_ico(&ico, 16).unwrap();
+        let image = image::load_from_memory_with_format(&png, image::ImageFormat::Png).unwrap().to_rgba8();
+        assert_eq!(image.dimensions(), (16, 16));
+        assert_eq!(image.get_pixel(3, 3).0, [0, 255, 0, 255]);
+    }
+
+    #[test]
+    fn chooses_smallest_entry_not_below_requested_size() {
+        let entry = |width, bit_count| IconEntry {
+            width,
+            height: width,
+            color_count: 0,
+            planes: 1,
+            bit_count,
+            bytes: 0,
+            reference: width,
+        };
+        let entries = vec![entry(16, 32), entry(48, 8), entry(48, 32), entry(256, 32)];
+        let chosen = choose_entry(&entries, 32).unwrap();
+        assert_eq!((chosen.width, chosen.bit_count), (48, 32));
+        assert_eq!(choose_entry(&entries, 512).unwrap().width, 256);
+        assert!(choose_entry(&[], 32).is_none());
+    }
+
+    #[test]
+    fn extracts_icon_from_pe32_and_pe32_plus() {
+        assert_eq!(decoded_size(&extract_pe_icon(&sample_pe(), 32).unwrap()), (32, 32));
+
+        let icon = bmp_icon_bytes(32);
+        let group = group_bytes(32, 1, icon.len() as u32);
+        let pe = pe_bytes(true, &icon, &group);
+        assert_eq!(decoded_size(&extract_pe_icon(&pe, 32).unwrap()), (32, 32));
+    }
+
+    #[test]
+    fn rejects_malformed_ico() {
+        let png = png_bytes(16, 16);
+        let mut wrong_type = ico_bytes(&[(16, 32, png.clone())]);
+        wrong_type[2] = 2;
+        assert_eq!(extract_ico(&wrong_type, 16).unwrap_err(), "Not an icon directory");
+
+        let empty = ico_bytes(&[]);
+        assert_eq!(extract_ico(&empty, 16).unwrap_err(), "Icon file contains no images");
+
+        let mut out_of_bounds = ico_bytes(&[(16, 32, png.clone())]);
+        put_u32(&mut out_of_bounds, 6 + 12, u32::MAX);
+        assert_eq!(extract_ico(&out_of_bounds, 16).unwrap_err(), "Icon image data is out of bounds");
+
+        let corrupt = ico_bytes(&[(16, 32, vec![0x42; 64])]);
+        assert!(extract_ico(&corrupt, 16).unwrap_err().starts_with("Failed to decode icon image"));
+
+        // 目录或图像数据被截断
+        let ico = ico_bytes(&[(16, 32, png)]);
+        for len in 0..ico.len() {
+            assert!(extract_ico(&ico[..len], 16).is_err(), "truncated to {} bytes", len);
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_pe_headers() {
+        assert_eq!(extract_pe_icon(b"XX", 32).unwrap_err(), "Not a PE file");
+
+        let mut no_signature = sample_pe();
+        no_signature[PE_OFFSET] = b'X';
+        assert_eq!(extract_pe_icon(&no_signature, 32).unwrap_err(), "Missing PE signature");
+
+        let mut bad_magic = sample_pe();
+        put_u16(&mut bad_magic, OPTIONAL_OFFSET, 0x1234);
+        assert_eq!(extract_pe_icon(&bad_magic, 32).unwrap_err(), "Unknown optional header magic 0x1234");
+
+        let mut few_directories = sample_pe();
+        put_u32(&mut few_directories, OPTIONAL_OFFSET + 92, 2);
+        assert_eq!(extract_pe_icon(&few_directories, 32).unwrap_err(), "PE file has no resource directory");
+
+        let mut no_resources = sample_pe();
+        put_u32(&mut no_resources, OPTIONAL_OFFSET + 96 + RESOURCE_DIRECTORY_INDEX * 8, 0);
+        assert_eq!(extract_pe_icon(&no_resources, 32).unwrap_err(), "PE file has no resources");
+
+        let mut outside_sections = sample_pe();
+        put_u32(&mut outside_sections, OPTIONAL_OFFSET + 96 + RESOURCE_DIRECTORY_INDEX * 8, 0x5000);
+        assert_eq!(extract_pe_icon(&outside_sections, 32).unwrap_err(), "Resource directory is outside every section");
+    }
+
+    #[test]
+    fn rejects_pe_without_usable_icons() {
+        let icon = png_bytes(32, 32);
+
+        // 资源树中只有 RT_ICON
+        let mut no_group = sample_pe();
+        put_u32(&mut no_group, SECTION_OFFSET + 24, 99);
+        assert_eq!(extract_pe_icon(&no_group, 32).unwrap_err(), "PE file has no icon group");
+
+        let missing_icon = pe_bytes(false, &icon, &group_bytes(32, 2, icon.len() as u32));
+        assert_eq!(extract_pe_icon(&missing_icon, 32).unwrap_err(), "Icon group references no icons");
+
+        let mut bad_group = group_bytes(32, 1, icon.len() as u32);
+        bad_group[2] = 2;
+        let bad_group = pe_bytes(false, &icon, &bad_group);
+        assert_eq!(extract_pe_icon(&bad_group, 32).unwrap_err(), "Not an icon directory");
+
+        let corrupt = pe_bytes(false, &[0x42; 64], &group_bytes(32, 1, 64));
+        assert!(extract_pe_icon(&corrupt, 32).unwrap_err().starts_with("Failed to decode icon image"));
+    }
+
+    #[test]
+    fn truncated_pe_is_an_error() {
+        let pe = sample_pe();
+        for len in 0..pe.len() {
+            assert!(extract_pe_icon(&pe[..len], 32).is_err(), "truncated to {} bytes", len);
+        }
+    }
+
+    // 头部和资源树中任意字段被改成极端值时只返回错误，不会 panic
+    #[test]
+    fn corrupted_headers_do_not_panic() {
+        let pe = sample_pe();
+        for offset in 0..SECTION_OFFSET + RESOURCE_TREE_SIZE {
+            for value in [0x00, 0x7F, 0x80, 0xFF] {
+                let mut corrupted = pe.clone();
+                corrupted[offset] = value;
+                let _ = extract_pe_icon(&corrupted, 32);
+            }
+        }
+    }
+}
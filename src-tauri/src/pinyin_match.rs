@@ -0,0 +1,227 @@
+use pinyin::{ToPinyin, ToPinyinMulti};
+
+// 评分常量，与 search 模块的模糊匹配处于同一量级
+const SCORE_CHAR: i32 = 16;
+const BONUS_FULL_SYLLABLE: i32 = 8;
+const BONUS_CONSECUTIVE: i32 = 6;
+const BONUS_FIRST_CHAR: i32 = 8;
+const PENALTY_SKIP: i32 = 1;
+
+// 单个多音字最多考虑的读音数量
+const MAX_READINGS: usize = 4;
+
+// 名称中的一个字符：汉字带有全部读音（多音字有多个），其他字符按小写保存
+#[derive(Debug, Clone)]
+enum NameChar {
+    Han { literal: char, readings: Vec<&'static str> },
+    Other(char),
+}
+
+// 判断字符串中是否含有可转换为拼音的汉字
+pub fn contains_han(text: &str) -> bool {
+    text.chars().any(|c| c.to_pinyin().is_some())
+}
+
+fn to_name_chars(text: &str) -> Vec<NameChar> {
+    text.chars()
+        .map(|c| match c.to_pinyin_multi() {
+            Some(multi) => {
+                let mut readings: Vec<&'static str> = Vec::new();
+                for reading in multi.into_iter().map(|p| p.plain()) {
+                    if !readings.contains(&reading) && readings.len() < MAX_READINGS {
+                        readings.push(reading);
+                    }
+                }
+                NameChar::Han { literal: c, readings }
+            }
+            None => NameChar::Other(c.to_lowercase().next().unwrap_or(c)),
+        })
+        .collect()
+}
+
+// 拼音匹配结果
+#[derive(Debug, Clone)]
+pub struct PinyinMatch {
+    pub score: i32,
+    pub positions: Vec<usize>,  // 命中的名称字符下标
+}
+
+// 动态规划状态：(名称字符下标, 已消耗的查询字符数, 上一个字符是否命中)
+#[derive(Clone, Copy)]
+struct Cell {
+    score: i32,
+    prev_k: usize,
+    prev_matched: bool,
+    matched: bool,
+}
+
+// 拼音匹配：支持全拼（weixin → 微信）、首字母（wx）以及混合输入（weix、微x）。
+// 每个汉字可以消耗其任一读音的前缀，也可以直接匹配汉字本身；其他字符按原样匹配
+pub fn match_pinyin(query: &[char], text: &str) -> Option<PinyinMatch> {
+    if query.is_empty() || !contains_han(text) {
+        return None;
+    }
+
+    let chars = to_name_chars(text);
+    let n = chars.len();
+    let m = query.len();
+    let width = (m + 1) * 2;
+
+    // dp[i][k][matched]：处理完前 i 个字符、消耗了 k 个查询字符时的最高得分
+    let mut dp: Vec<Option<Cell>> = vec![None; (n + 1) * width];
+    let index = |i: usize, k: usize, matched: bool| i * width + k * 2 + matched as usize;
+
+    dp[index(0, 0, false)] = Some(Cell { score: 0, prev_k: 0, prev_matched: false, matched: false });
+
+    for i in 0..n {
+        for k in 0..=m {
+            for prev_matched in [false, true] {
+                let current = match dp[index(i, k, prev_matched)] {
+                    Some(cell) => cell.score,
+                    None => continue,
+                };
+
+                let mut relax = |next_k: usize, matched: bool, score: i32| {
+                    let slot = &mut dp[index(i + 1, next_k, matched)];
+                    if slot.is_none_or(|cell| score > cell.score) {
+                        *slot = Some(Cell { score, prev_k: k, prev_matched, matched });
+                    }
+                };
+
+                // 跳过当前字符；查询已全部消耗后不再扣分
+                let skip_penalty = if k > 0 && k < m { PENALTY_SKIP } else { 0 };
+                relax(k, false, current - skip_penalty);
+
+                if k == m {
+                    continue;
+                }
+
+                let bonus = if prev_matched { BONUS_CONSECUTIVE } else { 0 }
+                    + if i == 0 { BONUS_FIRST_CHAR } else { 0 };
+
+                match &chars[i] {
+                    NameChar::Han { literal, readings } => {
+                        if query[k] == *literal {
+                            relax(k + 1, true, current + SCORE_CHAR + BONUS_FULL_SYLLABLE + bonus);
+                        }
+
+                        for reading in readings {
+                            let matched_len = reading.chars()
+                                .zip(query[k..].iter())
+                                .take_while(|(a, b)| a == *b)
+                                .count();
+
+                            // 读音的每个前缀都可以作为一次命中（首字母、部分拼音或全拼）
+                            let syllable_len = reading.chars().count();
+                            for len in 1..=matched_len {
+                                let full = if len == syllable_len { BONUS_FULL_SYLLABLE } else { 0 };
+                                relax(k + len, true, current + SCORE_CHAR + full + bonus);
+                            }
+                        }
+                    }
+                    NameChar::Other(c) => {
+                        if query[k] == *c {
+                            relax(k + 1, true, current + SCORE_CHAR + bonus);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let end = [dp[index(n, m, false)], dp[index(n, m, true)]]
+        .into_iter()
+        .enumerate()
+        .filter_map(|(matched, cell)| cell.map(|c| (matched == 1, c)))
+        .max_by_key(|(_, cell)| cell.score)?;
+
+    // 回溯命中的字符位置
+    let mut positions = Vec::new();
+    let (mut k, mut matched) = (m, end.0);
+    for i in (1..=n).rev() {
+        let cell = dp[index(i, k, matched)]?;
+        if cell.matched {
+            positions.push(i - 1);
+        }
+        k = cell.prev_k;
+        matched = cell.prev_matched;
+    }
+    positions.reverse();
+
+    Some(PinyinMatch { score: end.1.score, positions })
+}
+
+// 拼音排序键：汉字替换为首个读音，其他字符转为小写，音节之间以空格分隔
+pub fn collation_key(text: &str) -> String {
+    let mut key = String::with_capacity(text.len() * 2);
+    for c in text.chars() {
+        match c.to_pinyin() {
+            Some(p) => {
+                key.push_str(p.plain());
+                key.push(' ');
+            }
+            None => key.extend(c.to_lowercase()),
+        }
+    }
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matched(query: &str, text: &str) -> Option<PinyinMatch> {
+        let query: Vec<char> = query.chars().collect();
+        match_pinyin(&query, text)
+    }
+
+    fn positions(query: &str, text: &str) -> Option<Vec<usize>> {
+        matched(query, text).map(|m| m.positions)
+    }
+
+    #[test]
+    fn matches_full_pinyin_initials_and_mixed_input() {
+        assert_eq!(positions("weixin", "微信"), Some(vec![0, 1]));
+        assert_eq!(positions("wx", "微信"), Some(vec![0, 1]));
+        assert_eq!(positions("weix", "微信"), Some(vec![0, 1]));
+        assert_eq!(positions("微x", "微信"), Some(vec![0, 1]));
+        assert_eq!(positions("xin", "微信"), Some(vec![1]));
+    }
+
+    #[test]
+    fn matches_heteronyms_and_other_characters() {
+        assert_eq!(positions("cq", "重庆"), Some(vec![0, 1]));
+        assert_eq!(positions("zhongyao", "重要"), Some(vec![0, 1]));
+        assert_eq!(positions("qqyy", "QQ音乐"), Some(vec![0, 1, 2, 3]));
+        assert_eq!(positions("wps", "WPS 文字"), Some(vec![0, 1, 2]));
+    }
+
+    #[test]
+    fn rejects_non_matches() {
+        assert!(matched("xw", "微信").is_none());
+        assert!(matched("weixinx", "微信").is_none());
+        assert!(matched("", "微信").is_none());
+        assert!(matched("chrome", "Chrome").is_none());
+    }
+
+    #[test]
+    fn prefers_full_syllables_and_consecutive_matches() {
+        let full = matched("weixin", "微信").unwrap().score;
+        let initials = matched("wx", "微信").unwrap().score;
+        assert!(full > initials);
+
+        let consecutive = matched("wx", "微信读书").unwrap().score;
+        let scattered = matched("wd", "微信读书").unwrap().score;
+        assert!(consecutive > scattered);
+    }
+
+    #[test]
+    fn builds_collation_keys() {
+        assert!(contains_han("QQ音乐"));
+        assert!(!contains_han("Chrome"));
+        assert_eq!(collation_key("微信"), "wei xin ");
+        assert_eq!(collation_key("QQ音乐"), "qqyin le ");
+        assert!(collation_key("阿里") < collation_key("百度"));
+        assert!(collation_key("Apple") < collation_key("百度"));
+    }
+}
@@ -0,0 +1,79 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+
+// 正在运行的程序：按需刷新进程列表，间隔内重复查询使用同一份快照
+const SNAPSHOT_TTL: Duration = Duration::from_secs(2);
+
+struct Snapshot {
+    system: System,
+    executables: HashSet<PathBuf>,
+    taken_at: Option<Instant>,
+}
+
+pub struct ProcessMonitor {
+    snapshot: Mutex<Snapshot>,
+}
+
+// Windows 路径不区分大小写，统一转为小写后比较
+fn normalize(path: &Path) -> PathBuf {
+    if cfg!(target_os = "windows") {
+        PathBuf::from(path.to_string_lossy().to_lowercase())
+    } else {
+        path.to_path_buf()
+    }
+}
+
+impl ProcessMonitor {
+    pub fn new() -> Self {
+        Self {
+            snapshot: Mutex::new(Snapshot {
+                system: System::new(),
+                executables: HashSet::new(),
+                taken_at: None,
+            }),
+        }
+    }
+
+    fn refresh(snapshot: &mut Snapshot) {
+        snapshot.system.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::new().with_exe(UpdateKind::OnlyIfNotSet),
+        );
+        snapshot.executables = snapshot.system.processes()
+            .values()
+            .filter_map(|process| process.exe())
+            .map(normalize)
+            .collect();
+        snapshot.taken_at = Some(Instant::now());
+    }
+
+    // 目标是否有进程在运行；目标是 .app 包时，包内的程序在运行也算；
+    // 其他目录（主目录、项目目录等）不按前缀匹配，否则其下任何程序运行都会算作运行中
+    pub fn is_running(&self, target: &str) -> bool {
+        let Ok(mut snapshot) = self.snapshot.lock() else { return false };
+        if snapshot.taken_at.is_none_or(|taken_at| taken_at.elapsed() > SNAPSHOT_TTL) {
+            Self::refresh(&mut snapshot);
+        }
+
+        let path = Path::new(target);
+        let mut candidates = vec![normalize(path)];
+        if let Ok(canonical) = std::fs::canonicalize(path) {
+            candidates.push(normalize(&canonical));
+        }
+
+        if crate::app_bundle::is_app_bundle(path) {
+            snapshot.executables.iter()
+                .any(|exe| candidates.iter().any(|target| exe.starts_with(target)))
+        } else {
+            candidates.iter().any(|target| snapshot.executables.contains(target))
+        }
+    }
+}
+
+lazy_static::lazy_static! {
+    pub static ref GLOBAL_PROCESS_MONITOR: ProcessMonitor = ProcessMonitor::new();
+}
@@ -0,0 +1,304 @@
+use crate::models::{AppData, ProviderAction, ProviderResult, ProviderSettings, SearchConfig, SearchResult, WebSearchEngine};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// 快捷方式搜索直接在调用线程中执行（需要借用 AppData），不走线程池和超时
+pub const SHORTCUTS_PROVIDER: &str = "shortcuts";
+
+// 快捷方式得分达到该值时归一化得分为 0.5
+const SHORTCUT_SCORE_HALF: f64 = 100.0;
+
+// 运行提供者的线程数
+const POOL_SIZE: usize = 4;
+
+// 提供者执行时可以读取的上下文，会被发送到提供者所在线程
+pub struct ProviderContext {
+    pub query: String,
+    pub limit: usize,
+    pub web_search_engines: Vec<WebSearchEngine>,
+}
+
+// 搜索提供者：计算器、网页搜索等非快捷方式结果的来源
+pub trait SearchProvider: Send + Sync {
+    fn id(&self) -> &'static str;
+
+    fn search(&self, context: &ProviderContext) -> Vec<ProviderResult>;
+
+    // 把提供者自己的原始得分映射到 0..1，便于和其他提供者的结果比较
+    fn normalize_score(&self, raw: f64) -> f64 {
+        raw.clamp(0.0, 1.0)
+    }
+}
+
+// 计算器
+pub struct CalculatorProvider;
+
+impl SearchProvider for CalculatorProvider {
+    fn id(&self) -> &'static str {
+        "calculator"
+    }
+
+    fn search(&self, context: &ProviderContext) -> Vec<ProviderResult> {
+        if !crate::calculator::looks_like_expression(&context.query) {
+            return Vec::new();
+        }
+
+        let expression = context.query.trim().trim_start_matches('=');
+        let value = match crate::calculator::evaluate(expression) {
+            Ok(value) => value,
+            Err(_) => return Vec::new(),
+        };
+        let formatted = crate::calculator::format_number(value);
+
+        vec![ProviderResult {
+            provider_id: self.id().to_string(),
+            id: format!("calculator:{}", expression.trim()),
+            title: formatted.clone(),
+            subtitle: Some(format!("{} =", expression.trim())),
+            icon: None,
+            score: 0.9,
+            match_ranges: Vec::new(),
+            action: ProviderAction::CopyText(formatted),
+        }]
+    }
+}
+
+// 网页搜索模板："g rust" 命中关键字 g 时使用对应的引擎，否则为标记了 fallback 的引擎生成兜底结果
+pub struct WebSearchProvider;
+
+impl WebSearchProvider {
+    fn build_result(&self, engine: &WebSearchEngine, terms: &str, score: f64) -> ProviderResult {
+        let url = engine.url.replace("{query}", &crate::utils::percent_encode(terms));
+        ProviderResult {
+            provider_id: self.id().to_string(),
+            id: format!("web_search:{}:{}", engine.keyword, terms),
+            title: format!("Search {} for \"{}\"", engine.name, terms),
+            subtitle: Some(url.clone()),
+            icon: None,
+            score,
+            match_ranges: Vec::new(),
+            action: ProviderAction::OpenUrl(url),
+        }
+    }
+}
+
+impl SearchProvider for WebSearchProvider {
+    fn id(&self) -> &'static str {
+        "web_search"
+    }
+
+    fn search(&self, context: &ProviderContext) -> Vec<ProviderResult> {
+        let query = context.query.trim();
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        if let Some((keyword, terms)) = query.split_once(char::is_whitespace) {
+            let terms = terms.trim();
+            let engine = context.web_search_engines.iter()
+                .find(|e| !e.keyword.is_empty() && e.keyword.eq_ignore_ascii_case(keyword));
+            if let (Some(engine), false) = (engine, terms.is_empty()) {
+                return vec![self.build_result(engine, terms, 0.95)];
+            }
+        }
+
+        context.web_search_engines.iter()
+            .filter(|e| e.fallback)
+            .take(context.limit)
+            .map(|e| self.build_result(e, query, 0.05))
+            .collect()
+    }
+}
+
+type Job = Box<dyn FnOnce() + Send>;
+
+// 固定大小的线程池，避免每次按键都为每个提供者创建线程；超时的提供者会继续占用线程直到返回
+struct ProviderPool {
+    sender: Mutex<mpsc::Sender<Job>>,
+}
+
+impl ProviderPool {
+    fn new(size: usize) -> Self {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        for _ in 0..size {
+            let receiver = receiver.clone();
+            thread::spawn(move || loop {
+                let job = match receiver.lock() {
+                    Ok(receiver) => receiver.recv(),
+                    Err(_) => break,
+                };
+                match job {
+                    // 提供者 panic 时只丢弃这次结果，线程继续复用
+                    Ok(job) => {
+                        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(job));
+                    }
+                    Err(_) => break,
+                }
+            });
+        }
+
+        Self { sender: Mutex::new(sender) }
+    }
+
+    fn execute(&self, job: Job) {
+        if let Ok(sender) = self.sender.lock() {
+            let _ = sender.send(job);
+        }
+    }
+}
+
+// 已注册的提供者
+pub struct ProviderRegistry {
+    providers: Vec<Arc<dyn SearchProvider>>,
+    pool: ProviderPool,
+}
+
+impl ProviderRegistry {
+    pub fn new() -> Self {
+        Self {
+            providers: vec![
+                Arc::new(CalculatorProvider),
+                Arc::new(WebSearchProvider),
+            ],
+            pool: ProviderPool::new(POOL_SIZE),
+        }
+    }
+
+    fn get(&self, id: &str) -> Option<Arc<dyn SearchProvider>> {
+        self.providers.iter().find(|p| p.id() == id).cloned()
+    }
+
+    // 按配置顺序返回提供者设置；配置里缺少的已知提供者（升级后新增的）追加在末尾并默认启用
+    pub fn resolve_settings(&self, config: &SearchConfig) -> Vec<ProviderSettings> {
+        let mut settings: Vec<ProviderSettings> = config.providers.iter()
+            .filter(|s| s.id == SHORTCUTS_PROVIDER || self.get(&s.id).is_some())
+            .cloned()
+            .collect();
+
+        let known = std::iter::once(SHORTCUTS_PROVIDER).chain(self.providers.iter().map(|p| p.id()));
+        for id in known {
+            if !settings.iter().any(|s| s.id == id) {
+                settings.push(ProviderSettings::new(id, 1.0));
+            }
+        }
+
+        settings
+    }
+
+    // 把查询分发给所有启用的提供者，提供者在线程池中运行，超时的结果直接丢弃；
+    // data 应为调用方释放数据锁前复制的快照，等待期间不占用数据锁
+    pub fn search(&self, data: &AppData, query: &str, limit: usize) -> Vec<ProviderResult> {
+        let settings = self.resolve_settings(&data.config.search);
+        let context = Arc::new(ProviderContext {
+            query: query.to_string(),
+            limit,
+            web_search_engines: data.config.search.web_search_engines.clone(),
+        });
+
+        let started = Instant::now();
+        let (sender, receiver) = mpsc::channel::<(String, Vec<ProviderResult>)>();
+        let mut pending: HashMap<String, (Instant, Arc<dyn SearchProvider>)> = HashMap::new();
+
+        for setting in settings.iter().filter(|s| s.enabled && s.id != SHORTCUTS_PROVIDER) {
+            let Some(provider) = self.get(&setting.id) else { continue };
+            let deadline = started + Duration::from_millis(setting.timeout_ms);
+            pending.insert(setting.id.clone(), (deadline, provider.clone()));
+
+            let sender = sender.clone();
+            let context = context.clone();
+            self.pool.execute(Box::new(move || {
+                let results = provider.search(&context);
+                // 接收端可能已经因超时放弃，发送失败无需处理
+                let _ = sender.send((provider.id().to_string(), results));
+            }));
+        }
+        drop(sender);
+
+        // 收集结果：(归一化前的结果, 归一化后的得分)
+        let mut collected: Vec<(ProviderResult, f64)> = Vec::new();
+        let weights: HashMap<&str, f64> = settings.iter().map(|s| (s.id.as_str(), s.weight)).collect();
+
+        // 快捷方式搜索在等待其他提供者的同时执行
+        let shortcuts_enabled = settings.iter().any(|s| s.id == SHORTCUTS_PROVIDER && s.enabled);
+        if shortcuts_enabled {
+            // 语法错误时不返回快捷方式结果，其他提供者（比如计算器）仍然可以给出结果
+            if let Ok(results) = crate::search::search_shortcuts(data, query, Some(limit)) {
+                let weight = weights.get(SHORTCUTS_PROVIDER).copied().unwrap_or(1.0);
+                for result in results {
+                    let normalized = result.score.max(0.0) / (result.score.max(0.0) + SHORTCUT_SCORE_HALF);
+                    collected.push((shortcut_to_provider_result(result), normalized * weight));
+                }
+            }
+        }
+
+        while !pending.is_empty() {
+            let next_deadline = pending.values().map(|(deadline, _)| *deadline).min().unwrap_or(started);
+            let wait = next_deadline.saturating_duration_since(Instant::now());
+
+            match receiver.recv_timeout(wait) {
+                Ok((id, results)) => {
+                    if let Some((_, provider)) = pending.remove(&id) {
+                        let weight = weights.get(id.as_str()).copied().unwrap_or(1.0);
+                        for result in results {
+                            let normalized = provider.normalize_score(result.score) * weight;
+                            collected.push((result, normalized));
+                        }
+                    }
+                }
+                Err(mpsc::RecvTimeoutError::Timeout) => {
+                    let now = Instant::now();
+                    pending.retain(|_, (deadline, _)| *deadline > now);
+                }
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            }
+        }
+
+        // 得分降序；同分时按提供者配置顺序
+        let order: HashMap<&str, usize> = settings.iter().enumerate().map(|(i, s)| (s.id.as_str(), i)).collect();
+        collected.sort_by(|(a, sa), (b, sb)| {
+            sb.partial_cmp(sa).unwrap_or(Ordering::Equal)
+                .then_with(|| order.get(a.provider_id.as_str()).cmp(&order.get(b.provider_id.as_str())))
+        });
+        collected.truncate(limit);
+
+        collected.into_iter()
+            .map(|(mut result, score)| {
+                result.score = score;
+                result
+            })
+            .collect()
+    }
+}
+
+fn shortcut_to_provider_result(result: SearchResult) -> ProviderResult {
+    let action = if result.is_saved {
+        ProviderAction::LaunchShortcut {
+            id: result.shortcut.id.clone(),
+            input: result.parameter_input.clone(),
+        }
+    } else {
+        ProviderAction::OpenPath(result.shortcut.file_path.clone())
+    };
+
+    ProviderResult {
+        provider_id: SHORTCUTS_PROVIDER.to_string(),
+        id: result.shortcut.id,
+        title: result.shortcut.name,
+        subtitle: Some(result.shortcut.file_path),
+        icon: result.shortcut.icon_path,
+        score: result.score,
+        match_ranges: if result.matched_field == "name" { result.match_ranges } else { Vec::new() },
+        action,
+    }
+}
+
+// 全局提供者注册表
+lazy_static::lazy_static! {
+    pub static ref GLOBAL_PROVIDER_REGISTRY: ProviderRegistry = ProviderRegistry::new();
+}
@@ -0,0 +1,432 @@
+use chrono::Duration;
+use serde::Serialize;
+
+// 查询语法：
+//   普通词          按模糊匹配搜索名称、别名、关键词和路径，多个词分别匹配且都要命中
+//   "短语"          必须连续出现
+//   cat:dev         分类（名称或ID）
+//   tag:prod        关键词
+//   ext:sh          扩展名
+//   kind:url        目标类型：file / dir / url / app
+//   missing:true    目标文件是否丢失
+//   used:<7d        最近 7 天内使用过；used:>30d 超过 30 天未使用；used:never 从未使用
+//   -xxx            对以上任意一项取反
+// 值中含空格时可以加引号，例如 cat:"开发 工具"
+
+// 查询语法错误，position 和 length 按字符计
+#[derive(Debug, Clone, Serialize)]
+pub struct QueryError {
+    pub message: String,
+    pub position: usize,
+    pub length: usize,
+}
+
+impl QueryError {
+    fn new(message: impl Into<String>, position: usize, length: usize) -> Self {
+        Self { message: message.into(), position, length: length.max(1) }
+    }
+}
+
+impl std::fmt::Display for QueryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Query syntax error at {}: {}", self.position, self.message)
+    }
+}
+
+// 目标类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetKind {
+    File,
+    Directory,
+    Url,
+    App,
+}
+
+// 使用时间条件
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UsedFilter {
+    Within(Duration),
+    OlderThan(Duration),
+    Never,
+}
+
+// 过滤条件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Filter {
+    Category(String),
+    Tag(String),
+    Extension(String),
+    Kind(TargetKind),
+    Missing(bool),
+    Used(UsedFilter),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClauseKind {
+    Term(String),
+    Phrase(String),
+    Filter(Filter),
+}
+
+// 查询中的一项，span 为其在原始查询中的字符区间 [start, end)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Clause {
+    pub negated: bool,
+    pub kind: ClauseKind,
+    pub span: (usize, usize),
+}
+
+// 执行计划：过滤条件先行，剩余普通词逐个做模糊匹配
+#[derive(Debug, Clone, Default)]
+pub struct SearchPlan {
+    pub filters: Vec<(bool, Filter)>,  // (是否取反, 条件)
+    pub phrases: Vec<String>,
+    pub excluded_terms: Vec<String>,
+    pub terms: Vec<String>,
+    pub fuzzy_text: String,  // 以空格连接的 terms，用于别名的完整匹配
+    pub raw_text: String,  // fuzzy_text 保留原始大小写的版本，用作快捷方式参数
+}
+
+impl SearchPlan {
+    pub fn is_empty(&self) -> bool {
+        self.filters.is_empty()
+            && self.phrases.is_empty()
+            && self.excluded_terms.is_empty()
+            && self.terms.is_empty()
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|c| c.is_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    // 读取引号内的内容，支持 \" 和 \\ 转义；调用时 pos 指向开头的引号
+    fn read_quoted(&mut self) -> Result<String, QueryError> {
+        let start = self.pos;
+        self.pos += 1;
+        let mut value = String::new();
+
+        loop {
+            match self.peek() {
+                None => {
+                    return Err(QueryError::new("Unterminated quote", start, self.pos - start));
+                }
+                Some('"') => {
+                    self.pos += 1;
+                    return Ok(value);
+                }
+                Some('\\') if matches!(self.chars.get(self.pos + 1), Some('"') | Some('\\')) => {
+                    value.push(self.chars[self.pos + 1]);
+                    self.pos += 2;
+                }
+                Some(c) => {
+                    value.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+    }
+
+    // 读取到下一个空白为止的普通词
+    fn read_word(&mut self) -> String {
+        let mut word = String::new();
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() {
+                break;
+            }
+            word.push(c);
+            self.pos += 1;
+        }
+        word
+    }
+
+    fn parse_clause(&mut self) -> Result<Clause, QueryError> {
+        let start = self.pos;
+        let negated = self.peek() == Some('-');
+        if negated {
+            self.pos += 1;
+            if self.peek().is_none_or(|c| c.is_whitespace()) {
+                return Err(QueryError::new("Expected a term after '-'", start, 1));
+            }
+        }
+
+        if self.peek() == Some('"') {
+            let phrase = self.read_quoted()?;
+            if phrase.trim().is_empty() {
+                return Err(QueryError::new("Empty phrase", start, self.pos - start));
+            }
+            return Ok(Clause {
+                negated,
+                kind: ClauseKind::Phrase(phrase.to_lowercase()),
+                span: (start, self.pos),
+            });
+        }
+
+        let mut word = String::new();
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() || c == ':' {
+                break;
+            }
+            word.push(c);
+            self.pos += 1;
+        }
+
+        // 不是 key:value 形式，按普通词处理
+        if self.peek() != Some(':') {
+            return Ok(Clause {
+                negated,
+                kind: ClauseKind::Term(word.to_lowercase()),
+                span: (start, self.pos),
+            });
+        }
+
+        let key = word.to_lowercase();
+        if !matches!(key.as_str(), "cat" | "tag" | "ext" | "kind" | "missing" | "used") {
+            // 未知的键（Note: foo、C:\、https://）不是过滤条件，整体按普通词处理
+            let rest = self.read_word();
+            return Ok(Clause {
+                negated,
+                kind: ClauseKind::Term(format!("{}{}", word, rest).to_lowercase()),
+                span: (start, self.pos),
+            });
+        }
+
+        self.pos += 1;
+        let value_start = self.pos;
+        let value = if self.peek() == Some('"') {
+            self.read_quoted()?
+        } else {
+            self.read_word()
+        };
+
+        if value.trim().is_empty() {
+            return Err(QueryError::new(format!("Missing value for '{}:'", key), start, self.pos - start));
+        }
+
+        let filter = parse_filter(&key, &value)
+            .map_err(|message| QueryError::new(message, value_start, self.pos - value_start))?;
+
+        Ok(Clause {
+            negated,
+            kind: ClauseKind::Filter(filter),
+            span: (start, self.pos),
+        })
+    }
+}
+
+fn parse_filter(key: &str, value: &str) -> Result<Filter, String> {
+    let lowered = value.trim().to_lowercase();
+
+    match key {
+        "cat" => Ok(Filter::Category(lowered)),
+        "tag" => Ok(Filter::Tag(lowered)),
+        "ext" => Ok(Filter::Extension(lowered.trim_start_matches('.').to_string())),
+        "kind" => match lowered.as_str() {
+            "file" => Ok(Filter::Kind(TargetKind::File)),
+            "dir" | "folder" => Ok(Filter::Kind(TargetKind::Directory)),
+            "url" | "link" => Ok(Filter::Kind(TargetKind::Url)),
+            "app" => Ok(Filter::Kind(TargetKind::App)),
+            _ => Err(format!("Unknown kind '{}', expected file, dir, url or app", value)),
+        },
+        "missing" => match lowered.as_str() {
+            "true" | "yes" | "1" => Ok(Filter::Missing(true)),
+            "false" | "no" | "0" => Ok(Filter::Missing(false)),
+            _ => Err(format!("Expected true or false, got '{}'", value)),
+        },
+        "used" => parse_used(&lowered).map(Filter::Used),
+        _ => Err(format!("Unknown filter '{}'", key)),
+    }
+}
+
+// 解析 used 条件：<7d、>30d、never；单位支持 h、d、w、m（月，按 30 天）、y
+fn parse_used(value: &str) -> Result<UsedFilter, String> {
+    if value == "never" {
+        return Ok(UsedFilter::Never);
+    }
+
+    let (within, rest) = match value.chars().next() {
+        Some('<') => (true, &value[1..]),
+        Some('>') => (false, &value[1..]),
+        _ => return Err(format!("Expected <N, >N or never, got '{}'", value)),
+    };
+
+    let digits_end = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+    let digits = &rest[..digits_end];
+    if digits.is_empty() {
+        return Err(format!("Expected a number in '{}'", value));
+    }
+    // 数值过大时返回错误而不是溢出
+    let too_large = || format!("Time span '{}' is too large", value);
+    let amount: i64 = digits.parse().map_err(|_| too_large())?;
+    let days = |n: Option<i64>| n.and_then(Duration::try_days);
+    let duration = match &rest[digits_end..] {
+        "h" => Duration::try_hours(amount),
+        "d" | "" => Duration::try_days(amount),
+        "w" => Duration::try_weeks(amount),
+        "m" => days(amount.checked_mul(30)),
+        "y" => days(amount.checked_mul(365)),
+        unit => return Err(format!("Unknown time unit '{}', expected h, d, w, m or y", unit)),
+    }
+    .ok_or_else(too_large)?;
+
+    Ok(if within { UsedFilter::Within(duration) } else { UsedFilter::OlderThan(duration) })
+}
+
+// 解析查询字符串
+pub fn parse_query(query: &str) -> Result<Vec<Clause>, QueryError> {
+    let mut parser = Parser { chars: query.chars().collect(), pos: 0 };
+    let mut clauses = Vec::new();
+
+    loop {
+        parser.skip_whitespace();
+        if parser.peek().is_none() {
+            break;
+        }
+        clauses.push(parser.parse_clause()?);
+    }
+
+    Ok(clauses)
+}
+
+// 把解析结果整理为执行计划
+pub fn plan_query(clauses: Vec<Clause>) -> SearchPlan {
+    let mut plan = SearchPlan::default();
+    let mut terms: Vec<String> = Vec::new();
+
+    for clause in clauses {
+        match (clause.negated, clause.kind) {
+            (negated, ClauseKind::Filter(filter)) => plan.filters.push((negated, filter)),
+            (false, ClauseKind::Term(term)) => terms.push(term),
+            (false, ClauseKind::Phrase(phrase)) => plan.phrases.push(phrase),
+            (true, ClauseKind::Term(term)) | (true, ClauseKind::Phrase(term)) => plan.excluded_terms.push(term),
+        }
+    }
+
+    plan.fuzzy_text = terms.join(" ");
+    plan.terms = terms;
+    plan
+}
+
+pub fn compile_query(query: &str) -> Result<SearchPlan, QueryError> {
+    let clauses = parse_query(query)?;
+
+    let chars: Vec<char> = query.chars().collect();
+    let raw_terms: Vec<String> = clauses.iter()
+        .filter(|c| !c.negated && matches!(c.kind, ClauseKind::Term(_)))
+        .map(|c| chars[c.span.0..c.span.1].iter().collect())
+        .collect();
+
+    let mut plan = plan_query(clauses);
+    plan.raw_text = raw_terms.join(" ");
+    Ok(plan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(query: &str) -> Vec<(bool, ClauseKind)> {
+        parse_query(query).unwrap().into_iter().map(|c| (c.negated, c.kind)).collect()
+    }
+
+    #[test]
+    fn parses_used_spans() {
+        assert_eq!(parse_used("never"), Ok(UsedFilter::Never));
+        assert_eq!(parse_used("<7d"), Ok(UsedFilter::Within(Duration::days(7))));
+        assert_eq!(parse_used(">30"), Ok(UsedFilter::OlderThan(Duration::days(30))));
+        assert_eq!(parse_used("<12h"), Ok(UsedFilter::Within(Duration::hours(12))));
+        assert_eq!(parse_used("<2w"), Ok(UsedFilter::Within(Duration::weeks(2))));
+        assert_eq!(parse_used(">3m"), Ok(UsedFilter::OlderThan(Duration::days(90))));
+        assert_eq!(parse_used(">1y"), Ok(UsedFilter::OlderThan(Duration::days(365))));
+    }
+
+    #[test]
+    fn rejects_out_of_range_used_spans() {
+        for value in [
+            "<99999999999999999999d",
+            "<9223372036854775807h",
+            "<200000000000d",
+            "<20000000000w",
+            ">9000000000000000000m",
+            ">30000000000000000y",
+        ] {
+            let error = parse_used(value).unwrap_err();
+            assert!(error.contains("too large"), "{}: {}", value, error);
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_used_spans() {
+        assert!(parse_used("7d").unwrap_err().contains("Expected <N"));
+        assert!(parse_used("<d").unwrap_err().contains("Expected a number"));
+        assert!(parse_used("<").unwrap_err().contains("Expected a number"));
+        assert!(parse_used("<7x").unwrap_err().contains("Unknown time unit"));
+        assert!(parse_used("<-7d").unwrap_err().contains("Expected a number"));
+    }
+
+    #[test]
+    fn parses_filters_terms_and_phrases() {
+        assert_eq!(kinds(r#"cat:"开发 工具" -ext:.SH kind:dir missing:no used:never build "Release Notes" -old"#), vec![
+            (false, ClauseKind::Filter(Filter::Category("开发 工具".to_string()))),
+            (true, ClauseKind::Filter(Filter::Extension("sh".to_string()))),
+            (false, ClauseKind::Filter(Filter::Kind(TargetKind::Directory))),
+            (false, ClauseKind::Filter(Filter::Missing(false))),
+            (false, ClauseKind::Filter(Filter::Used(UsedFilter::Never))),
+            (false, ClauseKind::Term("build".to_string())),
+            (false, ClauseKind::Phrase("release notes".to_string())),
+            (true, ClauseKind::Term("old".to_string())),
+        ]);
+    }
+
+    #[test]
+    fn treats_unknown_keys_as_terms() {
+        assert_eq!(kinds(r"Note:foo C:\Tools https://example.com"), vec![
+            (false, ClauseKind::Term("note:foo".to_string())),
+            (false, ClauseKind::Term(r"c:\tools".to_string())),
+            (false, ClauseKind::Term("https://example.com".to_string())),
+        ]);
+        assert_eq!(kinds("-todo:later"), vec![(true, ClauseKind::Term("todo:later".to_string()))]);
+    }
+
+    #[test]
+    fn reports_errors_with_positions() {
+        let error = parse_query(r#"a "unterminated"#).unwrap_err();
+        assert_eq!((error.position, error.message.as_str()), (2, "Unterminated quote"));
+
+        let error = parse_query("a kind:car").unwrap_err();
+        assert_eq!((error.position, error.length), (7, 3));
+
+        let error = parse_query("used:<99999999999999999999d").unwrap_err();
+        assert_eq!(error.position, 5);
+        assert!(error.message.contains("too large"));
+
+        assert!(parse_query("tag:").is_err());
+        assert!(parse_query(r#""  ""#).is_err());
+        assert!(parse_query("a - b").is_err());
+    }
+
+    #[test]
+    fn compiles_plan_keeping_raw_text() {
+        let plan = compile_query(r#"Jira OPS-123 -draft "exact" tag:Work"#).unwrap();
+        assert_eq!(plan.terms, vec!["jira".to_string(), "ops-123".to_string()]);
+        assert_eq!(plan.fuzzy_text, "jira ops-123");
+        assert_eq!(plan.raw_text, "Jira OPS-123");
+        assert_eq!(plan.phrases, vec!["exact".to_string()]);
+        assert_eq!(plan.excluded_terms, vec!["draft".to_string()]);
+        assert_eq!(plan.filters, vec![(false, Filter::Tag("work".to_string()))]);
+
+        assert!(compile_query("   ").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,273 @@
+use crate::fingerprint::Fingerprint;
+use crate::models::{InstalledApp, RepairCandidate, RepairSuggestion, Shortcut};
+use crate::utils::{real_extension, version_free_stem, version_numbers};
+use ignore::WalkBuilder;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+// 为目标丢失的快捷方式查找可能的替代目标，来源和基础可信度：
+//   versioned    只有版本号不同的文件，或版本号不同的目录下相同的相对路径（app-1.2.3 → app-1.3.0），最新版本更高
+//   sibling      附近目录中的同名文件
+//   path         PATH 中的同名程序
+//   app_catalog  已安装应用的程序名或应用名相同
+//   fingerprint  与记录的内容指纹相同的文件
+// 多个来源指向同一路径时按 1 - ∏(1 - c) 合并可信度
+
+const NEWEST_VERSION_CONFIDENCE: f64 = 0.8;
+const OLDER_VERSION_CONFIDENCE: f64 = 0.55;
+const SIBLING_CONFIDENCE: f64 = 0.5;
+const PATH_CONFIDENCE: f64 = 0.6;
+const CATALOG_PROGRAM_CONFIDENCE: f64 = 0.65;
+const CATALOG_STEM_CONFIDENCE: f64 = 0.5;
+const CATALOG_NAME_CONFIDENCE: f64 = 0.45;
+const FINGERPRINT_CONFIDENCE: f64 = 0.95;
+const MAX_CONFIDENCE: f64 = 0.99;
+
+// 在附近目录中搜索的深度和最多检查的条目数
+const SEARCH_DEPTH: usize = 3;
+const MAX_SEARCH_ENTRIES: usize = 5000;
+const MAX_SIBLINGS: usize = 1000;
+const MAX_CANDIDATES: usize = 5;
+
+#[derive(Default)]
+struct Candidates {
+    found: HashMap<PathBuf, (f64, Vec<String>)>,
+}
+
+impl Candidates {
+    fn add(&mut self, path: PathBuf, confidence: f64, source: &str) {
+        let (combined, sources) = self.found.entry(path).or_insert((0.0, Vec::new()));
+        if sources.iter().any(|s| s == source) {
+            // 同一来源只计一次
+            return;
+        }
+        *combined = (1.0 - (1.0 - *combined) * (1.0 - confidence)).min(MAX_CONFIDENCE);
+        sources.push(source.to_string());
+    }
+
+    fn into_sorted(self, old_path: &Path) -> Vec<RepairCandidate> {
+        let mut candidates: Vec<RepairCandidate> = self.found.into_iter()
+            .filter(|(path, _)| path != old_path && path.exists())
+            .map(|(path, (confidence, sources))| RepairCandidate {
+                path: path.to_string_lossy().to_string(),
+                confidence: (confidence * 100.0).round() / 100.0,
+                sources,
+            })
+            .collect();
+        candidates.sort_by(|a, b| b.confidence.total_cmp(&a.confidence).then_with(|| a.path.cmp(&b.path)));
+        candidates.truncate(MAX_CANDIDATES);
+        candidates
+    }
+}
+
+// 为一个目标丢失的快捷方式查找候选目标；apps 为已安装应用列表
+pub fn suggest(shortcut: &Shortcut, apps: &[InstalledApp]) -> RepairSuggestion {
+    let old_path = PathBuf::from(&shortcut.file_path);
+    let fingerprint = shortcut.target_fingerprint.as_deref().and_then(Fingerprint::parse);
+    let mut candidates = Candidates::default();
+
+    if let Some(file_name) = old_path.file_name() {
+        search_nearby(&old_path, fingerprint.as_ref(), &mut candidates);
+        search_path(&file_name.to_string_lossy(), &mut candidates);
+        search_catalog(shortcut, &old_path, apps, &mut candidates);
+    }
+
+    // 其他来源找到的文件也比对一次内容指纹
+    if let Some(fingerprint) = &fingerprint {
+        let matched: Vec<PathBuf> = candidates.found.keys()
+            .filter(|path| fingerprint.matches(path))
+            .cloned()
+            .collect();
+        for path in matched {
+            candidates.add(path, FINGERPRINT_CONFIDENCE, "fingerprint");
+        }
+    }
+
+    RepairSuggestion {
+        shortcut_id: shortcut.id.clone(),
+        shortcut_name: shortcut.name.clone(),
+        old_path: shortcut.file_path.clone(),
+        candidates: candidates.into_sorted(&old_path),
+    }
+}
+
+// 从仍然存在的最深一级目录开始查找：版本号不同的同名文件或目录、附近的同名文件、内容指纹相同的文件
+fn search_nearby(old_path: &Path, fingerprint: Option<&Fingerprint>, candidates: &mut Candidates) {
+    let Some(base) = old_path.ancestors().skip(1).find(|dir| dir.is_dir()) else { return };
+    let Ok(relative) = old_path.strip_prefix(base) else { return };
+    let components: Vec<&std::ffi::OsStr> = relative.components()
+        .filter_map(|c| match c {
+            Component::Normal(name) => Some(name),
+            _ => None,
+        })
+        .collect();
+    let (Some(first), Some(file_name)) = (components.first(), components.last()) else { return };
+    let rest: PathBuf = components.iter().skip(1).collect();
+
+    // 版本号不同的兄弟条目：是文件时直接作为候选，是目录时在其下查找相同的相对路径
+    let first = Path::new(first);
+    if let (Some(stem), Ok(read_dir)) = (version_free_stem(first), fs::read_dir(base)) {
+        let mut versioned: Vec<PathBuf> = read_dir.flatten()
+            .take(MAX_SIBLINGS)
+            .map(|entry| entry.path())
+            .filter(|path| path.file_name() != Some(first.as_os_str()))
+            .filter(|path| version_free_stem(path).as_deref() == Some(stem.as_str()))
+            .filter(|path| components.len() > 1 || real_extension(path) == real_extension(first))
+            .map(|path| if components.len() > 1 { path.join(&rest) } else { path })
+            .filter(|path| path.exists())
+            .collect();
+
+        let version_of = |path: &PathBuf| {
+            let versioned_part = path.strip_prefix(base).ok()
+                .and_then(|p| p.components().next())
+                .map(|c| PathBuf::from(c.as_os_str()))
+                .unwrap_or_default();
+            version_numbers(&versioned_part)
+        };
+        versioned.sort_by_key(|path| std::cmp::Reverse(version_of(path)));
+        for (index, path) in versioned.into_iter().enumerate() {
+            let confidence = if index == 0 { NEWEST_VERSION_CONFIDENCE } else { OLDER_VERSION_CONFIDENCE };
+            candidates.add(path, confidence, "versioned");
+        }
+    }
+
+    // 附近目录中的同名文件，以及大小和内容指纹都相同的文件
+    let walker = WalkBuilder::new(base)
+        .hidden(true)
+        .git_ignore(false)
+        .max_depth(Some(SEARCH_DEPTH))
+        .build();
+    for entry in walker.flatten().take(MAX_SEARCH_ENTRIES) {
+        if entry.depth() == 0 || !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+        let path = entry.path();
+        if path.file_name() == Some(*file_name) {
+            candidates.add(path.to_path_buf(), SIBLING_CONFIDENCE, "sibling");
+        }
+        if let Some(fingerprint) = fingerprint {
+            let same_size = entry.metadata().is_ok_and(|m| m.len() == fingerprint.size);
+            if same_size && fingerprint.matches(path) {
+                candidates.add(path.to_path_buf(), FINGERPRINT_CONFIDENCE, "fingerprint");
+            }
+        }
+    }
+}
+
+fn find_in_path(file_name: &str) -> Option<PathBuf> {
+    let paths = std::env::var_os("PATH")?;
+    std::env::split_paths(&paths)
+        .map(|dir| dir.join(file_name))
+        .find(|candidate| candidate.is_file())
+}
+
+fn search_path(file_name: &str, candidates: &mut Candidates) {
+    if let Some(path) = find_in_path(file_name) {
+        candidates.add(path, PATH_CONFIDENCE, "path");
+    }
+}
+
+// 应用实际启动的程序：Exec 的第一段，是命令名时在 PATH 中查找；
+// 开始菜单和 .app 应用的 exec 就是快捷方式或应用包本身
+fn app_program(app: &InstalledApp) -> Option<PathBuf> {
+    let exec = app.exec.trim();
+    let program = match exec.strip_prefix('"') {
+        Some(quoted) => quoted.split('"').next()?,
+        None if Path::new(exec).exists() => exec,
+        None => exec.split_whitespace().next()?,
+    };
+
+    let path = PathBuf::from(program);
+    if path.is_absolute() {
+        path.exists().then_some(path)
+    } else {
+        find_in_path(program)
+    }
+}
+
+fn search_catalog(shortcut: &Shortcut, old_path: &Path, apps: &[InstalledApp], candidates: &mut Candidates) {
+    let old_stem = version_free_stem(old_path);
+    let shortcut_name = shortcut.name.trim().to_lowercase();
+
+    for app in apps {
+        let Some(program) = app_program(app) else { continue };
+        if program.file_name() == old_path.file_name() {
+            candidates.add(program, CATALOG_PROGRAM_CONFIDENCE, "app_catalog");
+        } else if old_stem.is_some() && version_free_stem(&program) == old_stem {
+            candidates.add(program, CATALOG_STEM_CONFIDENCE, "app_catalog");
+        } else if !shortcut_name.is_empty() && app.name.trim().to_lowercase() == shortcut_name {
+            candidates.add(program, CATALOG_NAME_CONFIDENCE, "app_catalog");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn touch(path: &Path) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, b"binary").unwrap();
+    }
+
+    fn nearby(old_path: &Path) -> HashMap<PathBuf, (f64, Vec<String>)> {
+        let mut candidates = Candidates::default();
+        search_nearby(old_path, None, &mut candidates);
+        candidates.found
+    }
+
+    #[test]
+    fn prefers_the_newest_version_with_the_same_extension() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["tool-1.3.exe", "tool-1.10.exe", "tool-1.11.txt", "toolkit-2.0.exe"] {
+            touch(&dir.path().join(name));
+        }
+
+        let found = nearby(&dir.path().join("tool-1.2.exe"));
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[&dir.path().join("tool-1.10.exe")].0, NEWEST_VERSION_CONFIDENCE);
+        assert_eq!(found[&dir.path().join("tool-1.3.exe")].0, OLDER_VERSION_CONFIDENCE);
+    }
+
+    #[test]
+    fn accepts_versions_without_extension() {
+        let dir = tempfile::tempdir().unwrap();
+        touch(&dir.path().join("tool-1.3.0"));
+
+        let found = nearby(&dir.path().join("tool-1.2.9"));
+        assert_eq!(found[&dir.path().join("tool-1.3.0")].1, vec!["versioned".to_string()]);
+    }
+
+    #[test]
+    fn follows_the_same_path_inside_versioned_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        touch(&dir.path().join("app-1.3.0/bin/app"));
+        touch(&dir.path().join("app-1.4.0/lib/other"));
+
+        let found = nearby(&dir.path().join("app-1.2.3/bin/app"));
+        let expected = dir.path().join("app-1.3.0/bin/app");
+        assert_eq!(found.len(), 1);
+        assert!(found[&expected].1.contains(&"versioned".to_string()));
+        assert!(found[&expected].1.contains(&"sibling".to_string()));
+    }
+
+    #[test]
+    fn does_not_treat_names_with_digits_as_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        touch(&dir.path().join("x265.exe"));
+        touch(&dir.path().join("zip.exe"));
+
+        assert!(nearby(&dir.path().join("x264.exe")).is_empty());
+        assert!(nearby(&dir.path().join("7zip.exe")).is_empty());
+    }
+
+    #[test]
+    fn finds_files_with_the_same_name_nearby() {
+        let dir = tempfile::tempdir().unwrap();
+        touch(&dir.path().join("b/tool.sh"));
+
+        let found = nearby(&dir.path().join("a/tool.sh"));
+        assert_eq!(found[&dir.path().join("b/tool.sh")], (SIBLING_CONFIDENCE, vec!["sibling".to_string()]));
+    }
+}
@@ -0,0 +1,911 @@
+use crate::models::{AppData, Category, IndexedFile, SearchResult, Shortcut, ShortcutSortMode};
+use crate::query::{Filter, QueryError, SearchPlan, TargetKind, UsedFilter};
+use crate::utils::is_url;
+use chrono::{DateTime, Utc};
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::path::Path;
+
+// 评分常量
+const SCORE_MATCH: i32 = 16;
+const SCORE_GAP_START: i32 = 3;
+const SCORE_GAP_EXTENSION: i32 = 1;
+const BONUS_BOUNDARY: i32 = 8;
+const BONUS_CAMEL: i32 = 7;
+const BONUS_CONSECUTIVE: i32 = 6;
+const BONUS_FIRST_CHAR_MULTIPLIER: i32 = 2;
+const BONUS_PREFIX: i32 = 24;
+const BONUS_EXACT: i32 = 48;
+const BONUS_ACRONYM: i32 = 16;
+
+// 别名精确命中时的固定得分，保证排在最前
+const SCORE_EXACT_ALIAS: f64 = 10_000.0;
+
+// 超过该长度的目标文本不参与模糊匹配，避免极端路径拖慢搜索
+const MAX_TARGET_LEN: usize = 512;
+
+// 字符类别，用于计算单词边界奖励
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+    Separator,
+    Lower,
+    Upper,
+    Digit,
+    Other,
+}
+
+fn char_class(c: char) -> CharClass {
+    if c.is_lowercase() {
+        CharClass::Lower
+    } else if c.is_uppercase() {
+        CharClass::Upper
+    } else if c.is_numeric() {
+        CharClass::Digit
+    } else if c.is_alphabetic() {
+        CharClass::Other
+    } else {
+        CharClass::Separator
+    }
+}
+
+// 根据前一个字符和当前字符计算位置奖励
+fn position_bonus(prev: CharClass, current: CharClass) -> i32 {
+    match (prev, current) {
+        (CharClass::Separator, CharClass::Separator) => 0,
+        (CharClass::Separator, _) => BONUS_BOUNDARY,
+        (CharClass::Lower, CharClass::Upper) => BONUS_CAMEL,
+        (CharClass::Lower | CharClass::Upper | CharClass::Other, CharClass::Digit) => BONUS_CAMEL,
+        _ => 0,
+    }
+}
+
+fn lower_char(c: char) -> char {
+    if c.is_ascii() {
+        c.to_ascii_lowercase()
+    } else {
+        c.to_lowercase().next().unwrap_or(c)
+    }
+}
+
+// 模糊匹配器：查询字符必须按顺序出现在目标文本中（不区分大小写），
+// 通过动态规划找出得分最高的命中位置；缓冲区在多次匹配之间复用以减少内存分配
+#[derive(Debug, Default)]
+pub struct Matcher {
+    chars: Vec<char>,
+    bonuses: Vec<i32>,
+    score: Vec<i32>,
+    from: Vec<usize>,
+    positions: Vec<usize>,
+}
+
+impl Matcher {
+    // 计算匹配得分，命中位置保存在 positions 中
+    pub fn score(&mut self, query: &[char], target: &str) -> Option<i32> {
+        if query.is_empty() {
+            self.positions.clear();
+            return Some(0);
+        }
+
+        // 快速排除：不是子序列则直接返回，无需分配
+        let mut qi = 0;
+        let mut n = 0;
+        for c in target.chars() {
+            n += 1;
+            if qi < query.len() && lower_char(c) == query[qi] {
+                qi += 1;
+            }
+        }
+        if qi < query.len() || n > MAX_TARGET_LEN {
+            return None;
+        }
+
+        let m = query.len();
+
+        self.chars.clear();
+        self.bonuses.clear();
+        let mut prev_class = CharClass::Separator;
+        for c in target.chars() {
+            let class = char_class(c);
+            self.bonuses.push(position_bonus(prev_class, class));
+            self.chars.push(lower_char(c));
+            prev_class = class;
+        }
+
+        // score[i][j]：查询前 i+1 个字符匹配完成且第 i 个字符落在 j 时的最高得分
+        // from[i][j]：对应的上一个字符位置，用于回溯
+        const NONE: i32 = i32::MIN / 2;
+        self.score.clear();
+        self.score.resize(m * n, NONE);
+        self.from.clear();
+        self.from.resize(m * n, usize::MAX);
+
+        let lowered = &self.chars;
+        let bonuses = &self.bonuses;
+        let score = &mut self.score;
+        let from = &mut self.from;
+
+        for j in 0..n {
+            if lowered[j] == query[0] {
+                score[j] = SCORE_MATCH + bonuses[j] * BONUS_FIRST_CHAR_MULTIPLIER;
+            }
+        }
+
+        for i in 1..m {
+            // 已扫描区域内的最佳前驱（已扣除间隔惩罚）
+            let mut best_gap = NONE;
+            let mut best_gap_pos = usize::MAX;
+
+            for j in i..n {
+                // 当前位置之前两格的得分进入间隔前驱候选
+                if j >= 2 {
+                    let candidate = score[(i - 1) * n + j - 2] - SCORE_GAP_START;
+                    best_gap -= SCORE_GAP_EXTENSION;
+                    if candidate > best_gap {
+                        best_gap = candidate;
+                        best_gap_pos = j - 2;
+                    }
+                }
+
+                if lowered[j] != query[i] {
+                    continue;
+                }
+
+                let idx = i * n + j;
+                let prev = score[(i - 1) * n + j - 1];
+                let base = SCORE_MATCH + bonuses[j];
+
+                if prev > NONE {
+                    // 连续命中：取位置奖励和连续奖励中较大的一个，以免边界奖励被抵消
+                    score[idx] = prev + base + BONUS_CONSECUTIVE.max(bonuses[j]);
+                    from[idx] = j - 1;
+                }
+
+                if best_gap > NONE && best_gap + base > score[idx] {
+                    score[idx] = best_gap + base;
+                    from[idx] = best_gap_pos;
+                }
+            }
+        }
+
+        // 选出最后一个字符的最佳位置
+        let last_row = (m - 1) * n;
+        let (mut best_pos, mut best_score) = (usize::MAX, NONE);
+        for j in (m - 1)..n {
+            if score[last_row + j] > best_score {
+                best_score = score[last_row + j];
+                best_pos = j;
+            }
+        }
+
+        if best_pos == usize::MAX {
+            return None;
+        }
+
+        self.positions.clear();
+        self.positions.resize(m, 0);
+        let mut pos = best_pos;
+        for i in (0..m).rev() {
+            self.positions[i] = pos;
+            if i > 0 {
+                pos = from[i * n + pos];
+            }
+        }
+
+        let positions = &self.positions;
+        let mut total = best_score;
+
+        // 完全相同、前缀以及首字母缩写命中的额外奖励
+        if m == n {
+            total += BONUS_EXACT;
+        } else if positions.iter().enumerate().all(|(i, &p)| i == p) {
+            total += BONUS_PREFIX;
+        } else if m > 1 && positions.iter().all(|&p| bonuses[p] >= BONUS_CAMEL) {
+            total += BONUS_ACRONYM;
+        }
+
+        Some(total)
+    }
+
+    pub fn positions(&self) -> &[usize] {
+        &self.positions
+    }
+}
+
+// 将命中位置合并为连续区间 [start, end)
+pub fn positions_to_ranges(positions: &[usize]) -> Vec<(usize, usize)> {
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for &p in positions {
+        match ranges.last_mut() {
+            Some(last) if last.1 == p => last.1 = p + 1,
+            _ => ranges.push((p, p + 1)),
+        }
+    }
+    ranges
+}
+
+// 使用频率与最近使用时间带来的加成
+fn usage_boost(shortcut: &Shortcut, now: DateTime<Utc>) -> f64 {
+    (1.0 + crate::frecency::score_at(shortcut, now)).ln() * 8.0
+}
+
+// 单个快捷方式的最佳匹配，排序完成后再转换为 SearchResult
+struct ShortcutMatch<'a> {
+    shortcut: &'a Shortcut,
+    score: f64,
+    field: &'static str,
+    positions: Vec<usize>,
+    matched_text: Option<&'a str>,
+    parameter_input: Option<String>,
+}
+
+// 匹配单个字段，得分更高时替换当前最佳匹配
+#[allow(clippy::too_many_arguments)]
+fn consider_field<'a>(
+    best: &mut Option<ShortcutMatch<'a>>,
+    matcher: &mut Matcher,
+    query: &[char],
+    shortcut: &'a Shortcut,
+    weight: f64,
+    field: &'static str,
+    text: &'a str,
+    keep_text: bool,
+) {
+    if let Some(score) = matcher.score(query, text) {
+        let weighted = score as f64 * weight;
+        if best.as_ref().is_none_or(|b| weighted > b.score) {
+            *best = Some(ShortcutMatch {
+                shortcut,
+                score: weighted,
+                field,
+                positions: matcher.positions().to_vec(),
+                matched_text: if keep_text { Some(text) } else { None },
+                parameter_input: None,
+            });
+        }
+    }
+}
+
+// 查找不区分大小写的连续子串，返回起始字符下标
+fn find_substring(query: &[char], text: &str) -> Option<usize> {
+    let first = *query.first()?;
+    text.char_indices()
+        .enumerate()
+        .filter(|(_, (_, c))| lower_char(*c) == first)
+        .find(|(_, (byte, _))| {
+            text[*byte..].chars().map(lower_char).take(query.len()).eq(query.iter().copied())
+        })
+        .map(|(index, _)| index)
+}
+
+// 路径匹配：文件名部分做模糊匹配，完整路径只接受连续子串，
+// 避免长路径中零散字符造成大量无意义的命中
+fn match_path<'a>(matcher: &mut Matcher, query: &[char], shortcut: &'a Shortcut) -> Option<ShortcutMatch<'a>> {
+    let path = shortcut.file_path.as_str();
+    let name_start = path.rfind(['/', '\\']).map(|i| i + 1).unwrap_or(0);
+    let offset = path[..name_start].chars().count();
+
+    if let Some(score) = matcher.score(query, &path[name_start..]) {
+        return Some(ShortcutMatch {
+            shortcut,
+            score: score as f64 * 0.5,
+            field: "path",
+            positions: matcher.positions().iter().map(|p| p + offset).collect(),
+            matched_text: None,
+            parameter_input: None,
+        });
+    }
+
+    let start = find_substring(query, path)?;
+
+    Some(ShortcutMatch {
+        shortcut,
+        score: (SCORE_MATCH * query.len() as i32) as f64 * 0.5,
+        field: "path",
+        positions: (start..start + query.len()).collect(),
+        matched_text: None,
+        parameter_input: None,
+    })
+}
+
+// 在单个快捷方式的各个字段中寻找单个词的最佳匹配
+fn match_term<'a>(matcher: &mut Matcher, shortcut: &'a Shortcut, query: &[char]) -> Option<ShortcutMatch<'a>> {
+    let mut best: Option<ShortcutMatch<'a>> = None;
+
+    // 各字段权重：名称和别名最重要，关键词次之
+    consider_field(&mut best, matcher, query, shortcut, 1.0, "name", &shortcut.name, false);
+
+    // 中文名称额外尝试拼音全拼和首字母匹配，命中区间仍指向名称中的汉字
+    if let Some(m) = crate::pinyin_match::match_pinyin(query, &shortcut.name) {
+        let score = m.score as f64;
+        if best.as_ref().is_none_or(|b| score > b.score) {
+            best = Some(ShortcutMatch {
+                shortcut,
+                score,
+                field: "name",
+                positions: m.positions,
+                matched_text: None,
+                parameter_input: None,
+            });
+        }
+    }
+    for alias in shortcut.aliases.iter() {
+        consider_field(&mut best, matcher, query, shortcut, 1.0, "alias", alias, true);
+    }
+    for keyword in shortcut.keywords.iter() {
+        consider_field(&mut best, matcher, query, shortcut, 0.8, "keyword", keyword, true);
+    }
+
+    // 路径只作为兜底匹配，权重最低
+    if best.is_none() {
+        best = match_path(matcher, query, shortcut);
+    }
+
+    best
+}
+
+// 合并各个词的匹配：得分相加，字段取得分最高的词，同一字段上其他词的命中一并高亮
+fn combine_term_matches(mut term_matches: Vec<ShortcutMatch>) -> Option<ShortcutMatch> {
+    let best_index = (0..term_matches.len())
+        .max_by(|&a, &b| term_matches[a].score.partial_cmp(&term_matches[b].score).unwrap_or(Ordering::Equal))?;
+    let total: f64 = term_matches.iter().map(|m| m.score).sum();
+
+    let mut best = term_matches.swap_remove(best_index);
+    for other in term_matches {
+        if other.field == best.field && other.matched_text == best.matched_text {
+            best.positions.extend(other.positions);
+        }
+    }
+    best.positions.sort_unstable();
+    best.positions.dedup();
+    best.score = total;
+    Some(best)
+}
+
+// 在单个快捷方式中匹配全部普通词，每个词分别匹配，任一词未命中即不匹配
+fn match_shortcut<'a>(
+    matcher: &mut Matcher,
+    shortcut: &'a Shortcut,
+    terms: &[Vec<char>],
+    query_lower: &str,
+    now: DateTime<Utc>,
+) -> Option<ShortcutMatch<'a>> {
+    if let Some(alias) = shortcut.aliases.iter().find(|a| a.to_lowercase() == query_lower) {
+        return Some(ShortcutMatch {
+            shortcut,
+            score: SCORE_EXACT_ALIAS + usage_boost(shortcut, now),
+            field: "alias",
+            positions: (0..query_lower.chars().count()).collect(),
+            matched_text: Some(alias.as_str()),
+            parameter_input: None,
+        });
+    }
+
+    let term_matches = terms.iter()
+        .map(|term| match_term(matcher, shortcut, term))
+        .collect::<Option<Vec<_>>>()?;
+
+    combine_term_matches(term_matches).map(|mut b| {
+        b.score += usage_boost(shortcut, now);
+        b
+    })
+}
+
+// 带参数的快捷方式：查询的第一个词命中名称或别名时，剩余文本作为参数（例如 "jira OPS-123"）
+fn match_with_input<'a>(
+    matcher: &mut Matcher,
+    shortcut: &'a Shortcut,
+    plan: &SearchPlan,
+    now: DateTime<Utc>,
+) -> Option<ShortcutMatch<'a>> {
+    let (head, _) = plan.fuzzy_text.split_once(char::is_whitespace)?;
+    // 参数需要保留原始大小写，从原始文本中取剩余部分
+    let (_, rest) = plan.raw_text.split_once(char::is_whitespace)?;
+    let rest = rest.trim();
+    if rest.is_empty() || !crate::shortcut_params::has_parameters(shortcut) {
+        return None;
+    }
+
+    let head_chars: Vec<char> = head.chars().collect();
+    let mut m = match_shortcut(matcher, shortcut, &[head_chars], head, now)?;
+    if m.field == "path" {
+        return None;
+    }
+
+    m.parameter_input = Some(rest.to_string());
+    Some(m)
+}
+
+// 结果排序：得分降序，其次按手动排序、名称和ID保证结果稳定
+fn compare_matches(a: &ShortcutMatch, b: &ShortcutMatch) -> Ordering {
+    b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal)
+        .then_with(|| a.shortcut.sort_order.cmp(&b.shortcut.sort_order))
+        .then_with(|| a.shortcut.name.cmp(&b.shortcut.name))
+        .then_with(|| a.shortcut.id.cmp(&b.shortcut.id))
+}
+
+// 可执行程序的常见扩展名，用于 kind:app
+const APP_EXTENSIONS: &[&str] = &["exe", "bat", "cmd", "ps1", "lnk", "msi", "app", "desktop", "appimage", "sh"];
+
+fn target_kind_matches(shortcut: &Shortcut, kind: TargetKind) -> bool {
+    let path = shortcut.file_path.as_str();
+    match kind {
+        TargetKind::Url => is_url(path),
+        TargetKind::Directory => !is_url(path) && Path::new(path).is_dir(),
+        TargetKind::File => !is_url(path) && Path::new(path).is_file(),
+        TargetKind::App => {
+            let extension = Path::new(path).extension()
+                .and_then(|e| e.to_str())
+                .map(|e| e.to_lowercase())
+                .unwrap_or_default();
+            !is_url(path) && APP_EXTENSIONS.contains(&extension.as_str())
+        }
+    }
+}
+
+fn filter_matches(filter: &Filter, shortcut: &Shortcut, categories: &[Category], now: DateTime<Utc>) -> bool {
+    match filter {
+        Filter::Category(value) => {
+            let category = shortcut.category_id.as_ref()
+                .and_then(|id| categories.iter().find(|c| &c.id == id));
+            match category {
+                Some(category) => {
+                    category.id.to_lowercase() == *value || category.name.to_lowercase().starts_with(value.as_str())
+                }
+                None => false,
+            }
+        }
+        Filter::Tag(value) => shortcut.keywords.iter().any(|k| k.to_lowercase() == *value),
+        Filter::Extension(value) => Path::new(&shortcut.file_path).extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| e.to_lowercase() == *value),
+        Filter::Kind(kind) => target_kind_matches(shortcut, *kind),
+        Filter::Missing(missing) => shortcut.file_exists != *missing,
+        Filter::Used(used) => match (used, shortcut.last_used) {
+            (UsedFilter::Never, last_used) => last_used.is_none(),
+            (UsedFilter::Within(duration), Some(last_used)) => now - last_used <= *duration,
+            (UsedFilter::OlderThan(duration), Some(last_used)) => now - last_used > *duration,
+            (_, None) => false,
+        },
+    }
+}
+
+// 名称、别名、关键词或路径中是否包含指定文本
+fn contains_text(shortcut: &Shortcut, text: &str) -> bool {
+    shortcut.name.to_lowercase().contains(text)
+        || shortcut.aliases.iter().any(|a| a.to_lowercase().contains(text))
+        || shortcut.keywords.iter().any(|k| k.to_lowercase().contains(text))
+        || shortcut.file_path.to_lowercase().contains(text)
+}
+
+// 检查快捷方式是否满足执行计划中的过滤条件、短语和排除词
+fn passes_plan(plan: &SearchPlan, shortcut: &Shortcut, categories: &[Category], now: DateTime<Utc>) -> bool {
+    plan.filters.iter().all(|(negated, filter)| filter_matches(filter, shortcut, categories, now) != *negated)
+        && plan.phrases.iter().all(|phrase| contains_text(shortcut, phrase))
+        && !plan.excluded_terms.iter().any(|term| contains_text(shortcut, term))
+}
+
+fn to_search_result(m: ShortcutMatch) -> SearchResult {
+    SearchResult {
+        shortcut: m.shortcut.clone(),
+        score: m.score,
+        matched_field: m.field.to_string(),
+        match_ranges: positions_to_ranges(&m.positions),
+        matched_text: m.matched_text.map(|t| t.to_string()),
+        source: "shortcut".to_string(),
+        is_saved: true,
+        parameter_input: m.parameter_input,
+    }
+}
+
+// 合并后的排序：得分降序，同分时已保存的快捷方式优先
+fn compare_results(a: &SearchResult, b: &SearchResult) -> Ordering {
+    b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal)
+        .then_with(|| b.is_saved.cmp(&a.is_saved))
+        .then_with(|| a.shortcut.sort_order.cmp(&b.shortcut.sort_order))
+        .then_with(|| a.shortcut.name.cmp(&b.shortcut.name))
+        .then_with(|| a.shortcut.id.cmp(&b.shortcut.id))
+}
+
+// 文件索引中的文件只支持 ext 和 kind:file/dir 条件，其他条件只针对已保存的快捷方式
+fn file_filter_matches(filter: &Filter, file: &IndexedFile) -> Option<bool> {
+    match filter {
+        Filter::Extension(value) => Some(Path::new(&file.path).extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| e.to_lowercase() == *value)),
+        Filter::Kind(TargetKind::File) => Some(!file.is_dir),
+        Filter::Kind(TargetKind::Directory) => Some(file.is_dir),
+        _ => None,
+    }
+}
+
+// 在文件索引中搜索尚未保存为快捷方式的文件
+fn search_indexed_files(data: &AppData, plan: &SearchPlan, terms: &[Vec<char>]) -> Vec<SearchResult> {
+    let indexer = &crate::file_indexer::GLOBAL_FILE_INDEXER;
+    let max_results = indexer.max_results();
+    if max_results == 0 {
+        return Vec::new();
+    }
+
+    let saved: HashSet<&str> = data.shortcuts.iter().map(|s| s.file_path.as_str()).collect();
+    let mut matcher = Matcher::default();
+    let mut results: Vec<SearchResult> = Vec::new();
+
+    indexer.for_each(|file| {
+        if saved.contains(file.path.as_str()) {
+            return;
+        }
+
+        let filters_pass = plan.filters.iter().all(|(negated, filter)| {
+            file_filter_matches(filter, file).is_some_and(|matched| matched != *negated)
+        });
+        if !filters_pass {
+            return;
+        }
+
+        let path_lower = file.path.to_lowercase();
+        if !plan.phrases.iter().all(|p| path_lower.contains(p.as_str()))
+            || plan.excluded_terms.iter().any(|t| path_lower.contains(t.as_str())) {
+            return;
+        }
+
+        // 每个词都要命中文件名，得分相加
+        let mut score = 0;
+        let mut positions: Vec<usize> = Vec::new();
+        for term in terms {
+            match matcher.score(term, &file.name) {
+                Some(term_score) => {
+                    score += term_score;
+                    positions.extend_from_slice(matcher.positions());
+                }
+                None => return,
+            }
+        }
+        positions.sort_unstable();
+        positions.dedup();
+
+        let mut shortcut = Shortcut::new(file.name.clone(), file.path.clone(), None);
+        shortcut.id = format!("file:{}", file.path);
+
+        results.push(SearchResult {
+            shortcut,
+            score: score as f64 * 0.6,
+            matched_field: "name".to_string(),
+            match_ranges: positions_to_ranges(&positions),
+            matched_text: None,
+            source: "file".to_string(),
+            is_saved: false,
+            parameter_input: None,
+        });
+    });
+
+    results.sort_by(compare_results);
+    results.truncate(max_results);
+    results
+}
+
+// 按执行计划搜索：先用过滤条件缩小范围，再对每个普通词做模糊匹配并排序
+pub fn execute_plan(data: &AppData, plan: &SearchPlan, limit: Option<usize>) -> Vec<SearchResult> {
+    let limit = limit.unwrap_or(usize::MAX);
+    let active = data.shortcuts.iter().filter(|s| s.is_active);
+
+    if plan.is_empty() {
+        return active.take(limit)
+            .map(|s| SearchResult {
+                shortcut: s.clone(),
+                score: 0.0,
+                matched_field: "none".to_string(),
+                match_ranges: Vec::new(),
+                matched_text: None,
+                source: "shortcut".to_string(),
+                is_saved: true,
+                parameter_input: None,
+            })
+            .collect();
+    }
+
+    let now = Utc::now();
+    let candidates = active.filter(|s| passes_plan(plan, s, &data.categories, now));
+
+    let terms: Vec<Vec<char>> = plan.terms.iter().map(|t| t.chars().collect()).collect();
+
+    let mut matches: Vec<ShortcutMatch> = if terms.is_empty() {
+        // 只有过滤条件时，按使用频率排序
+        candidates.map(|s| ShortcutMatch {
+            shortcut: s,
+            score: usage_boost(s, now),
+            field: "none",
+            positions: Vec::new(),
+            matched_text: None,
+            parameter_input: None,
+        }).collect()
+    } else {
+        let mut matcher = Matcher::default();
+        candidates
+            .filter_map(|s| match_shortcut(&mut matcher, s, &terms, &plan.fuzzy_text, now)
+                .or_else(|| match_with_input(&mut matcher, s, plan, now)))
+            .collect()
+    };
+
+    // 只需要前 limit 个结果时先做部分选择，避免对全部结果排序
+    if limit < matches.len() {
+        matches.select_nth_unstable_by(limit, compare_matches);
+        matches.truncate(limit);
+    }
+    matches.sort_by(compare_matches);
+
+    let mut results: Vec<SearchResult> = matches.into_iter().map(to_search_result).collect();
+
+    // 有文本查询时合并文件索引中的结果
+    if !terms.is_empty() {
+        let files = search_indexed_files(data, plan, &terms);
+        if !files.is_empty() {
+            results.extend(files);
+            results.sort_by(compare_results);
+            results.truncate(limit);
+        }
+    }
+
+    results
+}
+
+// 搜索快捷方式：解析查询语法后执行
+pub fn search_shortcuts(data: &AppData, query: &str, limit: Option<usize>) -> Result<Vec<SearchResult>, QueryError> {
+    let plan = crate::query::compile_query(query)?;
+    Ok(execute_plan(data, &plan, limit))
+}
+
+// 按指定方式排序快捷方式；名称排序使用拼音顺序，使中英文名称混排
+pub fn sort_shortcuts(shortcuts: &mut [Shortcut], mode: ShortcutSortMode) {
+    match mode {
+        ShortcutSortMode::Manual => {
+            shortcuts.sort_by_key(|s| s.sort_order);
+        }
+        ShortcutSortMode::Name => {
+            let mut keyed: Vec<(String, Shortcut)> = shortcuts.iter()
+                .map(|s| (crate::pinyin_match::collation_key(&s.name), s.clone()))
+                .collect();
+            keyed.sort_by(|(ka, a), (kb, b)| ka.cmp(kb).then_with(|| a.name.cmp(&b.name)));
+            for (slot, (_, shortcut)) in shortcuts.iter_mut().zip(keyed) {
+                *slot = shortcut;
+            }
+        }
+        ShortcutSortMode::Frecency => {
+            let now = Utc::now();
+            let mut keyed: Vec<(f64, Shortcut)> = shortcuts.iter()
+                .map(|s| (crate::frecency::score_at(s, now), s.clone()))
+                .collect();
+            keyed.sort_by(|(sa, a), (sb, b)| {
+                sb.partial_cmp(sa).unwrap_or(Ordering::Equal)
+                    .then_with(|| b.last_used.cmp(&a.last_used))
+                    .then_with(|| a.sort_order.cmp(&b.sort_order))
+            });
+            for (slot, (_, shortcut)) in shortcuts.iter_mut().zip(keyed) {
+                *slot = shortcut;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    fn chars(text: &str) -> Vec<char> {
+        text.chars().collect()
+    }
+
+    fn shortcut(name: &str, file_path: &str) -> Shortcut {
+        Shortcut::new(name.to_string(), file_path.to_string(), None)
+    }
+
+    fn data(shortcuts: Vec<Shortcut>) -> AppData {
+        AppData { shortcuts, ..AppData::default() }
+    }
+
+    fn names(results: &[SearchResult]) -> Vec<&str> {
+        results.iter().map(|r| r.shortcut.name.as_str()).collect()
+    }
+
+    #[test]
+    fn matcher_requires_an_in_order_subsequence() {
+        let mut matcher = Matcher::default();
+        assert_eq!(matcher.score(&chars(""), "anything"), Some(0));
+        assert_eq!(matcher.score(&chars("cba"), "abc"), None);
+        assert_eq!(matcher.score(&chars("abcd"), "abc"), None);
+        assert_eq!(matcher.score(&chars("abc"), &"x".repeat(MAX_TARGET_LEN + 1)), None);
+
+        assert!(matcher.score(&chars("abc"), "aXbXc").is_some());
+        assert_eq!(matcher.positions(), &[0, 2, 4]);
+    }
+
+    #[test]
+    fn matcher_prefers_exact_prefix_and_boundaries() {
+        let mut matcher = Matcher::default();
+        let mut score = |query: &str, target: &str| matcher.score(&chars(query), target).unwrap();
+
+        assert!(score("code", "Code") > score("code", "Code Editor"));
+        assert!(score("code", "Code Editor") > score("code", "VS Code"));
+        assert!(score("code", "VS Code") > score("code", "Xcodes"));
+        assert!(score("vsc", "Visual Studio Code") > score("vsc", "Devscripts"));
+        assert!(score("fb", "FooBar") > score("fb", "Fubar"));
+        assert_eq!(score("term", "TERM"), score("term", "term"));
+    }
+
+    #[test]
+    fn matcher_places_hits_on_word_starts() {
+        let mut matcher = Matcher::default();
+        matcher.score(&chars("vsc"), "Visual Studio Code").unwrap();
+        assert_eq!(matcher.positions(), &[0, 7, 14]);
+        matcher.score(&chars("gc"), "google-chrome").unwrap();
+        assert_eq!(matcher.positions(), &[0, 7]);
+    }
+
+    #[test]
+    fn merges_positions_into_ranges() {
+        assert_eq!(positions_to_ranges(&[]), Vec::<(usize, usize)>::new());
+        assert_eq!(positions_to_ranges(&[0, 1, 2, 5, 7, 8]), vec![(0, 3), (5, 6), (7, 9)]);
+    }
+
+    #[test]
+    fn ranks_shortcuts_by_match_quality() {
+        let data = data(vec![
+            shortcut("Notepad++", "/opt/npp/notepad++"),
+            shortcut("Telegram", "/usr/bin/telegram"),
+            shortcut("Terminal", "/usr/bin/gnome-terminal"),
+            shortcut("Term", "/usr/bin/term"),
+        ]);
+
+        let results = search_shortcuts(&data, "term", None).unwrap();
+        assert_eq!(names(&results), vec!["Term", "Terminal", "Telegram"]);
+        assert_eq!(results[1].matched_field, "name");
+        assert_eq!(results[1].match_ranges, vec![(0, 4)]);
+    }
+
+    #[test]
+    fn exact_alias_ranks_first() {
+        let mut alias = shortcut("Mozilla Firefox", "/usr/bin/firefox");
+        alias.aliases = vec!["ff".to_string()];
+        let data = data(vec![shortcut("ff", "/usr/bin/ff"), shortcut("FreeFileSync", "/usr/bin/ffs"), alias]);
+
+        let results = search_shortcuts(&data, "FF", None).unwrap();
+        assert_eq!(results[0].shortcut.name, "Mozilla Firefox");
+        assert_eq!(results[0].matched_field, "alias");
+        assert_eq!(results[0].matched_text.as_deref(), Some("ff"));
+        assert_eq!(names(&results[1..]), vec!["ff", "FreeFileSync"]);
+    }
+
+    #[test]
+    fn matches_keywords_pinyin_and_paths() {
+        let mut keyword = shortcut("Browser", "/usr/bin/browser");
+        keyword.keywords = vec!["internet".to_string()];
+        let data = data(vec![keyword, shortcut("微信", "/opt/wechat/wechat"), shortcut("Editor", "/opt/tools/vim")]);
+
+        let results = search_shortcuts(&data, "internet", None).unwrap();
+        assert_eq!((names(&results), results[0].matched_field.as_str()), (vec!["Browser"], "keyword"));
+
+        let results = search_shortcuts(&data, "wx", None).unwrap();
+        assert_eq!(names(&results), vec!["微信"]);
+        assert_eq!(results[0].match_ranges, vec![(0, 2)]);
+
+        let results = search_shortcuts(&data, "vim", None).unwrap();
+        assert_eq!((names(&results), results[0].matched_field.as_str()), (vec!["Editor"], "path"));
+        assert_eq!(results[0].match_ranges, vec![(11, 14)]);
+
+        // 路径的目录部分只接受连续子串
+        assert!(search_shortcuts(&data, "optv", None).unwrap().is_empty());
+        assert_eq!(names(&search_shortcuts(&data, "tools/v", None).unwrap()), vec!["Editor"]);
+    }
+
+    #[test]
+    fn usage_and_sort_order_break_ties() {
+        let mut first = shortcut("Calc", "/usr/bin/calc-a");
+        first.sort_order = 2;
+        let mut second = shortcut("Calc", "/usr/bin/calc-b");
+        second.sort_order = 1;
+        let mut used = shortcut("Calc", "/usr/bin/calc-c");
+        used.sort_order = 3;
+        crate::frecency::record_launch(&mut used, Utc::now());
+
+        let results = search_shortcuts(&data(vec![first, second, used]), "calc", None).unwrap();
+        let paths: Vec<&str> = results.iter().map(|r| r.shortcut.file_path.as_str()).collect();
+        assert_eq!(paths, vec!["/usr/bin/calc-c", "/usr/bin/calc-b", "/usr/bin/calc-a"]);
+    }
+
+    #[test]
+    fn applies_filters_exclusions_and_limit() {
+        let mut inactive = shortcut("Deploy old", "/srv/deploy-old.sh");
+        inactive.is_active = false;
+        let mut missing = shortcut("Deploy staging", "/srv/deploy-staging.sh");
+        missing.file_exists = false;
+        let data = data(vec![
+            shortcut("Deploy prod", "/srv/deploy-prod.sh"),
+            shortcut("Deploy docs", "https://example.com/deploy"),
+            missing,
+            inactive,
+        ]);
+
+        assert_eq!(names(&search_shortcuts(&data, "deploy ext:sh", None).unwrap()), vec!["Deploy prod", "Deploy staging"]);
+        assert_eq!(names(&search_shortcuts(&data, "deploy kind:url", None).unwrap()), vec!["Deploy docs"]);
+        assert_eq!(names(&search_shortcuts(&data, "missing:true", None).unwrap()), vec!["Deploy staging"]);
+        assert_eq!(names(&search_shortcuts(&data, "deploy -staging -docs", None).unwrap()), vec!["Deploy prod"]);
+        assert_eq!(names(&search_shortcuts(&data, r#""prod""#, None).unwrap()), vec!["Deploy prod"]);
+        assert_eq!(search_shortcuts(&data, "deploy", Some(2)).unwrap().len(), 2);
+        assert_eq!(search_shortcuts(&data, "", None).unwrap().len(), 3);
+        assert!(search_shortcuts(&data, "kind:car", None).is_err());
+    }
+
+    #[test]
+    fn matches_each_term_independently() {
+        let mut tools = shortcut("Toolbox", "/opt/toolbox");
+        tools.keywords = vec!["delta".to_string()];
+        let data = data(vec![
+            shortcut("Charlie Delta", "/opt/cd"),
+            shortcut("Delta Charlie", "/opt/dc"),
+            shortcut("Charlie Echo", "/opt/ce"),
+            tools,
+        ]);
+
+        let results = search_shortcuts(&data, "delta charlie", None).unwrap();
+        assert_eq!(names(&results), vec!["Charlie Delta", "Delta Charlie"]);
+        assert_eq!(results[0].score, results[1].score);
+        assert_eq!(results[0].matched_field, "name");
+        assert_eq!(results[0].match_ranges, vec![(0, 7), (8, 13)]);
+
+        // 每个词都要命中，但可以落在不同字段上
+        let results = search_shortcuts(&data, "delta tool", None).unwrap();
+        assert_eq!(names(&results), vec!["Toolbox"]);
+        assert!(search_shortcuts(&data, "charlie foxtrot", None).unwrap().is_empty());
+
+        // 多个词的得分相加
+        let mut matcher = Matcher::default();
+        let single = match_shortcut(&mut matcher, &data.shortcuts[0], &[chars("delta")], "delta", Utc::now()).unwrap();
+        let both = match_shortcut(&mut matcher, &data.shortcuts[0], &[chars("delta"), chars("charlie")], "delta charlie", Utc::now()).unwrap();
+        assert!(both.score > single.score);
+    }
+
+    #[test]
+    fn passes_remaining_text_to_parameterized_shortcuts() {
+        let mut jira = shortcut("Jira", "https://jira.example.com/browse/{ticket}");
+        jira.aliases = vec!["j".to_string()];
+        let data = data(vec![jira, shortcut("Jigsaw", "/usr/bin/jigsaw")]);
+
+        let results = search_shortcuts(&data, "j OPS-123", None).unwrap();
+        assert_eq!(names(&results), vec!["Jira"]);
+        assert_eq!(results[0].parameter_input.as_deref(), Some("OPS-123"));
+    }
+
+    // 10000 个快捷方式中每次搜索应在 50ms 内完成；计时只在优化构建中有意义，调试构建下默认跳过
+    // （cargo test --release 运行）
+    #[test]
+    #[cfg_attr(debug_assertions, ignore)]
+    fn searches_ten_thousand_shortcuts_quickly() {
+        const BUDGET_MS: f64 = 50.0;
+
+        let words = ["alpha", "bravo", "charlie", "delta", "echo", "foxtrot", "golf", "hotel", "india", "juliet"];
+        let shortcuts = (0..10_000)
+            .map(|i| {
+                let name = format!("{} {} {}", words[i % 10], words[i / 10 % 10], i);
+                let mut s = shortcut(&name, &format!("/opt/apps/{}/{}/bin/app-{}", words[i / 100 % 10], words[i % 7], i));
+                s.keywords = vec![words[(i + 3) % 10].to_string()];
+                s
+            })
+            .collect();
+        let data = data(shortcuts);
+
+        // 每个查询取三次中最快的一次，减少调度抖动的影响
+        for query in ["a", "ab", "charlie delta", "hotel 99", "zz", "app-9999", "ech fox"] {
+            let elapsed = (0..3)
+                .map(|_| {
+                    let started = Instant::now();
+                    search_shortcuts(&data, query, Some(50)).unwrap();
+                    started.elapsed().as_secs_f64() * 1000.0
+                })
+                .fold(f64::INFINITY, f64::min);
+            assert!(elapsed < BUDGET_MS, "'{}' took {:.1}ms", query, elapsed);
+        }
+
+        let results = search_shortcuts(&data, "hotel india 9987", Some(50)).unwrap();
+        assert_eq!(results[0].shortcut.name, "hotel india 9987");
+    }
+}
@@ -0,0 +1,354 @@
+use crate::models::{Shortcut, ShortcutParameter};
+use std::collections::HashMap;
+
+// 参数占位符：
+//   {ticket}          必填参数
+//   {query:rust}      带默认值的参数
+//   {{ 和 }}          字面量的花括号
+// 名称必须以字母开头，只能包含字母、数字和下划线；其他花括号内容（例如 Windows 的 {GUID}）按原文处理
+//
+// 填充方式：
+//   file_path 是网址时对参数值做百分号编码，否则原样替换
+//   arguments 先按 shell 规则拆分为参数列表再逐项替换，参数值不会被再次拆分，无需担心空格和引号
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Literal(String),
+    Param { name: String, default_value: Option<String> },
+}
+
+fn is_param_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn push_literal(segments: &mut Vec<Segment>, text: &str) {
+    if let Some(Segment::Literal(last)) = segments.last_mut() {
+        last.push_str(text);
+    } else {
+        segments.push(Segment::Literal(text.to_string()));
+    }
+}
+
+// 把模板拆分为字面量和占位符；不合法的占位符按字面量处理，因此不会失败
+fn parse_template(template: &str) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    let mut rest = template;
+
+    while let Some(open) = rest.find(['{', '}']) {
+        push_literal(&mut segments, &rest[..open]);
+        let tail = &rest[open..];
+
+        if tail.starts_with("{{") || tail.starts_with("}}") {
+            push_literal(&mut segments, &tail[..1]);
+            rest = &tail[2..];
+            continue;
+        }
+
+        if let Some(after) = tail.strip_prefix('}') {
+            push_literal(&mut segments, "}");
+            rest = after;
+            continue;
+        }
+
+        let Some(close) = tail.find('}') else {
+            push_literal(&mut segments, tail);
+            rest = "";
+            break;
+        };
+
+        let content = &tail[1..close];
+        let (name, default_value) = match content.split_once(':') {
+            Some((name, default_value)) => (name.trim(), Some(default_value.to_string())),
+            None => (content.trim(), None),
+        };
+
+        if is_param_name(name) {
+            segments.push(Segment::Param { name: name.to_string(), default_value });
+        } else {
+            push_literal(&mut segments, &tail[..=close]);
+        }
+        rest = &tail[close + 1..];
+    }
+
+    push_literal(&mut segments, rest);
+    segments.retain(|s| !matches!(s, Segment::Literal(text) if text.is_empty()));
+    segments
+}
+
+// 按 POSIX shell 的规则拆分参数：支持单引号、双引号和反斜杠转义
+fn split_arguments(arguments: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut chars = arguments.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_word = true;
+                for c in chars.by_ref() {
+                    if c == '\'' {
+                        break;
+                    }
+                    current.push(c);
+                }
+            }
+            '"' => {
+                in_word = true;
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' if matches!(chars.peek(), Some('"') | Some('\\')) => {
+                            current.push(chars.next().unwrap_or('\\'));
+                        }
+                        _ => current.push(c),
+                    }
+                }
+            }
+            // Windows 路径里的反斜杠很常见，只把它当作空白和引号的转义
+            '\\' if matches!(chars.peek(), Some(c) if c.is_whitespace() || *c == '"' || *c == '\'') => {
+                in_word = true;
+                current.push(chars.next().unwrap_or('\\'));
+            }
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            _ => {
+                in_word = true;
+                current.push(c);
+            }
+        }
+    }
+
+    if in_word {
+        words.push(current);
+    }
+    words
+}
+
+fn collect_parameters(segments: &[Segment], parameters: &mut Vec<ShortcutParameter>) {
+    for segment in segments {
+        if let Segment::Param { name, default_value } = segment {
+            match parameters.iter_mut().find(|p| &p.name == name) {
+                // 同名参数只出现一次，默认值取第一个给出的
+                Some(existing) => {
+                    if existing.default_value.is_none() {
+                        existing.default_value = default_value.clone();
+                    }
+                }
+                None => parameters.push(ShortcutParameter {
+                    name: name.clone(),
+                    default_value: default_value.clone(),
+                }),
+            }
+        }
+    }
+}
+
+// 快捷方式中的全部参数，按出现顺序（先 file_path 后 arguments）
+pub fn parameters(shortcut: &Shortcut) -> Vec<ShortcutParameter> {
+    let mut parameters = Vec::new();
+    collect_parameters(&parse_template(&shortcut.file_path), &mut parameters);
+    if let Some(arguments) = &shortcut.arguments {
+        collect_parameters(&parse_template(arguments), &mut parameters);
+    }
+    parameters
+}
+
+pub fn has_parameters(shortcut: &Shortcut) -> bool {
+    let has_placeholder = |text: &str| {
+        text.contains('{') && parse_template(text).iter().any(|s| matches!(s, Segment::Param { .. }))
+    };
+    has_placeholder(&shortcut.file_path) || shortcut.arguments.as_deref().is_some_and(has_placeholder)
+}
+
+// 把搜索框中的剩余文本分配给参数：前面的参数各取一个词，最后一个参数取剩下的全部文本
+pub fn assign_input(parameters: &[ShortcutParameter], input: &str) -> HashMap<String, String> {
+    let mut values = HashMap::new();
+    let mut rest = input.trim();
+
+    for (index, parameter) in parameters.iter().enumerate() {
+        if rest.is_empty() {
+            break;
+        }
+        if index + 1 == parameters.len() {
+            values.insert(parameter.name.clone(), rest.to_string());
+            break;
+        }
+        let (word, remaining) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+        values.insert(parameter.name.clone(), word.to_string());
+        rest = remaining.trim_start();
+    }
+
+    values
+}
+
+fn render(segments: &[Segment], values: &HashMap<String, String>, encode: fn(&str) -> String) -> String {
+    let mut rendered = String::new();
+    for segment in segments {
+        match segment {
+            Segment::Literal(text) => rendered.push_str(text),
+            Segment::Param { name, default_value } => {
+                // 空值视为未填写，使用默认值
+                let value = values.get(name)
+                    .filter(|v| !v.trim().is_empty())
+                    .or(default_value.as_ref())
+                    .map(|v| v.as_str())
+                    .unwrap_or("");
+                rendered.push_str(&encode(value));
+            }
+        }
+    }
+    rendered
+}
+
+// 填充参数后的启动目标
+#[derive(Debug, Clone)]
+pub struct ResolvedTarget {
+    pub target: String,
+    pub arguments: Vec<String>,
+}
+
+// 填充参数；有必填参数缺少值时返回缺少的参数列表
+pub fn resolve(shortcut: &Shortcut, values: &HashMap<String, String>) -> Result<ResolvedTarget, Vec<ShortcutParameter>> {
+    let missing: Vec<ShortcutParameter> = parameters(shortcut).into_iter()
+        .filter(|p| p.default_value.is_none() && values.get(&p.name).is_none_or(|v| v.trim().is_empty()))
+        .collect();
+    if !missing.is_empty() {
+        return Err(missing);
+    }
+
+    let encode_target: fn(&str) -> String = if crate::utils::is_url(&shortcut.file_path) {
+        crate::utils::percent_encode
+    } else {
+        str::to_string
+    };
+    let target = render(&parse_template(&shortcut.file_path), values, encode_target);
+
+    let arguments = shortcut.arguments.as_deref()
+        .map(|arguments| split_arguments(arguments).iter()
+            .map(|word| render(&parse_template(word), values, str::to_string))
+            .collect())
+        .unwrap_or_default();
+
+    Ok(ResolvedTarget { target, arguments })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn literal(text: &str) -> Segment {
+        Segment::Literal(text.to_string())
+    }
+
+    fn param(name: &str, default_value: Option<&str>) -> Segment {
+        Segment::Param { name: name.to_string(), default_value: default_value.map(str::to_string) }
+    }
+
+    fn shortcut(file_path: &str, arguments: Option<&str>) -> Shortcut {
+        let mut shortcut = Shortcut::new("test".to_string(), file_path.to_string(), None);
+        shortcut.arguments = arguments.map(str::to_string);
+        shortcut
+    }
+
+    fn values(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn parses_templates() {
+        let cases = [
+            ("plain text", vec![literal("plain text")]),
+            ("{ticket}", vec![param("ticket", None)]),
+            ("q={query:rust lang}&x", vec![literal("q="), param("query", Some("rust lang")), literal("&x")]),
+            ("{ name : }", vec![param("name", Some(" "))]),
+            ("{a}{b:1}", vec![param("a", None), param("b", Some("1"))]),
+            ("{{literal}}", vec![literal("{literal}")]),
+            ("{{{name}}}", vec![literal("{"), param("name", None), literal("}")]),
+            ("{1abc} {a-b} {}", vec![literal("{1abc} {a-b} {}")]),
+            ("::{645FF040-5081-101B-9F08-08002B30309D}", vec![literal("::{645FF040-5081-101B-9F08-08002B30309D}")]),
+            ("open { brace", vec![literal("open { brace")]),
+            ("stray } brace", vec![literal("stray } brace")]),
+        ];
+        for (template, expected) in cases {
+            assert_eq!(parse_template(template), expected, "{}", template);
+        }
+    }
+
+    #[test]
+    fn splits_arguments_like_a_shell() {
+        let cases: [(&str, &[&str]); 8] = [
+            ("", &[]),
+            ("  a   b  ", &["a", "b"]),
+            ("'single quoted' x", &["single quoted", "x"]),
+            (r#""double \"quoted\"" x"#, &[r#"double "quoted""#, "x"]),
+            (r"escaped\ space", &["escaped space"]),
+            (r"C:\Program Files\app", &[r"C:\Program", r"Files\app"]),
+            (r#""C:\Program Files\app""#, &[r"C:\Program Files\app"]),
+            ("''", &[""]),
+        ];
+        for (arguments, expected) in cases {
+            assert_eq!(split_arguments(arguments), expected, "{}", arguments);
+        }
+    }
+
+    #[test]
+    fn assigns_input_words_to_parameters() {
+        let parameters: Vec<ShortcutParameter> = ["repo", "query"].iter()
+            .map(|name| ShortcutParameter { name: name.to_string(), default_value: None })
+            .collect();
+
+        let cases: [(&str, &[(&str, &str)]); 4] = [
+            ("", &[]),
+            ("  rust ", &[("repo", "rust")]),
+            ("rust  borrow checker", &[("repo", "rust"), ("query", "borrow checker")]),
+            ("a b c d", &[("repo", "a"), ("query", "b c d")]),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(assign_input(&parameters, input), values(expected), "{}", input);
+        }
+        assert!(assign_input(&[], "ignored").is_empty());
+    }
+
+    #[test]
+    fn lists_parameters_once_in_order() {
+        let shortcut = shortcut("https://example.com/{repo}/search?q={query}", Some("{repo:main} {extra:1} {query:x}"));
+        let parameters = parameters(&shortcut);
+        let listed: Vec<(&str, Option<&str>)> = parameters.iter()
+            .map(|p| (p.name.as_str(), p.default_value.as_deref()))
+            .collect();
+        assert_eq!(listed, vec![("repo", Some("main")), ("query", Some("x")), ("extra", Some("1"))]);
+        assert!(has_parameters(&shortcut));
+        assert!(!has_parameters(&self::shortcut("::{645FF040-5081-101B-9F08-08002B30309D}", Some("{{x}}"))));
+    }
+
+    #[test]
+    fn percent_encodes_values_in_urls_only() {
+        let url = shortcut("https://example.com/search?q={query}", None);
+        let resolved = resolve(&url, &values(&[("query", "a b&c/d")])).unwrap();
+        assert_eq!(resolved.target, "https://example.com/search?q=a%20b%26c%2Fd");
+
+        let program = shortcut("/usr/bin/{tool}", Some("--name {name} '{path}'"));
+        let resolved = resolve(&program, &values(&[("tool", "grep"), ("name", "a b"), ("path", "x y")])).unwrap();
+        assert_eq!(resolved.target, "/usr/bin/grep");
+        assert_eq!(resolved.arguments, vec!["--name", "a b", "x y"]);
+    }
+
+    #[test]
+    fn uses_defaults_and_reports_missing_parameters() {
+        let shortcut = shortcut("https://example.com/{repo:main}/{ticket}", None);
+
+        let missing = resolve(&shortcut, &values(&[("ticket", "  ")])).unwrap_err();
+        assert_eq!(missing.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(), vec!["ticket"]);
+
+        let resolved = resolve(&shortcut, &values(&[("repo", ""), ("ticket", "42")])).unwrap();
+        assert_eq!(resolved.target, "https://example.com/main/42");
+        assert!(resolved.arguments.is_empty());
+    }
+}
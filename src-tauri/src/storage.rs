@@ -1,7 +1,8 @@
 use crate::models::*;
 use serde_json;
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tauri::{AppHandle, Manager};
 use chrono::Utc;
 
@@ -102,18 +103,29 @@ impl DataManager {
     
     pub fn get_data(&mut self) -> Result<&AppData, String> {
         if self.cached_data.is_none() {
-            self.cached_data = Some(self.storage.load_data()?);
+            self.cached_data = Some(self.load_and_migrate()?);
         }
         Ok(self.cached_data.as_ref().unwrap())
     }
     
     pub fn get_data_mut(&mut self) -> Result<&mut AppData, String> {
         if self.cached_data.is_none() {
-            self.cached_data = Some(self.storage.load_data()?);
+            self.cached_data = Some(self.load_and_migrate()?);
         }
         Ok(self.cached_data.as_mut().unwrap())
     }
     
+    // 加载数据并升级旧版本字段
+    fn load_and_migrate(&self) -> Result<AppData, String> {
+        let mut data = self.storage.load_data()?;
+        
+        if crate::frecency::migrate(&mut data) {
+            self.storage.save_data(&data)?;
+        }
+        
+        Ok(data)
+    }
+    
     pub fn save_data(&mut self) -> Result<(), String> {
         if let Some(ref data) = self.cached_data {
             self.storage.save_data(data)?;
@@ -122,7 +134,8 @@ impl DataManager {
     }
     
     pub fn reload_data(&mut self) -> Result<(), String> {
-        self.cached_data = Some(self.storage.load_data()?);
+        let data = self.load_and_migrate()?;
+        self.cached_data = Some(data);
         Ok(())
     }
     
@@ -144,6 +157,18 @@ impl DataManager {
             shortcut.sort_order = sort_order;
         }
         
+        if let Some(aliases) = request.aliases {
+            let aliases = normalize_terms(aliases);
+            check_alias_conflicts(&data.shortcuts, &aliases, None)?;
+            shortcut.aliases = aliases;
+        }
+        
+        if let Some(keywords) = request.keywords {
+            shortcut.keywords = normalize_terms(keywords);
+        }
+        
+        shortcut.arguments = request.arguments.filter(|a| !a.trim().is_empty());
+        
         data.shortcuts.push(shortcut.clone());
         self.save_data()?;
         
@@ -153,6 +178,16 @@ impl DataManager {
     pub fn update_shortcut(&mut self, id: &str, request: UpdateShortcutRequest) -> Result<Shortcut, String> {
         let data = self.get_data_mut()?;
         
+        // 别名需要先与其他快捷方式比较，再取可变引用
+        let aliases = match request.aliases {
+            Some(aliases) => {
+                let aliases = normalize_terms(aliases);
+                check_alias_conflicts(&data.shortcuts, &aliases, Some(id))?;
+                Some(aliases)
+            }
+            None => None,
+        };
+        
         let shortcut = data.shortcuts.iter_mut()
             .find(|s| s.id == id)
             .ok_or("Shortcut not found")?;
@@ -162,6 +197,14 @@ impl DataManager {
         }
         
         if let Some(file_path) = request.file_path {
+            // 修改目标后立即更新状态，不等下一轮后台检查
+            shortcut.file_exists = crate::utils::is_url(&file_path) || std::path::Path::new(&file_path).exists();
+            if shortcut.file_exists {
+                shortcut.missing_since = None;
+            }
+            if shortcut.file_path != file_path {
+                shortcut.target_fingerprint = None;
+            }
             shortcut.file_path = file_path;
         }
         
@@ -181,11 +224,46 @@ impl DataManager {
             shortcut.is_active = is_active;
         }
         
+        if let Some(aliases) = aliases {
+            shortcut.aliases = aliases;
+        }
+        
+        if let Some(keywords) = request.keywords {
+            shortcut.keywords = normalize_terms(keywords);
+        }
+        
+        // 传入空字符串表示清除启动参数
+        if let Some(arguments) = request.arguments {
+            shortcut.arguments = Some(arguments).filter(|a| !a.trim().is_empty());
+        }
+        
+        shortcut.updated_at = Utc::now();
+        
+        let updated_shortcut = shortcut.clone();
+        self.save_data()?;
+        
+        // 替换图标后旧的自定义图标可能不再被引用
+        let _ = self.collect_orphaned_icons();
+        
+        Ok(updated_shortcut)
+    }
+    
+    // 设置或清除快捷方式的图标，None 表示恢复为从目标提取
+    pub fn set_shortcut_icon(&mut self, id: &str, icon_path: Option<String>) -> Result<Shortcut, String> {
+        let data = self.get_data_mut()?;
+        
+        let shortcut = data.shortcuts.iter_mut()
+            .find(|s| s.id == id)
+            .ok_or("Shortcut not found")?;
+        
+        shortcut.icon_path = icon_path;
         shortcut.updated_at = Utc::now();
         
         let updated_shortcut = shortcut.clone();
         self.save_data()?;
         
+        let _ = self.collect_orphaned_icons();
+        
         Ok(updated_shortcut)
     }
     
@@ -199,9 +277,19 @@ impl DataManager {
         data.shortcuts.remove(index);
         self.save_data()?;
         
+        let _ = self.collect_orphaned_icons();
+        
         Ok(())
     }
     
+    // 删除没有快捷方式引用的自定义图标文件
+    pub fn collect_orphaned_icons(&mut self) -> Result<usize, String> {
+        let data = self.get_data()?;
+        crate::custom_icon::GLOBAL_CUSTOM_ICONS.collect_garbage(
+            data.shortcuts.iter().filter_map(|s| s.icon_path.as_deref())
+        )
+    }
+    
     pub fn increment_usage(&mut self, id: &str) -> Result<(), String> {
         let data = self.get_data_mut()?;
         
@@ -209,15 +297,111 @@ impl DataManager {
             .find(|s| s.id == id)
             .ok_or("Shortcut not found")?;
         
+        let now = Utc::now();
         shortcut.usage_count += 1;
-        shortcut.last_used = Some(Utc::now());
-        shortcut.updated_at = Utc::now();
+        shortcut.last_used = Some(now);
+        crate::frecency::record_launch(shortcut, now);
+        shortcut.updated_at = now;
         
         self.save_data()?;
         
         Ok(())
     }
     
+    // 写入后台检查的结果（快捷方式 ID, 目标是否存在），返回状态发生变化的快捷方式；没有变化时不写磁盘
+    pub fn update_target_status(&mut self, results: &[(String, bool)]) -> Result<Vec<Shortcut>, String> {
+        let data = self.get_data_mut()?;
+        let now = Utc::now();
+        
+        let mut changed = Vec::new();
+        for (id, exists) in results {
+            let Some(shortcut) = data.shortcuts.iter_mut().find(|s| &s.id == id) else { continue };
+            if shortcut.file_exists == *exists {
+                continue;
+            }
+            
+            shortcut.file_exists = *exists;
+            shortcut.missing_since = if *exists { None } else { Some(now) };
+            changed.push(shortcut.clone());
+        }
+        
+        if !changed.is_empty() {
+            self.save_data()?;
+        }
+        
+        Ok(changed)
+    }
+    
+    // 记录后台检查时计算的目标内容指纹
+    pub fn record_target_fingerprints(&mut self, fingerprints: &[(String, String)]) -> Result<(), String> {
+        let data = self.get_data_mut()?;
+        
+        let mut changed = false;
+        for (id, fingerprint) in fingerprints {
+            let Some(shortcut) = data.shortcuts.iter_mut().find(|s| &s.id == id) else { continue };
+            if shortcut.target_fingerprint.as_ref() != Some(fingerprint) {
+                shortcut.target_fingerprint = Some(fingerprint.clone());
+                changed = true;
+            }
+        }
+        
+        if changed {
+            self.save_data()?;
+        }
+        
+        Ok(())
+    }
+    
+    // 目标被移动后更新路径，返回 (原路径, 更新后的快捷方式)
+    pub fn update_target_paths(&mut self, updates: &[(String, String)]) -> Result<Vec<(String, Shortcut)>, String> {
+        let data = self.get_data_mut()?;
+        let now = Utc::now();
+        
+        let mut updated = Vec::new();
+        for (id, new_path) in updates {
+            let Some(shortcut) = data.shortcuts.iter_mut().find(|s| &s.id == id) else { continue };
+            if &shortcut.file_path == new_path {
+                continue;
+            }
+            
+            let old_path = std::mem::replace(&mut shortcut.file_path, new_path.clone());
+            shortcut.file_exists = Path::new(new_path).exists();
+            shortcut.missing_since = if shortcut.file_exists { None } else { shortcut.missing_since.or(Some(now)) };
+            shortcut.target_fingerprint = None;
+            shortcut.updated_at = now;
+            updated.push((old_path, shortcut.clone()));
+        }
+        
+        if !updated.is_empty() {
+            self.save_data()?;
+        }
+        
+        Ok(updated)
+    }
+    
+    // 查找被多个快捷方式重复使用的别名（例如手动编辑数据文件导致）
+    pub fn find_alias_conflicts(&mut self) -> Result<Vec<AliasConflict>, String> {
+        let data = self.get_data()?;
+        
+        let mut owners: HashMap<String, Vec<String>> = HashMap::new();
+        for shortcut in data.shortcuts.iter() {
+            for alias in shortcut.aliases.iter() {
+                let ids = owners.entry(alias.to_lowercase()).or_default();
+                if !ids.contains(&shortcut.id) {
+                    ids.push(shortcut.id.clone());
+                }
+            }
+        }
+        
+        let mut conflicts: Vec<AliasConflict> = owners.into_iter()
+            .filter(|(_, ids)| ids.len() > 1)
+            .map(|(alias, shortcut_ids)| AliasConflict { alias, shortcut_ids })
+            .collect();
+        conflicts.sort_by(|a, b| a.alias.cmp(&b.alias));
+        
+        Ok(conflicts)
+    }
+    
     // 分类操作
     pub fn add_category(&mut self, request: CreateCategoryRequest) -> Result<Category, String> {
         let data = self.get_data_mut()?;
@@ -301,4 +485,30 @@ impl DataManager {
         
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+// 规范化别名和关键词：去除首尾空白、统一小写、去掉空项和重复项
+fn normalize_terms(terms: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for term in terms {
+        let term = term.trim().to_lowercase();
+        if !term.is_empty() && !normalized.contains(&term) {
+            normalized.push(term);
+        }
+    }
+    normalized
+}
+
+// 检查别名是否已被其他快捷方式使用
+fn check_alias_conflicts(shortcuts: &[Shortcut], aliases: &[String], exclude_id: Option<&str>) -> Result<(), String> {
+    for alias in aliases {
+        let owner = shortcuts.iter()
+            .filter(|s| Some(s.id.as_str()) != exclude_id)
+            .find(|s| s.aliases.iter().any(|a| a.to_lowercase() == *alias));
+        
+        if let Some(owner) = owner {
+            return Err(format!("Alias '{}' is already used by shortcut '{}'", alias, owner.name));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,429 @@
+use crate::commands::DataManagerState;
+use crate::models::{PathSuggestion, Shortcut, ShortcutPathEvent};
+use crate::utils::{real_extension, version_free_stem};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use notify::event::{ModifyKind, RenameMode};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager};
+use uuid::Uuid;
+
+// 用系统的文件通知（Linux 上为 inotify）监听快捷方式目标所在的目录及其上两级目录：
+//   目录被重命名或移动、目标被移到其他目录但文件名不变时，直接更新 file_path
+//   目标本身被改名、被移出后在别处出现、或被同目录下的新版本替换时，生成修复建议等待确认
+// 同样的通知用于让图标缓存失效，被监听的目标在缓存命中时不再检查文件元数据
+// 事件：
+//   shortcut-path-updated    file_path 已自动更新
+//   shortcut-path-suggested  新的修复建议
+pub const PATH_UPDATED_EVENT: &str = "shortcut-path-updated";
+pub const PATH_SUGGESTED_EVENT: &str = "shortcut-path-suggested";
+
+// 监听目标的父目录以及更上层的目录数，用于发现项目目录改名
+const WATCH_DEPTH: usize = 3;
+// 目标消失后，多长时间内出现的文件可以作为它的修复建议（秒）
+const VANISHED_WINDOW: i64 = 30;
+// 状态中保留的最近错误条数
+const MAX_ERRORS: usize = 20;
+
+#[derive(Debug, Clone)]
+struct VanishedTarget {
+    shortcut_id: String,
+    path: PathBuf,
+    at: DateTime<Utc>,
+}
+
+// 监听状态
+#[derive(Debug, Clone, Serialize)]
+pub struct TargetWatcherStatus {
+    pub watching: bool,
+    pub watched_directories: usize,
+    pub pending_suggestions: usize,
+    pub errors: Vec<String>,  // 最近的错误（通知出错、自动更新路径失败等）
+}
+
+struct WatcherState {
+    app_handle: Option<AppHandle>,
+    watcher: Option<RecommendedWatcher>,
+    watched: HashSet<PathBuf>,
+    target_dirs: HashSet<PathBuf>,  // 目标的直接父目录
+    targets: HashSet<PathBuf>,  // 全部本地目标，用于在读取快捷方式前过滤无关的通知
+    vanished: Vec<VanishedTarget>,
+    suggestions: Vec<PathSuggestion>,
+    errors: Vec<String>,
+}
+
+#[derive(Clone)]
+pub struct TargetWatcher {
+    state: Arc<Mutex<WatcherState>>,
+}
+
+// 本地目标的路径，网址不参与监听
+fn local_target(shortcut: &Shortcut) -> Option<PathBuf> {
+    (!crate::utils::is_url(&shortcut.file_path)).then(|| PathBuf::from(&shortcut.file_path))
+}
+
+impl TargetWatcher {
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(WatcherState {
+                app_handle: None,
+                watcher: None,
+                watched: HashSet::new(),
+                target_dirs: HashSet::new(),
+                targets: HashSet::new(),
+                vanished: Vec::new(),
+                suggestions: Vec::new(),
+                errors: Vec::new(),
+            })),
+        }
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, WatcherState>, String> {
+        self.state.lock().map_err(|e| format!("Failed to lock target watcher: {}", e))
+    }
+
+    // 创建监听器并在后台处理通知；之后由 sync_targets 维护监听的目录
+    pub fn start(&self, app_handle: AppHandle, shortcuts: &[Shortcut]) -> Result<(), String> {
+        {
+            let mut state = self.lock()?;
+            if state.watcher.is_some() {
+                drop(state);
+                self.sync_targets(shortcuts);
+                return Ok(());
+            }
+
+            let (sender, receiver) = mpsc::channel::<notify::Result<notify::Event>>();
+            let watcher = match notify::recommended_watcher(move |event| {
+                let _ = sender.send(event);
+            }) {
+                Ok(watcher) => watcher,
+                // 创建失败记录在状态中，调用方可以忽略错误继续启动
+                Err(e) => {
+                    drop(state);
+                    let error = format!("Failed to create target watcher: {}", e);
+                    self.record_error(error.clone());
+                    return Err(error);
+                }
+            };
+
+            state.watcher = Some(watcher);
+            state.app_handle = Some(app_handle);
+
+            let target_watcher = self.clone();
+            std::thread::spawn(move || {
+                for event in receiver {
+                    match event {
+                        Ok(event) => target_watcher.handle_event(&event),
+                        // 出错时可能漏掉了变化，立即检查全部目标
+                        Err(e) => {
+                            target_watcher.record_error(format!("Target watcher error: {}", e));
+                            let _ = crate::health_monitor::GLOBAL_HEALTH_MONITOR.check_now();
+                        }
+                    }
+                }
+            });
+        }
+
+        self.sync_targets(shortcuts);
+        Ok(())
+    }
+
+    // 按当前的快捷方式调整监听的目录，只增删有变化的部分
+    pub fn sync_targets(&self, shortcuts: &[Shortcut]) {
+        let Ok(mut state) = self.lock() else { return };
+        let state = &mut *state;
+        state.targets = shortcuts.iter().filter_map(local_target).collect();
+        let Some(watcher) = state.watcher.as_mut() else { return };
+
+        let mut wanted = HashSet::new();
+        let mut target_dirs = HashSet::new();
+        for target in state.targets.iter() {
+            let ancestors: Vec<&Path> = target.ancestors()
+                .skip(1)
+                .filter(|dir| dir.parent().is_some() && !dir.as_os_str().is_empty())
+                .take(WATCH_DEPTH)
+                .collect();
+            if let Some(parent) = ancestors.first() {
+                target_dirs.insert(parent.to_path_buf());
+            }
+            wanted.extend(ancestors.into_iter().map(Path::to_path_buf));
+        }
+
+        for dir in state.watched.difference(&wanted) {
+            let _ = watcher.unwatch(dir);
+        }
+        let mut watched = HashSet::new();
+        for dir in wanted {
+            if state.watched.contains(&dir) {
+                watched.insert(dir);
+            } else if dir.is_dir() && watcher.watch(&dir, RecursiveMode::NonRecursive).is_ok() {
+                watched.insert(dir);
+            }
+        }
+
+        state.watched = watched;
+        // 只有父目录确实在监听时，图标缓存才能依赖通知
+        state.target_dirs = target_dirs.into_iter()
+            .filter(|dir| state.watched.contains(dir))
+            .collect();
+    }
+
+    // 路径的变化是否能通过通知得知；是则图标缓存不必检查文件元数据
+    pub fn covers(&self, file_path: &str) -> bool {
+        let Some(parent) = Path::new(file_path).parent() else { return false };
+        self.lock().map(|s| s.target_dirs.contains(parent)).unwrap_or(false)
+    }
+
+    pub fn status(&self) -> Result<TargetWatcherStatus, String> {
+        let state = self.lock()?;
+        Ok(TargetWatcherStatus {
+            watching: state.watcher.is_some(),
+            watched_directories: state.watched.len(),
+            pending_suggestions: state.suggestions.len(),
+            errors: state.errors.clone(),
+        })
+    }
+
+    fn record_error(&self, error: String) {
+        if let Ok(mut state) = self.lock() {
+            if state.errors.len() >= MAX_ERRORS {
+                state.errors.remove(0);
+            }
+            state.errors.push(error);
+        }
+    }
+
+    pub fn suggestions(&self) -> Result<Vec<PathSuggestion>, String> {
+        Ok(self.lock()?.suggestions.clone())
+    }
+
+    // 取出一条建议（应用或忽略后不再保留）
+    pub fn take_suggestion(&self, id: &str) -> Result<Option<PathSuggestion>, String> {
+        let mut state = self.lock()?;
+        let index = state.suggestions.iter().position(|s| s.id == id);
+        Ok(index.map(|index| state.suggestions.remove(index)))
+    }
+
+    fn handle_event(&self, event: &notify::Event) {
+        // 通知队列溢出时无法知道丢了哪些变化：清空内存中的图标并立即检查全部目标
+        if event.need_rescan() {
+            crate::icon_cache::GLOBAL_ICON_CACHE.clear_memory();
+            let _ = crate::health_monitor::GLOBAL_HEALTH_MONITOR.check_now();
+            return;
+        }
+
+        if matches!(event.kind, EventKind::Access(_)) {
+            return;
+        }
+        for path in event.paths.iter() {
+            crate::icon_cache::GLOBAL_ICON_CACHE.invalidate(path);
+        }
+
+        match (&event.kind, event.paths.as_slice()) {
+            (EventKind::Modify(ModifyKind::Name(RenameMode::Both)), [from, to]) => self.handle_move(from, to),
+            (EventKind::Modify(ModifyKind::Name(RenameMode::From)), paths) | (EventKind::Remove(_), paths) => {
+                paths.iter().for_each(|path| self.handle_vanished(path));
+            }
+            (EventKind::Modify(ModifyKind::Name(RenameMode::To)), paths) | (EventKind::Create(_), paths) => {
+                paths.iter().for_each(|path| self.handle_appeared(path));
+            }
+            // 有的平台只报告“名称变化”而不区分来去，按路径当前是否存在判断
+            (EventKind::Modify(ModifyKind::Name(_)), paths) => {
+                for path in paths {
+                    if path.exists() {
+                        self.handle_appeared(path);
+                    } else {
+                        self.handle_vanished(path);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // 路径本身或其下是否有快捷方式的目标；否则不必读取快捷方式数据
+    fn affects_target(&self, path: &Path) -> bool {
+        self.lock().map(|s| s.targets.iter().any(|t| t.starts_with(path))).unwrap_or(false)
+    }
+
+    fn app_handle(&self) -> Option<AppHandle> {
+        self.lock().ok()?.app_handle.clone()
+    }
+
+    fn shortcuts(&self, app_handle: &AppHandle) -> Vec<Shortcut> {
+        let data_state = app_handle.state::<DataManagerState>();
+        let Ok(mut manager_guard) = data_state.lock() else { return Vec::new() };
+        manager_guard.as_mut()
+            .and_then(|manager| manager.get_data().ok())
+            .map(|data| data.shortcuts.clone())
+            .unwrap_or_default()
+    }
+
+    fn handle_move(&self, from: &Path, to: &Path) {
+        if !self.affects_target(from) {
+            return;
+        }
+        let Some(app_handle) = self.app_handle() else { return };
+
+        let mut updates = Vec::new();
+        let mut suggestions = Vec::new();
+        for shortcut in self.shortcuts(&app_handle) {
+            let Some(target) = local_target(&shortcut) else { continue };
+            let Ok(remainder) = target.strip_prefix(from) else { continue };
+
+            if !remainder.as_os_str().is_empty() {
+                // 上层目录改名或移动，目标在新位置下的相对路径不变
+                let new_path = to.join(remainder);
+                if new_path.exists() {
+                    updates.push((shortcut.id, new_path.to_string_lossy().to_string()));
+                }
+            } else if from.file_name() == to.file_name() {
+                updates.push((shortcut.id, to.to_string_lossy().to_string()));
+            } else {
+                suggestions.push(self.new_suggestion(&shortcut, to, "renamed"));
+            }
+        }
+
+        self.apply_updates(&app_handle, updates);
+        self.add_suggestions(&app_handle, suggestions);
+    }
+
+    fn handle_vanished(&self, path: &Path) {
+        if !self.affects_target(path) {
+            return;
+        }
+        let Some(app_handle) = self.app_handle() else { return };
+
+        let vanished: Vec<VanishedTarget> = self.shortcuts(&app_handle).into_iter()
+            .filter_map(|shortcut| {
+                let target = local_target(&shortcut)?;
+                target.starts_with(path).then(|| VanishedTarget {
+                    shortcut_id: shortcut.id,
+                    path: target,
+                    at: Utc::now(),
+                })
+            })
+            .collect();
+        if vanished.is_empty() {
+            return;
+        }
+
+        if let Ok(mut state) = self.lock() {
+            state.vanished.retain(|v| !vanished.iter().any(|n| n.shortcut_id == v.shortcut_id));
+            state.vanished.extend(vanished);
+        }
+        // 立即更新 file_exists 并发送失效事件，不等下一轮定期检查
+        let _ = crate::health_monitor::GLOBAL_HEALTH_MONITOR.check_now();
+    }
+
+    // 新出现的文件与最近消失的目标文件名相同（被移到别处），或与其在同一目录且去掉版本号后同名（被新版本替换）
+    fn handle_appeared(&self, path: &Path) {
+        let Some(app_handle) = self.app_handle() else { return };
+
+        let matched: Vec<VanishedTarget> = {
+            let Ok(mut state) = self.lock() else { return };
+            let now = Utc::now();
+            state.vanished.retain(|v| (now - v.at).num_seconds() <= VANISHED_WINDOW);
+
+            let (matched, rest): (Vec<_>, Vec<_>) = state.vanished.drain(..).partition(|v| {
+                let same_name = v.path.file_name() == path.file_name();
+                let replaced = v.path.parent() == path.parent()
+                    && real_extension(&v.path) == real_extension(path)
+                    && version_free_stem(&v.path).is_some_and(|stem| version_free_stem(path) == Some(stem));
+                v.path != path && (same_name || replaced)
+            });
+            state.vanished = rest;
+            matched
+        };
+        if matched.is_empty() {
+            return;
+        }
+
+        let shortcuts = self.shortcuts(&app_handle);
+        let suggestions = matched.iter()
+            .filter_map(|v| {
+                let shortcut = shortcuts.iter().find(|s| s.id == v.shortcut_id)?;
+                let reason = if v.path.file_name() == path.file_name() { "moved" } else { "replaced" };
+                Some(self.new_suggestion(shortcut, path, reason))
+            })
+            .collect();
+        self.add_suggestions(&app_handle, suggestions);
+    }
+
+    fn new_suggestion(&self, shortcut: &Shortcut, new_path: &Path, reason: &str) -> PathSuggestion {
+        PathSuggestion {
+            id: Uuid::new_v4().to_string(),
+            shortcut_id: shortcut.id.clone(),
+            shortcut_name: shortcut.name.clone(),
+            old_path: shortcut.file_path.clone(),
+            new_path: new_path.to_string_lossy().to_string(),
+            reason: reason.to_string(),
+            created_at: Utc::now(),
+        }
+    }
+
+    // 同一快捷方式只保留最新的一条建议
+    fn add_suggestions(&self, app_handle: &AppHandle, suggestions: Vec<PathSuggestion>) {
+        if suggestions.is_empty() {
+            return;
+        }
+        if let Ok(mut state) = self.lock() {
+            state.suggestions.retain(|s| !suggestions.iter().any(|n| n.shortcut_id == s.shortcut_id));
+            state.suggestions.extend(suggestions.iter().cloned());
+        }
+        for suggestion in suggestions {
+            let _ = app_handle.emit(PATH_SUGGESTED_EVENT, suggestion);
+        }
+    }
+
+    fn apply_updates(&self, app_handle: &AppHandle, updates: Vec<(String, String)>) {
+        if updates.is_empty() {
+            return;
+        }
+
+        let data_state = app_handle.state::<DataManagerState>();
+        let result = {
+            let Ok(mut manager_guard) = data_state.lock() else { return };
+            let Some(manager) = manager_guard.as_mut() else { return };
+            manager.update_target_paths(&updates).and_then(|updated| {
+                Ok((updated, manager.get_data()?.shortcuts.clone()))
+            })
+        };
+
+        let (updated, shortcuts) = match result {
+            Ok(result) => result,
+            // 没能自动更新时改为修复建议，由用户确认后应用
+            Err(e) => {
+                self.record_error(format!("Failed to update moved shortcut targets: {}", e));
+                let shortcuts = self.shortcuts(app_handle);
+                let suggestions = updates.iter()
+                    .filter_map(|(id, new_path)| {
+                        let shortcut = shortcuts.iter().find(|s| &s.id == id)?;
+                        Some(self.new_suggestion(shortcut, Path::new(new_path), "moved"))
+                    })
+                    .collect();
+                self.add_suggestions(app_handle, suggestions);
+                return;
+            }
+        };
+
+        // 数据锁释放后再按新的路径调整监听
+        self.sync_targets(&shortcuts);
+
+        for (old_path, shortcut) in updated {
+            let _ = app_handle.emit(PATH_UPDATED_EVENT, ShortcutPathEvent {
+                shortcut_id: shortcut.id,
+                old_path,
+                new_path: shortcut.file_path,
+            });
+        }
+    }
+}
+
+lazy_static::lazy_static! {
+    pub static ref GLOBAL_TARGET_WATCHER: TargetWatcher = TargetWatcher::new();
+}
@@ -0,0 +1,305 @@
+use image::{DynamicImage, ImageDecoder, ImageReader};
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+// 媒体文件的缩略图：
+//   图片  用 image 解码，按 EXIF 方向旋转
+//   视频  ffmpeg 截取一帧，macOS 上也可以用 qlmanage
+//   PDF   pdftoppm 渲染第一页，macOS 上也可以用 qlmanage
+// 外部工具不存在、超时或失败时返回错误，由调用方退回普通图标
+
+// 超过该大小的图片不生成缩略图，避免一次解码占用过多内存
+const MAX_IMAGE_BYTES: u64 = 64 * 1024 * 1024;
+const HELPER_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaKind {
+    Image,
+    Video,
+    Pdf,
+}
+
+// .ico 和 .icns 按多尺寸图标处理，不在这里
+pub fn media_kind(extension: &str) -> Option<MediaKind> {
+    match extension {
+        "png" | "jpg" | "jpeg" | "jpe" | "jfif" | "gif" | "bmp" | "webp" | "tif" | "tiff"
+        | "tga" | "qoi" | "pnm" | "pbm" | "pgm" | "ppm" | "hdr" | "exr" => Some(MediaKind::Image),
+        #[cfg(target_os = "linux")]
+        "svg" | "svgz" => Some(MediaKind::Image),
+        // .ts 更常见的是 TypeScript 源文件，不当作视频
+        "mp4" | "m4v" | "mov" | "mkv" | "avi" | "webm" | "wmv" | "flv" | "mpg" | "mpeg" | "3gp" => {
+            Some(MediaKind::Video)
+        }
+        "pdf" => Some(MediaKind::Pdf),
+        _ => None,
+    }
+}
+
+// 生成缩略图，返回缩放到 pixels 的 PNG 数据
+pub fn generate(path: &Path, kind: MediaKind, pixels: u32) -> Result<Vec<u8>, String> {
+    let image = match kind {
+        MediaKind::Image => {
+            // SVG 直接按目标尺寸栅格化
+            #[cfg(target_os = "linux")]
+            if is_svg(path) {
+                return crate::icon_theme::render_icon(path, pixels);
+            }
+            decode_image(path)?
+        }
+        MediaKind::Video => video_frame(path, pixels)?,
+        MediaKind::Pdf => pdf_page(path, pixels)?,
+    };
+    crate::icon_extractor::encode_png(image, pixels)
+}
+
+#[cfg(target_os = "linux")]
+fn is_svg(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("svg") || e.eq_ignore_ascii_case("svgz"))
+}
+
+fn decode_image(path: &Path) -> Result<DynamicImage, String> {
+    let size = std::fs::metadata(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?
+        .len();
+    if size > MAX_IMAGE_BYTES {
+        return Err("Image is too large for a thumbnail".to_string());
+    }
+
+    let mut decoder = ImageReader::open(path)
+        .map_err(|e| format!("Failed to open image: {}", e))?
+        .with_guessed_format()
+        .map_err(|e| format!("Failed to read image: {}", e))?
+        .into_decoder()
+        .map_err(|e| format!("Unsupported image: {}", e))?;
+    let orientation = decoder.orientation()
+        .unwrap_or(image::metadata::Orientation::NoTransforms);
+
+    let mut image = DynamicImage::from_decoder(decoder)
+        .map_err(|e| format!("Failed to decode image: {}", e))?;
+    image.apply_orientation(orientation);
+    Ok(image)
+}
+
+fn video_frame(path: &Path, pixels: u32) -> Result<DynamicImage, String> {
+    if let Some(ffmpeg) = find_helper("ffmpeg") {
+        let scale = format!("scale={}:{}:force_original_aspect_ratio=decrease", pixels, pixels);
+        // 先取第 1 秒的画面跳过片头黑屏，视频太短时取第一帧
+        for seek in [Some("1"), None] {
+            let mut args: Vec<OsString> = vec!["-v".into(), "error".into()];
+            if let Some(seek) = seek {
+                args.extend(["-ss".into(), seek.into()]);
+            }
+            args.extend([
+                "-i".into(), path.as_os_str().to_os_string(),
+                "-frames:v".into(), "1".into(),
+                "-vf".into(), scale.clone().into(),
+                "-f".into(), "image2pipe".into(),
+                "-vcodec".into(), "png".into(),
+                "-".into(),
+            ]);
+            if let Some(data) = run_helper(&ffmpeg, &args) {
+                return load_png(&data);
+            }
+        }
+    }
+
+    quick_look(path, pixels)
+}
+
+fn pdf_page(path: &Path, pixels: u32) -> Result<DynamicImage, String> {
+    // 不指定输出文件名时 pdftoppm 把图片写到标准输出
+    if let Some(pdftoppm) = find_helper("pdftoppm") {
+        let args: Vec<OsString> = vec![
+            "-png".into(),
+            "-singlefile".into(),
+            "-f".into(), "1".into(),
+            "-l".into(), "1".into(),
+            "-scale-to".into(), pixels.to_string().into(),
+            path.as_os_str().to_os_string(),
+        ];
+        if let Some(data) = run_helper(&pdftoppm, &args) {
+            return load_png(&data);
+        }
+    }
+
+    quick_look(path, pixels)
+}
+
+// macOS 的 Quick Look 能为大多数文档和视频生成缩略图，结果写到临时目录
+#[cfg(target_os = "macos")]
+fn quick_look(path: &Path, pixels: u32) -> Result<DynamicImage, String> {
+    let qlmanage = find_helper("qlmanage").ok_or("No thumbnail helper available")?;
+    let output_dir = std::env::temp_dir().join(format!("ql-thumb-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&output_dir)
+        .map_err(|e| format!("Failed to create thumbnail directory: {}", e))?;
+
+    let args: Vec<OsString> = vec![
+        "-t".into(),
+        "-s".into(), pixels.to_string().into(),
+        "-o".into(), output_dir.as_os_str().to_os_string(),
+        path.as_os_str().to_os_string(),
+    ];
+    let result = run_helper(&qlmanage, &args)
+        .and_then(|_| {
+            let file_name = path.file_name()?;
+            let mut thumbnail = file_name.to_os_string();
+            thumbnail.push(".png");
+            std::fs::read(output_dir.join(thumbnail)).ok()
+        })
+        .ok_or_else(|| "Quick Look did not produce a thumbnail".to_string())
+        .and_then(|data| load_png(&data));
+
+    let _ = std::fs::remove_dir_all(&output_dir);
+    result
+}
+
+#[cfg(not(target_os = "macos"))]
+fn quick_look(_path: &Path, _pixels: u32) -> Result<DynamicImage, String> {
+    Err("No thumbnail helper available".to_string())
+}
+
+fn load_png(data: &[u8]) -> Result<DynamicImage, String> {
+    image::load_from_memory_with_format(data, image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to decode thumbnail: {}", e))
+}
+
+// 在 PATH 中查找外部工具，结果在进程内缓存
+fn find_helper(name: &str) -> Option<PathBuf> {
+    lazy_static::lazy_static! {
+        static ref HELPERS: Mutex<HashMap<String, Option<PathBuf>>> = Mutex::new(HashMap::new());
+    }
+
+    let mut helpers = HELPERS.lock().ok()?;
+    helpers.entry(name.to_string())
+        .or_insert_with(|| {
+            let file_name = if cfg!(target_os = "windows") { format!("{}.exe", name) } else { name.to_string() };
+            let paths = std::env::var_os("PATH")?;
+            std::env::split_paths(&paths)
+                .map(|dir| dir.join(&file_name))
+                .find(|candidate| candidate.is_file())
+        })
+        .clone()
+}
+
+// 运行外部工具并读取标准输出；超时后结束进程，失败或没有输出时返回 None
+fn run_helper(program: &Path, args: &[OsString]) -> Option<Vec<u8>> {
+    let mut command = Command::new(program);
+    command.args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null());
+
+    // 不弹出控制台窗口
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        command.creation_flags(0x08000000);
+    }
+
+    let mut child = command.spawn().ok()?;
+    let mut stdout = child.stdout.take()?;
+    let reader = std::thread::spawn(move || {
+        let mut output = Vec::new();
+        stdout.read_to_end(&mut output).map(|_| output)
+    });
+
+    let deadline = Instant::now() + HELPER_TIMEOUT;
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => {
+                let output = reader.join().ok()?.ok()?;
+                return (status.success() && !output.is_empty()).then_some(output);
+            }
+            Ok(None) if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(50)),
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    // 左半红、右半蓝的 JPEG，SOI 之后插入只含方向标签的 EXIF 段
+    fn jpeg_with_orientation(orientation: u16) -> Vec<u8> {
+        let image = RgbImage::from_fn(32, 16, |x, _| if x < 16 { Rgb([255, 0, 0]) } else { Rgb([0, 0, 255]) });
+        let mut jpeg = Vec::new();
+        DynamicImage::ImageRgb8(image)
+            .write_to(&mut std::io::Cursor::new(&mut jpeg), image::ImageFormat::Jpeg)
+            .unwrap();
+
+        let mut exif = b"Exif\0\0MM\0\x2a\0\0\0\x08\0\x01\x01\x12\0\x03\0\0\0\x01".to_vec();
+        exif.extend_from_slice(&orientation.to_be_bytes());
+        exif.extend_from_slice(&[0, 0, 0, 0, 0, 0]);
+        let mut segment = vec![0xFF, 0xE1];
+        segment.extend_from_slice(&(exif.len() as u16 + 2).to_be_bytes());
+        segment.extend_from_slice(&exif);
+
+        jpeg.splice(2..2, segment);
+        jpeg
+    }
+
+    fn decode(data: &[u8]) -> DynamicImage {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("photo.jpg");
+        std::fs::write(&path, data).unwrap();
+        decode_image(&path).unwrap()
+    }
+
+    fn is_red(image: &RgbImage, x: u32, y: u32) -> bool {
+        let Rgb([r, _, b]) = *image.get_pixel(x, y);
+        r > 200 && b < 60
+    }
+
+    #[test]
+    fn keeps_images_without_rotation() {
+        let image = decode(&jpeg_with_orientation(1)).to_rgb8();
+        assert_eq!(image.dimensions(), (32, 16));
+        assert!(is_red(&image, 2, 8));
+        assert!(!is_red(&image, 29, 8));
+    }
+
+    #[test]
+    fn rotates_by_exif_orientation() {
+        // 6：顺时针旋转 90 度，原来的左侧到了上方
+        let image = decode(&jpeg_with_orientation(6)).to_rgb8();
+        assert_eq!(image.dimensions(), (16, 32));
+        assert!(is_red(&image, 8, 2));
+        assert!(!is_red(&image, 8, 29));
+
+        // 3：旋转 180 度，左右互换
+        let image = decode(&jpeg_with_orientation(3)).to_rgb8();
+        assert_eq!(image.dimensions(), (32, 16));
+        assert!(!is_red(&image, 2, 8));
+        assert!(is_red(&image, 29, 8));
+    }
+
+    #[test]
+    fn rejects_files_that_are_not_images() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("photo.jpg");
+        std::fs::write(&path, b"not an image").unwrap();
+        assert!(decode_image(&path).is_err());
+    }
+
+    #[test]
+    fn classifies_media_by_extension() {
+        assert_eq!(media_kind("jpg"), Some(MediaKind::Image));
+        assert_eq!(media_kind("mkv"), Some(MediaKind::Video));
+        assert_eq!(media_kind("pdf"), Some(MediaKind::Pdf));
+        assert_eq!(media_kind("ts"), None);
+        assert_eq!(media_kind("ico"), None);
+    }
+}
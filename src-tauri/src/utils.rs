@@ -49,6 +49,7 @@ pub fn get_file_info(file_path: &str) -> Result<FileInfo, String> {
         exists,
         is_file,
         is_directory,
+        display_name: crate::app_bundle::display_name(path),
         file_name,
         file_extension,
         file_size,
@@ -119,24 +120,71 @@ pub fn check_file_exists(file_path: &str) -> Result<bool, String> {
 
 // 使用新的图标提取模块
 pub fn extract_file_icon(file_path: &str, large_icon: bool) -> Result<IconResult, String> {
-    crate::icon_extractor::extract_file_icon(file_path, large_icon)
+    crate::icon_extractor::extract_file_icon(file_path, crate::models::IconSize::from_large(large_icon))
 }
 
-// icon_to_base64函数暂时使用占位实现
-fn icon_to_base64(_hicon: u32) -> Result<String, String> {
-    // 返回一个1x1透明PNG的base64编码作为占位符
-    Ok("data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNkYPhfDwAChwGA60e6kgAAAABJRU5ErkJggg==".to_string())
+// XDG 数据目录：XDG_DATA_HOME 在前，其后是 XDG_DATA_DIRS，按优先级排列
+#[cfg(target_os = "linux")]
+pub fn xdg_data_dirs() -> Vec<std::path::PathBuf> {
+    use std::path::PathBuf;
+    
+    let mut dirs = Vec::new();
+    
+    let data_home = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".local/share")));
+    if let Some(data_home) = data_home {
+        dirs.push(data_home);
+    }
+    
+    let data_dirs = std::env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+    dirs.extend(data_dirs.split(':').filter(|d| !d.is_empty()).map(PathBuf::from));
+    
+    dirs
 }
 
-#[cfg(not(target_os = "windows"))]
-pub fn extract_file_icon(_file_path: &str, _large_icon: bool) -> Result<IconResult, String> {
-    // 非Windows平台的占位实现
-    Ok(IconResult {
-        icon_data: "".to_string(),
-        icon_format: "png".to_string(),
-        from_cache: false,
-        file_hash: None,
-    })
+// 系统默认打开方式：Windows 用 start，macOS 用 open，Linux 上 .desktop 文件用 gio 启动对应的应用，其他用 xdg-open
+fn opener_command(file_path: &str) -> std::process::Command {
+    use std::process::Command;
+    
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]).raw_arg(escape_cmd_argument(file_path));
+        command
+    }
+    
+    #[cfg(target_os = "macos")]
+    {
+        let mut command = Command::new("open");
+        command.arg(file_path);
+        command
+    }
+    
+    #[cfg(target_os = "linux")]
+    {
+        let mut command = if is_desktop_entry(Path::new(file_path)) {
+            let mut command = Command::new("gio");
+            command.arg("launch");
+            command
+        } else {
+            Command::new("xdg-open")
+        };
+        command.arg(file_path);
+        command
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn is_desktop_entry(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("desktop"))
 }
 
 pub fn launch_file(file_path: &str) -> Result<(), String> {
@@ -146,67 +194,230 @@ pub fn launch_file(file_path: &str) -> Result<(), String> {
         return Err("File does not exist".to_string());
     }
     
+    match opener_command(file_path).spawn() {
+        Ok(_) => Ok(()),
+        Err(e) => Err(format!("Failed to launch file: {}", e)),
+    }
+}
+
+// 判断目标是否为网址（http://、file://、mailto: 等）
+pub fn is_url(path: &str) -> bool {
+    let lowered = path.to_lowercase();
+    lowered.starts_with("mailto:") || lowered.split_once("://").is_some_and(|(scheme, _)| {
+        !scheme.is_empty() && scheme.chars().all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.')
+    })
+}
+
+// 是否为可以直接运行的程序
+fn is_program(path: &Path) -> bool {
     #[cfg(target_os = "windows")]
     {
-        use std::process::Command;
-        
-        let result = Command::new("cmd")
-            .args(["/C", "start", "", file_path])
-            .spawn();
-        
-        match result {
-            Ok(_) => Ok(()),
-            Err(e) => Err(format!("Failed to launch file: {}", e)),
-        }
+        let extension = path.extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_lowercase())
+            .unwrap_or_default();
+        path.is_file() && matches!(extension.as_str(), "exe" | "com" | "bat" | "cmd")
     }
     
-    #[cfg(target_os = "macos")]
+    #[cfg(unix)]
     {
-        use std::process::Command;
-        
-        let result = Command::new("open")
-            .arg(file_path)
-            .spawn();
+        use std::os::unix::fs::PermissionsExt;
         
-        match result {
-            Ok(_) => Ok(()),
-            Err(e) => Err(format!("Failed to launch file: {}", e)),
+        #[cfg(target_os = "linux")]
+        if is_desktop_entry(path) {
+            return false;
+        }
+        fs::metadata(path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+    }
+}
+
+// 按 Windows 程序解析命令行的规则加引号：含空白或引号时整体加引号，引号和其前面的反斜杠转义
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn quote_windows_argument(argument: &str) -> String {
+    if !argument.is_empty() && !argument.chars().any(|c| c.is_whitespace() || c == '"') {
+        return argument.to_string();
+    }
+
+    let mut quoted = String::from('"');
+    let mut backslashes = 0;
+    for c in argument.chars() {
+        if c == '\\' {
+            backslashes += 1;
+            continue;
         }
+        // 引号前的反斜杠加倍，再转义引号本身；其他字符前的反斜杠原样保留
+        let count = if c == '"' { backslashes * 2 + 1 } else { backslashes };
+        quoted.push_str(&"\\".repeat(count));
+        quoted.push(c);
+        backslashes = 0;
     }
+    // 结尾的反斜杠后面紧跟闭合引号，同样需要加倍
+    quoted.push_str(&"\\".repeat(backslashes * 2));
+    quoted.push('"');
+    quoted
+}
+
+// 经过 cmd 传递的参数（需用 raw_arg 原样传给 cmd）：先加引号，再给 cmd 的全部特殊字符加 ^；
+// 引号也被转义，cmd 不会把任何部分当作引号内的文本，%变量% 和 !变量! 也不会被展开
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn escape_cmd_argument(argument: &str) -> String {
+    quote_windows_argument(argument).chars()
+        .flat_map(|c| match c {
+            '^' | '&' | '|' | '<' | '>' | '(' | ')' | '%' | '!' | '"' => vec!['^', c],
+            _ => vec![c],
+        })
+        .collect()
+}
+
+// 带参数启动；程序直接运行，参数逐项传递，不经过 shell；
+// 其他文件（.lnk、.app、.desktop 等）和 launch_file 一样交给系统的打开方式，再附加参数
+pub fn launch_with_arguments(file_path: &str, arguments: &[String]) -> Result<(), String> {
+    use std::process::Command;
     
-    #[cfg(target_os = "linux")]
-    {
-        use std::process::Command;
+    let path = Path::new(file_path);
+    
+    if !path.exists() {
+        return Err("File does not exist".to_string());
+    }
+    
+    let mut command = if is_program(path) {
+        let mut command = Command::new(file_path);
+        if let Some(parent) = path.parent() {
+            command.current_dir(parent);
+        }
+        command.args(arguments);
+        command
+    } else {
+        let mut command = opener_command(file_path);
         
-        let result = Command::new("xdg-open")
-            .arg(file_path)
-            .spawn();
+        #[cfg(target_os = "windows")]
+        {
+            use std::os::windows::process::CommandExt;
+            for argument in arguments {
+                command.raw_arg(escape_cmd_argument(argument));
+            }
+        }
+        
+        #[cfg(target_os = "macos")]
+        command.arg("--args").args(arguments);
         
-        match result {
-            Ok(_) => Ok(()),
-            Err(e) => Err(format!("Failed to launch file: {}", e)),
+        // gio launch 把参数作为文件传给应用，xdg-open 不支持参数
+        #[cfg(target_os = "linux")]
+        {
+            if !is_desktop_entry(path) {
+                return Err("Arguments can only be passed to programs and applications".to_string());
+            }
+            command.args(arguments);
         }
+        
+        command
+    };
+    
+    match command.spawn() {
+        Ok(_) => Ok(()),
+        Err(e) => Err(format!("Failed to launch file: {}", e)),
     }
 }
 
-pub fn get_file_hash(file_path: &str) -> Result<String, String> {
-    use std::collections::hash_map::DefaultHasher;
-    use std::hash::{Hash, Hasher};
+// 用系统默认浏览器打开网址
+pub fn open_url(url: &str) -> Result<(), String> {
+    use std::process::Command;
+    
+    #[cfg(target_os = "windows")]
+    let result = {
+        use std::os::windows::process::CommandExt;
+        Command::new("cmd")
+            .args(["/C", "start", ""])
+            .raw_arg(escape_cmd_argument(url))
+            .spawn()
+    };
     
-    let metadata = fs::metadata(file_path)
-        .map_err(|e| format!("Failed to get file metadata: {}", e))?;
+    #[cfg(target_os = "macos")]
+    let result = Command::new("open")
+        .arg(url)
+        .spawn();
     
-    let mut hasher = DefaultHasher::new();
-    file_path.hash(&mut hasher);
-    metadata.len().hash(&mut hasher);
+    #[cfg(target_os = "linux")]
+    let result = Command::new("xdg-open")
+        .arg(url)
+        .spawn();
     
-    if let Ok(modified) = metadata.modified() {
-        if let Ok(duration) = modified.duration_since(std::time::UNIX_EPOCH) {
-            duration.as_secs().hash(&mut hasher);
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(format!("Failed to open url: {}", e)),
+    }
+}
+
+// 按 RFC 3986 对 URL 组件做百分号编码，只保留非保留字符
+pub fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char);
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
         }
     }
-    
-    Ok(format!("{:x}", hasher.finish()))
+    encoded
+}
+
+// 文件的扩展名（小写）；最后一段全是数字时是版本号的一部分（tool-1.2.3），不算扩展名
+pub fn real_extension(path: &Path) -> Option<String> {
+    let extension = path.extension()?.to_str()?;
+    (!extension.bytes().all(|b| b.is_ascii_digit())).then(|| extension.to_lowercase())
+}
+
+// 去掉扩展名后的文件名
+fn name_without_extension(path: &Path) -> Option<&str> {
+    let name = path.file_name()?.to_str()?;
+    match real_extension(path) {
+        Some(_) => name.rsplit_once('.').map(|(stem, _)| stem),
+        None => Some(name),
+    }
+}
+
+// 把名称末尾的版本号分离出来：分隔符加数字（tool-1.2、tool_v3、tool 2），或直接相连的多段数字（python3.11）；
+// 直接相连的单段数字是名称的一部分（x264、mp3），不算版本号
+fn split_version_suffix(stem: &str) -> (&str, &str) {
+    let bytes = stem.as_bytes();
+    let digits_before = |end: usize| bytes[..end].iter().rev().take_while(|b| b.is_ascii_digit()).count();
+
+    let mut start = bytes.len() - digits_before(bytes.len());
+    if start == bytes.len() {
+        return (stem, "");
+    }
+    let mut dotted = false;
+    while start >= 2 && bytes[start - 1] == b'.' && digits_before(start - 1) > 0 {
+        start -= 1 + digits_before(start - 1);
+        dotted = true;
+    }
+
+    let is_separator = |b: u8| matches!(b, b'-' | b'_' | b' ');
+    if start >= 2 && matches!(bytes[start - 1], b'v' | b'V') && is_separator(bytes[start - 2]) {
+        start -= 2;
+    } else if start >= 1 && is_separator(bytes[start - 1]) {
+        start -= 1;
+    } else if !dotted {
+        return (stem, "");
+    }
+    (&stem[..start], &stem[start..])
+}
+
+// 文件名去掉扩展名和末尾版本号后的部分（小写），用于识别同一程序的不同版本（tool-1.2 与 tool-1.3）
+pub fn version_free_stem(path: &Path) -> Option<String> {
+    let (stem, _) = split_version_suffix(name_without_extension(path)?);
+    let stem = stem.trim().to_lowercase();
+    (!stem.is_empty()).then_some(stem)
+}
+
+// 文件名末尾的版本号，按数字逐段比较（1.10 大于 1.9）；没有版本号时为空
+pub fn version_numbers(path: &Path) -> Vec<u64> {
+    let Some(stem) = name_without_extension(path) else { return Vec::new() };
+    let (_, version) = split_version_suffix(stem);
+    version.split(|c: char| !c.is_ascii_digit())
+        .filter_map(|part| part.parse().ok())
+        .collect()
 }
 
 pub fn sanitize_filename(filename: &str) -> String {
@@ -224,12 +435,84 @@ pub fn get_default_icon_for_extension(extension: &str) -> &'static str {
         "exe" | "msi" => "⚙️",
         "txt" | "md" | "doc" | "docx" => "📄",
         "pdf" => "📕",
-        "jpg" | "jpeg" | "png" | "gif" | "bmp" => "🖼️",
+        "jpg" | "jpeg" | "png" | "gif" | "bmp" | "webp" | "tif" | "tiff" => "🖼️",
         "mp3" | "wav" | "flac" | "aac" => "🎵",
-        "mp4" | "avi" | "mkv" | "mov" => "🎬",
+        "mp4" | "avi" | "mkv" | "mov" | "webm" | "wmv" => "🎬",
         "zip" | "rar" | "7z" | "tar" => "📦",
         "html" | "htm" => "🌐",
         "js" | "ts" | "py" | "rs" | "cpp" | "c" => "💻",
         _ => "📁",
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_only_trailing_version_suffixes() {
+        let cases = [
+            ("tool-1.2.3", Some("tool")),
+            ("tool-1.2.3.exe", Some("tool")),
+            ("Tool_v2.AppImage", Some("tool")),
+            ("tool 2.exe", Some("tool")),
+            ("python3.11", Some("python")),
+            ("app-1.2.3", Some("app")),
+            ("x264.exe", Some("x264")),
+            ("x265.exe", Some("x265")),
+            ("7zip.exe", Some("7zip")),
+            ("zip.exe", Some("zip")),
+            ("dev1.2", Some("dev")),
+            ("tool.exe", Some("tool")),
+            ("1.2.3", None),
+        ];
+        for (name, expected) in cases {
+            assert_eq!(version_free_stem(Path::new(name)).as_deref(), expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn real_extension_ignores_numeric_suffixes() {
+        assert_eq!(real_extension(Path::new("tool-1.2.3")), None);
+        assert_eq!(real_extension(Path::new("tool-1.2.3.EXE")).as_deref(), Some("exe"));
+        assert_eq!(real_extension(Path::new("archive.7z")).as_deref(), Some("7z"));
+        assert_eq!(real_extension(Path::new("tool")), None);
+    }
+
+    #[test]
+    fn quotes_arguments_for_windows_programs() {
+        let cases = [
+            ("plain", "plain"),
+            ("", r#""""#),
+            ("two words", r#""two words""#),
+            (r#"say "hi""#, r#""say \"hi\"""#),
+            (r"C:\dir\", r"C:\dir\"),
+            (r"C:\my dir\", r#""C:\my dir\\""#),
+            (r#"a\"b"#, r#""a\\\"b""#),
+        ];
+        for (argument, expected) in cases {
+            assert_eq!(quote_windows_argument(argument), expected, "{}", argument);
+        }
+    }
+
+    #[test]
+    fn escapes_every_cmd_metacharacter() {
+        assert_eq!(escape_cmd_argument("a&b|c"), "a^&b^|c");
+        assert_eq!(escape_cmd_argument("<in>(x)"), "^<in^>^(x^)");
+        assert_eq!(escape_cmd_argument("%PATH%!x!^"), "^%PATH^%^!x^!^^");
+        assert_eq!(escape_cmd_argument("Tom & Jerry"), r#"^"Tom ^& Jerry^""#);
+        assert_eq!(escape_cmd_argument(r#"say "a&b""#), r#"^"say \^"a^&b\^"^""#);
+        assert_eq!(
+            escape_cmd_argument("https://example.com/?q=a%20b&x=1"),
+            "https://example.com/?q=a^%20b^&x=1",
+        );
+    }
+
+    #[test]
+    fn reads_version_numbers_from_the_suffix() {
+        assert_eq!(version_numbers(Path::new("tool-1.10.exe")), vec![1, 10]);
+        assert_eq!(version_numbers(Path::new("x264-0.9")), vec![0, 9]);
+        assert_eq!(version_numbers(Path::new("tool-v3")), vec![3]);
+        assert_eq!(version_numbers(Path::new("x264.exe")), Vec::<u64>::new());
+        assert!(version_numbers(Path::new("tool-1.10")) > version_numbers(Path::new("tool-1.9")));
+    }
+}
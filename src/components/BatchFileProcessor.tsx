@@ -56,7 +56,7 @@ export const BatchFileProcessor: React.FC<BatchFileProcessorProps> = ({
       const newFile: FileItem = {
         id: Date.now().toString() + Math.random(),
         path: path.trim(),
-        name: pathInfo.file_name,
+        name: pathInfo.display_name ?? pathInfo.file_name,
         isDirectory: pathInfo.is_directory,
         status: 'pending'
       };
@@ -182,8 +182,8 @@ export const useDragAndDrop = ({ onFileAdded, categoryId, dataService }: UseDrag
             }
 
             // 生成快捷方式名称
-            let shortcutName = pathInfo.file_name;
-            if (!isDirectory && shortcutName.includes('.')) {
+            let shortcutName = pathInfo.display_name ?? pathInfo.file_name;
+            if (!pathInfo.display_name && !isDirectory && shortcutName.includes('.')) {
               // 对于文件，移除扩展名
               shortcutName = shortcutName.replace(/\.[^/.]+$/, '');
             }
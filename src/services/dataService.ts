@@ -1,5 +1,6 @@
 import { invoke } from '@tauri-apps/api/core';
-import { Shortcut, Category, CreateShortcutRequest, UpdateShortcutRequest, CreateCategoryRequest, UpdateCategoryRequest, FileInfo, IconResult } from '../types';
+import { listen, UnlistenFn } from '@tauri-apps/api/event';
+import { Shortcut, Category, CreateShortcutRequest, UpdateShortcutRequest, CreateCategoryRequest, UpdateCategoryRequest, FileInfo, IconResult, IconSize, IconCrop, ShortcutIcon, CategoryColorSuggestion, ShortcutHealthEvent, HealthMonitorStatus, ShortcutPathEvent, PathSuggestion, CustomIconStatus, TargetWatcherStatus, RepairSuggestion, RepairFix, Fingerprint, TargetIntegrity, HotkeyStatus, IconBatchItem, IconBatchProgress, CacheStats, SearchResult, ShortcutSortMode, ProviderResult, ProviderAction, LaunchOutcome, ShortcutParameter } from '../types';
 
 // 获取invoke函数，优先使用导入的，如果不可用则使用全局的
 const getInvoke = () => {
@@ -37,9 +38,9 @@ export class DataService {
   }
 
   // 快捷方式相关操作
-  async getShortcuts(): Promise<Shortcut[]> {
+  async getShortcuts(sortMode?: ShortcutSortMode): Promise<Shortcut[]> {
     const invokeFunc = getInvoke();
-    return await invokeFunc('get_shortcuts');
+    return await invokeFunc('get_shortcuts', { sortMode });
   }
 
   async getShortcut(id: string): Promise<Shortcut> {
@@ -70,9 +71,14 @@ export class DataService {
     await invokeFunc('delete_shortcut', { id });
   }
 
-  async launchShortcut(id: string): Promise<void> {
+  async launchShortcut(id: string, input?: string, parameters?: Record<string, string>): Promise<LaunchOutcome> {
     const invokeFunc = getInvoke();
-    await invokeFunc('launch_shortcut', { id });
+    return await invokeFunc('launch_shortcut', { id, input, parameters });
+  }
+
+  async getShortcutParameters(id: string): Promise<ShortcutParameter[]> {
+    const invokeFunc = getInvoke();
+    return await invokeFunc('get_shortcut_parameters', { id });
   }
 
   // 分类相关操作
@@ -101,6 +107,12 @@ export class DataService {
     await invokeFunc('delete_category', { id });
   }
 
+  // 根据分类中的图标推荐分类颜色，没有可用图标时为 null
+  async suggestCategoryColor(categoryId: string): Promise<CategoryColorSuggestion | null> {
+    const invokeFunc = getInvoke();
+    return await invokeFunc('suggest_category_color', { categoryId });
+  }
+
   // 文件操作
   async validateFilePath(path: string): Promise<boolean> {
     console.log('[DataService] validateFilePath调用', { path });
@@ -154,11 +166,17 @@ export class DataService {
     }
   }
 
-  async getFileIcon(path: string, largeIcon: boolean = false): Promise<IconResult> {
-    console.log('[DataService] getFileIcon调用', { path, largeIcon });
+  async getFileIcon(path: string, largeIcon: boolean = false, iconSize?: IconSize, iconPath?: string): Promise<IconResult> {
+    console.log('[DataService] getFileIcon调用', { path, largeIcon, iconSize });
     const invokeFunc = getInvoke();
     try {
-      const result = await invokeFunc('get_file_icon_command', { filePath: path, large_icon: largeIcon });
+      const result = await invokeFunc('get_file_icon_command', {
+        filePath: path,
+        iconPath,
+        largeIcon,
+        size: iconSize?.size,
+        scale: iconSize?.scale ?? window.devicePixelRatio
+      });
       console.log('[DataService] getFileIcon结果', { path, result });
       return result;
     } catch (error) {
@@ -167,11 +185,17 @@ export class DataService {
     }
   }
 
-  async getDirectoryIcon(path: string, largeIcon: boolean = false): Promise<IconResult> {
-    console.log('[DataService] getDirectoryIcon调用', { path, largeIcon });
+  async getDirectoryIcon(path: string, largeIcon: boolean = false, iconSize?: IconSize, iconPath?: string): Promise<IconResult> {
+    console.log('[DataService] getDirectoryIcon调用', { path, largeIcon, iconSize });
     const invokeFunc = getInvoke();
     try {
-      const result = await invokeFunc('get_directory_icon_command', { directoryPath: path, large_icon: largeIcon });
+      const result = await invokeFunc('get_directory_icon_command', {
+        directoryPath: path,
+        iconPath,
+        largeIcon,
+        size: iconSize?.size,
+        scale: iconSize?.scale ?? window.devicePixelRatio
+      });
       console.log('[DataService] getDirectoryIcon结果', { path, result });
       return result;
     } catch (error) {
@@ -180,11 +204,48 @@ export class DataService {
     }
   }
 
-  async getIconsBatch(paths: string[], largeIcon: boolean = false): Promise<{ [path: string]: IconResult }> {
-    console.log('[DataService] getIconsBatch调用', { paths, largeIcon });
+  // 带状态角标的快捷方式图标，不传 ids 时返回全部快捷方式
+  async getShortcutIcons(ids?: string[], iconSize?: IconSize): Promise<ShortcutIcon[]> {
+    const invokeFunc = getInvoke();
+    return await invokeFunc('get_shortcut_icons', {
+      ids,
+      size: iconSize?.size,
+      scale: iconSize?.scale ?? window.devicePixelRatio
+    });
+  }
+
+  // 自定义图标：保存后 icon_path 指向存储的图标文件，getFileIcon 传入 iconPath 时优先使用
+  async setShortcutIconFromFile(id: string, imagePath: string, crop?: IconCrop): Promise<Shortcut> {
+    const invokeFunc = getInvoke();
+    return await invokeFunc('set_shortcut_icon_from_file', { id, imagePath, crop });
+  }
+
+  async setShortcutIconFromClipboard(id: string, crop?: IconCrop): Promise<Shortcut> {
+    const invokeFunc = getInvoke();
+    return await invokeFunc('set_shortcut_icon_from_clipboard', { id, crop });
+  }
+
+  async copyShortcutIcon(id: string, sourceId: string): Promise<Shortcut> {
+    const invokeFunc = getInvoke();
+    return await invokeFunc('copy_shortcut_icon', { id, sourceId });
+  }
+
+  async clearShortcutIcon(id: string): Promise<Shortcut> {
+    const invokeFunc = getInvoke();
+    return await invokeFunc('clear_shortcut_icon', { id });
+  }
+
+  async collectOrphanedIcons(): Promise<number> {
+    const invokeFunc = getInvoke();
+    return await invokeFunc('collect_orphaned_icons');
+  }
+
+  // 指定 sizes 时每个路径返回多种尺寸，用 size 区分；失败的项带有 error
+  async getIconsBatch(paths: string[], largeIcon: boolean = false, sizes?: IconSize[]): Promise<IconBatchItem[]> {
+    console.log('[DataService] getIconsBatch调用', { paths, largeIcon, sizes });
     const invokeFunc = getInvoke();
     try {
-      const result = await invokeFunc('get_icons_batch_command', { paths, large_icon: largeIcon });
+      const result = await invokeFunc('get_icons_batch_command', { filePaths: paths, largeIcon, sizes });
       console.log('[DataService] getIconsBatch结果', { paths, result });
       return result;
     } catch (error) {
@@ -193,6 +254,26 @@ export class DataService {
     }
   }
 
+  // 在后台批量提取图标，返回任务 ID；结果和进度通过 onIconBatchItem / onIconBatchProgress 接收
+  // 事件可能早于返回值到达，需要在调用前按 jobId 过滤事件时，先生成 ID 再传入
+  async startIconBatch(paths: string[], sizes?: IconSize[], jobId: string = crypto.randomUUID()): Promise<string> {
+    const invokeFunc = getInvoke();
+    return await invokeFunc('start_icon_batch', { jobId, filePaths: paths, largeIcon: true, sizes });
+  }
+
+  async cancelIconBatch(jobId: string): Promise<boolean> {
+    const invokeFunc = getInvoke();
+    return await invokeFunc('cancel_icon_batch', { jobId });
+  }
+
+  async onIconBatchItem(handler: (item: IconBatchItem) => void): Promise<UnlistenFn> {
+    return await listen<IconBatchItem>('icon-batch-item', (event) => handler(event.payload));
+  }
+
+  async onIconBatchProgress(handler: (progress: IconBatchProgress) => void): Promise<UnlistenFn> {
+    return await listen<IconBatchProgress>('icon-batch-progress', (event) => handler(event.payload));
+  }
+
   async checkFileExists(path: string): Promise<boolean> {
     console.log('[DataService] checkFileExists调用', { path });
     const invokeFunc = getInvoke();
@@ -206,20 +287,116 @@ export class DataService {
     }
   }
 
+  // 立即在后台检查全部快捷方式目标，状态变化通过 onShortcutBroken / onShortcutRecovered 接收
+  async checkShortcutTargets(): Promise<void> {
+    const invokeFunc = getInvoke();
+    await invokeFunc('check_shortcut_targets');
+  }
+
+  async getHealthMonitorStatus(): Promise<HealthMonitorStatus> {
+    const invokeFunc = getInvoke();
+    return await invokeFunc('get_health_monitor_status');
+  }
+
+  async onShortcutBroken(handler: (event: ShortcutHealthEvent) => void): Promise<UnlistenFn> {
+    return await listen<ShortcutHealthEvent>('shortcut-broken', (event) => handler(event.payload));
+  }
+
+  async onShortcutRecovered(handler: (event: ShortcutHealthEvent) => void): Promise<UnlistenFn> {
+    return await listen<ShortcutHealthEvent>('shortcut-recovered', (event) => handler(event.payload));
+  }
+
+  // 目标路径修复建议
+  async getPathSuggestions(): Promise<PathSuggestion[]> {
+    const invokeFunc = getInvoke();
+    return await invokeFunc('get_path_suggestions');
+  }
+
+  async applyPathSuggestion(suggestionId: string): Promise<Shortcut> {
+    const invokeFunc = getInvoke();
+    return await invokeFunc('apply_path_suggestion', { suggestionId });
+  }
+
+  async dismissPathSuggestion(suggestionId: string): Promise<boolean> {
+    const invokeFunc = getInvoke();
+    return await invokeFunc('dismiss_path_suggestion', { suggestionId });
+  }
+
+  async getCustomIconStatus(): Promise<CustomIconStatus> {
+    const invokeFunc = getInvoke();
+    return await invokeFunc('get_custom_icon_status');
+  }
+
+  async getTargetWatcherStatus(): Promise<TargetWatcherStatus> {
+    const invokeFunc = getInvoke();
+    return await invokeFunc('get_target_watcher_status');
+  }
+
+  async onShortcutPathUpdated(handler: (event: ShortcutPathEvent) => void): Promise<UnlistenFn> {
+    return await listen<ShortcutPathEvent>('shortcut-path-updated', (event) => handler(event.payload));
+  }
+
+  async onPathSuggested(handler: (suggestion: PathSuggestion) => void): Promise<UnlistenFn> {
+    return await listen<PathSuggestion>('shortcut-path-suggested', (event) => handler(event.payload));
+  }
+
+  // 快捷键
+  async getHotkeyStatus(): Promise<HotkeyStatus> {
+    const invokeFunc = getInvoke();
+    return await invokeFunc('get_hotkey_status');
+  }
+
+  // 返回规范写法，格式错误时抛出具体原因
+  async validateHotkey(accelerator: string): Promise<string> {
+    const invokeFunc = getInvoke();
+    return await invokeFunc('validate_hotkey', { accelerator });
+  }
+
+  async onHotkeyStatus(handler: (status: HotkeyStatus) => void): Promise<UnlistenFn> {
+    return await listen<HotkeyStatus>('hotkey-status', (event) => handler(event.payload));
+  }
+
+  async onGlobalHotkeyActivated(handler: () => void): Promise<UnlistenFn> {
+    return await listen('global-hotkey-activated', () => handler());
+  }
+
+  // 文件指纹和目标完整性检查
+  async getFileFingerprint(filePath: string, full?: boolean): Promise<Fingerprint> {
+    const invokeFunc = getInvoke();
+    return await invokeFunc('get_file_fingerprint', { filePath, full });
+  }
+
+  async checkTargetIntegrity(shortcutIds?: string[]): Promise<TargetIntegrity[]> {
+    const invokeFunc = getInvoke();
+    return await invokeFunc('check_target_integrity', { shortcutIds });
+  }
+
+  // 修复目标丢失的快捷方式
+  async suggestRepairs(shortcutIds?: string[]): Promise<RepairSuggestion[]> {
+    const invokeFunc = getInvoke();
+    return await invokeFunc('suggest_repairs', { shortcutIds });
+  }
+
+  async applyRepairs(fixes: RepairFix[]): Promise<Shortcut[]> {
+    const invokeFunc = getInvoke();
+    return await invokeFunc('apply_repairs', { fixes });
+  }
+
   // 缓存管理
   async clearIconCache(): Promise<string> {
     const invokeFunc = getInvoke();
     return await invokeFunc('clear_icon_cache');
   }
 
-  async getCacheStats(): Promise<any> {
+  async getCacheStats(): Promise<CacheStats> {
     const invokeFunc = getInvoke();
     return await invokeFunc('get_cache_stats');
   }
 
-  async preloadIcons(filePaths: string[]): Promise<string> {
+  // 后台预加载图标，立即返回
+  async preloadIcons(filePaths: string[], sizes?: IconSize[]): Promise<string> {
     const invokeFunc = getInvoke();
-    return await invokeFunc('preload_icons', { file_paths: filePaths });
+    return await invokeFunc('preload_icons', { filePaths, sizes });
   }
 
   // 打开文件选择对话框
@@ -229,9 +406,23 @@ export class DataService {
   }
 
   // 搜索和统计
-  async searchShortcuts(query: string): Promise<Shortcut[]> {
+  async searchShortcuts(query: string, limit?: number): Promise<SearchResult[]> {
+    const invokeFunc = getInvoke();
+    return await invokeFunc('search_shortcuts', { query, limit });
+  }
+
+  async unifiedSearch(query: string, limit?: number): Promise<ProviderResult[]> {
+    const invokeFunc = getInvoke();
+    return await invokeFunc('unified_search', { query, limit });
+  }
+
+  async executeProviderAction(action: ProviderAction): Promise<LaunchOutcome> {
+    if (action.type === 'copy_text') {
+      await navigator.clipboard.writeText(action.value);
+      return { launched: true, missing_parameters: [] };
+    }
     const invokeFunc = getInvoke();
-    return await invokeFunc('search_shortcuts', { query });
+    return await invokeFunc('execute_provider_action', { action });
   }
 
   async getRecentShortcuts(limit: number = 10): Promise<Shortcut[]> {
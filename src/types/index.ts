@@ -7,9 +7,16 @@ export interface Shortcut {
   category_id?: string;
   usage_count: number;
   last_used?: string;
+  frecency: number;
+  frecency_updated_at?: string;
   sort_order: number;
   is_active: boolean;
   file_exists: boolean;
+  missing_since?: string; // 后台检查发现目标不存在的时间
+  target_fingerprint?: string; // 目标文件的内容指纹，形如 sha256:<大小>:<摘要> 或 partial:<大小>:<摘要>
+  aliases: string[];
+  keywords: string[];
+  arguments?: string;
   created_at: string;
   updated_at: string;
 }
@@ -33,6 +40,9 @@ export interface CreateShortcutRequest {
   category_id?: string;
   icon_path?: string;
   sort_order?: number;
+  aliases?: string[];
+  keywords?: string[];
+  arguments?: string;
 }
 
 // 更新快捷方式请求
@@ -43,6 +53,68 @@ export interface UpdateShortcutRequest {
   icon_path?: string;
   sort_order?: number;
   is_active?: boolean;
+  aliases?: string[];
+  keywords?: string[];
+  arguments?: string;
+}
+
+// 别名冲突信息
+export interface AliasConflict {
+  alias: string;
+  shortcut_ids: string[];
+}
+
+// 快捷方式排序方式
+export type ShortcutSortMode = 'manual' | 'name' | 'frecency';
+
+// 搜索结果
+export interface SearchResult {
+  shortcut: Shortcut;
+  score: number;
+  matched_field: 'name' | 'alias' | 'keyword' | 'path' | 'none';
+  match_ranges: [number, number][];
+  matched_text?: string;
+  source: 'shortcut' | 'file';
+  is_saved: boolean;
+  parameter_input?: string;
+}
+
+// 快捷方式参数
+export interface ShortcutParameter {
+  name: string;
+  default_value?: string;
+}
+
+// 启动结果，缺少参数时 launched 为 false
+export interface LaunchOutcome {
+  launched: boolean;
+  missing_parameters: ShortcutParameter[];
+}
+
+// 搜索语法错误（位置按字符计）
+export interface QueryError {
+  message: string;
+  position: number;
+  length: number;
+}
+
+// 已安装应用
+export interface InstalledApp {
+  id: string;
+  name: string;
+  exec: string;
+  icon?: string;
+  categories: string[];
+  keywords: string[];
+  source: 'desktop' | 'flatpak' | 'snap' | 'start_menu' | 'app_bundle';
+  source_path: string;
+}
+
+// 已安装应用搜索结果
+export interface AppSearchResult {
+  app: InstalledApp;
+  score: number;
+  match_ranges: [number, number][];
 }
 
 // 创建分类请求
@@ -68,6 +140,7 @@ export interface FileInfo {
   is_file: boolean;
   is_directory: boolean;
   file_name: string;
+  display_name?: string;
   file_extension?: string;
   file_size?: number;
   modified_time?: string;
@@ -79,7 +152,166 @@ export interface IconResult {
   icon_data: string;
   icon_format: string;
   from_cache: boolean;
-  file_hash?: string;
+  file_hash?: string; // 文件指纹，形如 meta:<大小>:<摘要>；自定义图标为图标 ID
+  size: number; // 实际像素尺寸
+  colors?: IconColors; // 文字形式的默认图标没有颜色
+}
+
+// 图标的主色和强调色，以及在其上显示文字时对比度足够的颜色，均为 #RRGGBB
+export interface IconColors {
+  dominant: string;
+  accent: string;
+  dominant_text: string;
+  accent_text: string;
+}
+
+// 根据分类中快捷方式的图标推荐的分类颜色
+export interface CategoryColorSuggestion {
+  color: string;
+  text_color: string;
+  sample_count: number;
+}
+
+// 快捷方式目标失效或恢复时的事件
+export interface ShortcutHealthEvent {
+  shortcut_id: string;
+  name: string;
+  file_path: string;
+  file_exists: boolean;
+  missing_since?: string;
+}
+
+// 目标检查状态
+export interface HealthMonitorStatus {
+  interval: number;
+  last_check?: string;  // 最近一轮检查完成的时间
+  last_error?: string;  // 最近一轮检查失败的原因，成功后清除
+}
+
+// 目标被移动后自动更新了路径
+export interface ShortcutPathEvent {
+  shortcut_id: string;
+  old_path: string;
+  new_path: string;
+}
+
+// 目标被改名、移动或替换后等待确认的路径修复建议
+export interface PathSuggestion {
+  id: string;
+  shortcut_id: string;
+  shortcut_name: string;
+  old_path: string;
+  new_path: string;
+  reason: 'renamed' | 'moved' | 'replaced';
+  created_at: string;
+}
+
+// 自定义图标存储状态
+export interface CustomIconStatus {
+  open: boolean;
+  directory?: string;
+  last_error?: string; // 最近一次打开目录或清理图标的错误
+}
+
+// 目标监听状态
+export interface TargetWatcherStatus {
+  watching: boolean;
+  watched_directories: number;
+  pending_suggestions: number;
+  errors: string[];  // 最近的错误（通知出错、自动更新路径失败等）
+}
+
+// 文件指纹：meta 只包含路径、大小和修改时间；partial 为大文件的部分内容；sha256 为全部内容
+export interface Fingerprint {
+  kind: 'meta' | 'partial' | 'sha256';
+  size: number;
+  digest: string;
+}
+
+// 快捷方式目标与记录的内容指纹的比对结果
+export interface TargetIntegrity {
+  shortcut_id: string;
+  status: 'unchanged' | 'modified' | 'missing' | 'unrecorded';
+  recorded?: string;
+  current?: string;
+}
+
+// 目标丢失的快捷方式的一个可能的替代目标
+export interface RepairCandidate {
+  path: string;
+  confidence: number;  // 0 到 1
+  sources: Array<'versioned' | 'sibling' | 'path' | 'app_catalog' | 'fingerprint'>;
+}
+
+export interface RepairSuggestion {
+  shortcut_id: string;
+  shortcut_name: string;
+  old_path: string;
+  candidates: RepairCandidate[];
+}
+
+export interface RepairFix {
+  shortcut_id: string;
+  new_path: string;
+}
+
+// 带状态角标的快捷方式图标
+export interface ShortcutIcon {
+  shortcut_id: string;
+  icon?: IconResult;
+  badges: string[]; // missing / link / running / category
+  error?: string;
+}
+
+// 批量提取图标中一个文件、一种尺寸的结果，失败时 error 为原因
+export interface IconBatchItem {
+  job_id?: string; // 同步调用时为空
+  file_path: string;
+  size: number;
+  icon?: IconResult;
+  error?: string;
+}
+
+// 批量提取图标的进度
+export interface IconBatchProgress {
+  job_id: string;
+  total: number;
+  completed: number; // 已处理的数量，包含失败
+  failed: number;
+  finished: boolean;
+  cancelled: boolean;
+}
+
+// 图标缓存统计
+export interface CacheStats {
+  total_entries: number;
+  memory_bytes: number;
+  max_memory_bytes: number;
+  cache_duration: number;
+  disk_entries: number;
+  disk_bytes: number;
+  max_disk_bytes: number;
+  hits: number; // 内存命中
+  disk_hits: number;
+  misses: number; // 两级缓存都未命中，需要提取
+  evictions: number; // 内存缓存因容量淘汰的条目数
+  disk_evictions: number;
+  disk_error?: string; // 磁盘缓存无法打开的原因
+  hit_rate: number;
+  average_extraction_ms: number;
+}
+
+// 图标尺寸请求：逻辑尺寸 × 缩放倍数 = 输出的像素尺寸
+export interface IconSize {
+  size: number;
+  scale?: number;
+}
+
+// 自定义图标的裁剪区域，单位为原图像素
+export interface IconCrop {
+  x: number;
+  y: number;
+  size: number; // 正方形边长
 }
 
 // 应用配置
@@ -88,6 +320,8 @@ export interface AppConfig {
   behavior: BehaviorConfig;
   hotkeys: HotkeyConfig;
   advanced: AdvancedConfig;
+  indexer: IndexerConfig;
+  search: SearchConfig;
 }
 
 export interface UiConfig {
@@ -97,6 +331,15 @@ export interface UiConfig {
   theme: string;
   icon_size: number;
   show_labels: boolean;
+  badges: BadgeConfig;
+}
+
+// 图标上叠加的状态角标，各项可单独关闭
+export interface BadgeConfig {
+  missing_target: boolean;
+  link_arrow: boolean;
+  running_indicator: boolean;
+  category_tag: boolean;
 }
 
 export interface BehaviorConfig {
@@ -111,11 +354,89 @@ export interface HotkeyConfig {
   quick_search: string;
 }
 
+// 快捷键的注册状态，normalized 为解析后的规范写法，格式错误时为空
+export interface HotkeyState {
+  accelerator: string;
+  normalized?: string;
+  registered: boolean;
+  error?: string;
+}
+
+export interface HotkeyStatus {
+  global_hotkey: HotkeyState;
+  quick_search: HotkeyState;
+}
+
 export interface AdvancedConfig {
   max_recent_items: number;
   file_check_interval: number;
   backup_enabled: boolean;
   log_level: string;
+  icon_cache_memory_mb: number; // 内存图标缓存容量
+  icon_cache_disk_mb: number; // 磁盘图标缓存容量
+}
+
+// 统一搜索配置
+export interface SearchConfig {
+  providers: ProviderSettings[];
+  web_search_engines: WebSearchEngine[];
+}
+
+export interface ProviderSettings {
+  id: string;
+  enabled: boolean;
+  timeout_ms: number;
+  weight: number;
+}
+
+export interface WebSearchEngine {
+  name: string;
+  keyword: string;
+  url: string;
+  fallback: boolean;
+}
+
+// 搜索提供者返回的结果
+export interface ProviderResult {
+  provider_id: string;
+  id: string;
+  title: string;
+  subtitle?: string;
+  icon?: string;
+  score: number;
+  match_ranges: [number, number][];
+  action: ProviderAction;
+}
+
+export type ProviderAction =
+  | { type: 'launch_shortcut'; value: { id: string; input?: string } }
+  | { type: 'open_path'; value: string }
+  | { type: 'open_url'; value: string }
+  | { type: 'copy_text'; value: string };
+
+// 文件索引配置
+export interface IndexerConfig {
+  enabled: boolean;
+  roots: IndexRoot[];
+  max_results: number;
+}
+
+export interface IndexRoot {
+  path: string;
+  include: string[];
+  exclude: string[];
+  max_depth?: number;
+  respect_gitignore: boolean;
+}
+
+// 文件索引状态
+export interface IndexerStatus {
+  enabled: boolean;
+  indexing: boolean;
+  total_files: number;
+  roots: number;
+  watching: boolean;
+  errors: string[];  // 最近的错误（无效的规则、无法监听的目录等）
 }
 
 // 应用数据